@@ -0,0 +1,441 @@
+use crate::{
+    config, dialog, download, export, locale, server, sources, storage,
+    tracker::{self, Tracker as _},
+    watch,
+};
+
+use std::{
+    cell::RefCell,
+    io::{self, Write as _},
+    path::Path,
+    process::ExitCode,
+    rc::Rc,
+};
+
+/// Run the front-end: parse the arguments, load the config and run the command or the dialog
+/// # Arguments
+/// * `args` - Command line arguments without the name of the binary
+/// # Returns
+/// `2` if the arguments or the config are invalid, `1` if the command failed
+pub async fn run<I>(args: I) -> ExitCode
+where
+    I: Iterator<Item = String>,
+{
+    let args = match config::Args::parse(args) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.help {
+        print!("{}", config::USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let command = args.command;
+    if command == Some(config::Command::ClearCache) {
+        match sources::cache::Cache::load().clear() {
+            Ok(removed) => println!("{}", locale::Message::CacheCleared(removed)),
+            Err(err) => {
+                eprintln!("{}", locale::Message::CantClearCache(&err));
+                return ExitCode::from(1);
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let config = match config::Config::load(args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let http = match sources::http::Http::new(config.http()) {
+        Ok(http) => http,
+        Err(err) => {
+            eprintln!("{}", locale::Message::CantCreateHttpClient(&err));
+            return ExitCode::from(2);
+        }
+    };
+
+    let accounts = match storage::accounts::Accounts::load() {
+        Ok(accounts) => accounts,
+        // The linked account or the session wouldn't be saved
+        Err(err)
+            if command == Some(config::Command::ShikimoriLogin)
+                || command == Some(config::Command::AnilibriaLogin) =>
+        {
+            eprintln!("{}", locale::Message::CantLoadAccounts(&err));
+            return ExitCode::from(1);
+        }
+        Err(err) => {
+            eprintln!("{}", locale::Message::CantLoadAccounts(&err));
+            storage::accounts::Accounts::new(None)
+        }
+    };
+    let accounts = Rc::new(RefCell::new(accounts));
+    let shikimori = tracker::shikimori::Shikimori::new(
+        config.shikimori().clone(),
+        http.clone(),
+        Rc::clone(&accounts),
+    );
+
+    if command == Some(config::Command::ShikimoriLogin) {
+        let url = match shikimori.authorize_url() {
+            Ok(url) => url,
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::from(1);
+            }
+        };
+        println!("{}", locale::Message::AuthorizeUrl(&url));
+        print!("{}: ", locale::Message::EnterAuthorizationCode);
+        let _ = io::stdout().flush();
+
+        let mut code = String::new();
+        if io::stdin().read_line(&mut code).is_err() || code.trim().is_empty() {
+            return ExitCode::from(1);
+        }
+
+        match shikimori.link(&code).await {
+            Ok(nickname) => println!(
+                "{}",
+                locale::Message::AccountLinked(shikimori.name(), &nickname)
+            ),
+            Err(err) => {
+                eprintln!("{}", locale::Message::CantLinkAccount(&err));
+                return ExitCode::from(1);
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if command == Some(config::Command::ShikimoriLogout) {
+        match shikimori.unlink() {
+            Ok(true) => println!("{}", locale::Message::AccountUnlinked(shikimori.name())),
+            Ok(false) => println!(
+                "{}",
+                locale::Message::TrackerNotLinked(tracker::shikimori::SERVICE)
+            ),
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::from(1);
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let stream_check = if config.check_stream() {
+        sources::stream::StreamCheck::new(http.clone())
+    } else {
+        sources::stream::StreamCheck::disabled()
+    };
+
+    // The relay is served from the line-by-line dialog
+    let relay = (command == Some(config::Command::ServeStream))
+        .then(|| server::relay::Relay::new(http.clone(), config.relay()));
+
+    let mut anilibria = sources::ru::anilibria::Anilibria::new(http.clone())
+        .with_cache(config.cache().clone())
+        .with_accounts(Rc::clone(&accounts));
+
+    if matches!(
+        command,
+        Some(
+            config::Command::AnilibriaLogin
+                | config::Command::AnilibriaLogout
+                | config::Command::AnilibriaFavorites
+        )
+    ) {
+        let mut terminal = dialog::cli::Terminal::stdio(config.color().color_choice())
+            .with_theme(config.theme().clone())
+            .with_picker(config.picker());
+
+        match command {
+            Some(config::Command::AnilibriaLogin) => {
+                if !dialog::cli::login(&mut terminal, &anilibria).await {
+                    return ExitCode::from(1);
+                }
+            }
+            Some(config::Command::AnilibriaLogout) => match anilibria.logout() {
+                Ok(true) => println!("{}", locale::Message::LoggedOut(&anilibria.to_string())),
+                Ok(false) => println!("{}", locale::Message::NotLoggedIn("anilibria")),
+                Err(err) => {
+                    eprintln!("{err}");
+                    return ExitCode::from(1);
+                }
+            },
+            _ => {
+                if !anilibria.is_logged_in() {
+                    eprintln!("{}", locale::Message::NotLoggedIn("anilibria"));
+                    return ExitCode::from(1);
+                }
+                dialog::cli::favorites(&mut terminal, &mut anilibria).await;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut sources = vec![sources::any::AnySource::Anilibria(anilibria)];
+    sources.extend(config.sources().iter().map(|spec| {
+        sources::any::AnySource::Rest(
+            sources::rest::Rest::new(spec.clone(), http.clone()).with_cache(config.cache().clone()),
+        )
+    }));
+    sources.extend(config.html_sources().iter().map(|spec| {
+        sources::any::AnySource::Html(sources::scrape::HtmlSource::new(spec.clone(), http.clone()))
+    }));
+    if let Some(dir) = config.plugins_dir() {
+        sources.extend(
+            load_plugins(dir)
+                .await
+                .into_iter()
+                .map(sources::any::AnySource::Plugin),
+        );
+    }
+
+    let mut history = match storage::history::History::load() {
+        Ok(history) => history,
+        Err(err) => {
+            eprintln!("{}", locale::Message::CantLoadHistory(&err));
+            storage::history::History::new(None)
+        }
+    };
+
+    let mut downloads = match storage::downloads::Downloads::load() {
+        Ok(downloads) => downloads,
+        // Sync would download episodes again and couldn't remove old ones
+        Err(err) if command == Some(config::Command::Sync) => {
+            eprintln!("{}", locale::Message::CantLoadDownloads(&err));
+            return ExitCode::from(1);
+        }
+        Err(err) => {
+            eprintln!("{}", locale::Message::CantLoadDownloads(&err));
+            storage::downloads::Downloads::new(None)
+        }
+    };
+
+    if command == Some(config::Command::Sync) {
+        let sync = config.sync();
+        if sync.rules.is_empty() {
+            eprintln!("{}", locale::Message::NoSyncRules);
+            return ExitCode::from(1);
+        }
+
+        let mut downloader = download::Downloader::new(http);
+        if let Some(bandwidth) = sync.bandwidth {
+            downloader = downloader.with_bandwidth(bandwidth);
+        }
+
+        let report = crate::sync::run(&sources, sync, &downloader, &mut downloads).await;
+        println!(
+            "{}",
+            locale::Message::SyncFinished(report.downloaded, report.failed, report.removed)
+        );
+        if report.failed > 0 {
+            return ExitCode::from(1);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if command == Some(config::Command::ExportLibrary) {
+        let export = config.export();
+        let Some(dir) = &export.dir else {
+            eprintln!("{}", locale::Message::NoExportDir);
+            return ExitCode::from(1);
+        };
+
+        let watchlist = match storage::watchlist::Watchlist::load() {
+            Ok(watchlist) => watchlist,
+            Err(err) => {
+                eprintln!("{}", locale::Message::CantLoadWatchlist(&err));
+                return ExitCode::from(1);
+            }
+        };
+        if watchlist.entries().is_empty() {
+            eprintln!("{}", locale::Message::EmptyWatchlist);
+            return ExitCode::from(1);
+        }
+
+        let report = export::run(
+            &sources,
+            watchlist.entries(),
+            &downloads,
+            dir,
+            export.quality.as_deref(),
+        )
+        .await;
+        println!(
+            "{}",
+            locale::Message::ExportFinished(report.titles, report.episodes, report.failed)
+        );
+        if report.failed > 0 {
+            return ExitCode::from(1);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if command == Some(config::Command::ShikimoriImport) {
+        let mut watchlist = match storage::watchlist::Watchlist::load() {
+            Ok(watchlist) => watchlist,
+            Err(err) => {
+                eprintln!("{}", locale::Message::CantLoadWatchlist(&err));
+                return ExitCode::from(1);
+            }
+        };
+
+        match tracker::import::run(&shikimori, &sources, &mut watchlist).await {
+            Ok(report) => println!(
+                "{}",
+                locale::Message::ImportFinished(report.added, report.known, report.not_found)
+            ),
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::from(1);
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    // Progress is tracked only if the account is linked
+    let tracker = shikimori.nickname().is_some().then_some(&shikimori);
+
+    if command == Some(config::Command::Watch) {
+        let seen = match storage::seen::SeenEpisodes::load() {
+            Ok(seen) => seen,
+            Err(err) => {
+                eprintln!("{}", locale::Message::CantLoadSeenEpisodes(&err));
+                return ExitCode::from(1);
+            }
+        };
+
+        let watch = config.watch();
+        let mut watcher = watch::Watcher::new(&sources, seen, watch::clock::SystemClock)
+            .with_hooks(watch.hooks.clone(), http);
+
+        println!(
+            "{}",
+            locale::Message::WatchStarted(&watch.interval.as_secs().div_ceil(60))
+        );
+        let on_report = |report: watch::Report| {
+            for new in &report.new_episodes {
+                println!("{}", new.message());
+            }
+            for err in &report.errors {
+                eprintln!("{err}");
+            }
+        };
+        let stop = async {
+            let _ = tokio::signal::ctrl_c().await;
+        };
+        watcher
+            .run(
+                watch.interval,
+                storage::watchlist::Watchlist::load,
+                on_report,
+                stop,
+            )
+            .await;
+        return ExitCode::SUCCESS;
+    }
+
+    if command == Some(config::Command::Serve) {
+        let watchlist = match storage::watchlist::Watchlist::load() {
+            Ok(watchlist) => watchlist,
+            Err(err) => {
+                eprintln!("{}", locale::Message::CantLoadWatchlist(&err));
+                storage::watchlist::Watchlist::new(None)
+            }
+        };
+
+        let api = server::api::ApiServer::new(sources.clone(), stream_check, history, watchlist);
+        let serving = match api.bind(config.api_bind()) {
+            Ok(serving) => serving,
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::from(1);
+            }
+        };
+
+        for url in serving.urls() {
+            println!("{}", locale::Message::ApiUrl(&url));
+        }
+        println!("{}", locale::Message::ApiHint);
+
+        let stop = async {
+            let _ = tokio::signal::ctrl_c().await;
+        };
+        if let Err(err) = serving.run(stop).await {
+            eprintln!("{err}");
+            return ExitCode::from(1);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    // The library has no full-screen interface yet
+    let library = command == Some(config::Command::Library);
+    if config.tui() && relay.is_none() && !library {
+        if let Err(err) = dialog::tui::run(
+            &sources,
+            &mut history,
+            &downloads,
+            tracker,
+            &stream_check,
+            config.favorites(),
+        )
+        .await
+        {
+            eprintln!("{err}");
+            return ExitCode::from(1);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut terminal = dialog::cli::Terminal::stdio(config.color().color_choice())
+        .with_theme(config.theme().clone())
+        .with_picker(config.picker());
+
+    if library {
+        dialog::cli::library(&mut terminal, &mut downloads, &mut history);
+        return ExitCode::SUCCESS;
+    }
+
+    dialog::cli::run(
+        &sources,
+        &mut terminal,
+        &mut history,
+        &downloads,
+        tracker,
+        &stream_check,
+        relay.as_ref(),
+        config.favorites(),
+    )
+    .await;
+
+    ExitCode::SUCCESS
+}
+
+/// Start plugins of the directory, plugins that can't be started are skipped with a warning
+async fn load_plugins(dir: &Path) -> Vec<sources::plugin::Plugin> {
+    let paths = match sources::plugin::find(dir) {
+        Ok(paths) => paths,
+        Err(err) => {
+            eprintln!("{}", locale::Message::CantReadPlugins(&dir.display(), &err));
+            return Vec::new();
+        }
+    };
+
+    let mut plugins = Vec::new();
+    for path in paths {
+        match sources::plugin::Plugin::spawn(&path, sources::plugin::TIMEOUT).await {
+            Ok(plugin) => plugins.push(plugin),
+            Err(err) => eprintln!("{}", locale::Message::CantLoadPlugin(&path.display(), &err)),
+        }
+    }
+
+    plugins
+}
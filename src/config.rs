@@ -0,0 +1,306 @@
+mod args;
+mod export;
+mod file;
+pub(crate) mod html;
+mod http;
+mod relay;
+mod rest;
+mod shikimori;
+mod sync;
+mod watch;
+
+pub use args::{Args, Command, USAGE};
+
+use args::parse_picker;
+
+use crate::{
+    dialog::cli::Theme,
+    enums::{color_mode::ColorMode, locale::Locale, picker::Picker},
+    errors::ConfigError,
+    export::ExportConfig,
+    locale::{self, Message},
+    server::relay::RelayConfig,
+    sources::{cache::Cache, http::HttpConfig, plugin, rest::RestSpec, scrape::HtmlSpec},
+    sync::SyncConfig,
+    tracker::shikimori::ShikimoriConfig,
+    watch::WatchConfig,
+};
+
+use serde_json::Value;
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Settings of the program. \
+/// Values are taken from the command line arguments, then from the config file, then defaults.
+pub struct Config {
+    tui: bool,
+    favorites: bool,
+    picker: Option<Picker>,
+    color: ColorMode,
+    theme: Theme,
+    cache: Cache,
+    http: HttpConfig,
+    check_stream: bool,
+    relay: RelayConfig,
+    api_bind: SocketAddr,
+    plugins_dir: Option<PathBuf>,
+    sources: Vec<RestSpec>,
+    html_sources: Vec<HtmlSpec>,
+    watch: WatchConfig,
+    sync: SyncConfig,
+    export: ExportConfig,
+    shikimori: ShikimoriConfig,
+}
+
+impl Config {
+    /// Load the config file and apply the arguments over it. \
+    /// The locale of the user interface is set as soon as it's known,
+    /// so errors of the config are displayed in it
+    pub fn load(args: Args) -> Result<Self, ConfigError> {
+        let file = match &args.config_path {
+            Some(path) => match file::read(path)? {
+                Some(file) => file,
+                None => {
+                    locale::set(args.locale.or_else(Locale::from_env).unwrap_or_default());
+                    return Err(ConfigError::ReadError(
+                        Message::ConfigNotFound(&path.display()).to_string(),
+                    ));
+                }
+            },
+            None => match file::default_path() {
+                Some(path) => file::read(&path)?.unwrap_or(Value::Null),
+                None => Value::Null,
+            },
+        };
+
+        // The locale of the environment is used only if it isn't set explicitly
+        let locale = match args.locale {
+            Some(locale) => locale,
+            None => match file["locale"].as_str() {
+                Some(locale) => Locale::try_from(locale)?,
+                None => Locale::from_env().unwrap_or_default(),
+            },
+        };
+        locale::set(locale);
+
+        let tui = args.tui || file["tui"].as_bool().unwrap_or_default();
+
+        let picker = match args.picker {
+            Some(picker) => picker,
+            None => match file["picker"].as_str() {
+                Some(picker) => parse_picker(picker)?,
+                None => None,
+            },
+        };
+
+        let color = match args.color {
+            Some(color) => color,
+            None => match file["color"].as_str() {
+                Some(color) => ColorMode::try_from(color)?,
+                None => ColorMode::default(),
+            },
+        };
+
+        let mut theme = match args.theme.as_deref().or(file["theme"].as_str()) {
+            Some(name) => Theme::by_name(name)?,
+            None => Theme::default(),
+        };
+        // Styles of the config file override the styles of the selected theme,
+        // e.g. `"styles": { "warning": "bold yellow" }`
+        if let Some(styles) = file["styles"].as_object() {
+            for (name, style) in styles {
+                let style = style.as_str().ok_or_else(|| {
+                    ConfigError::ParseError(Message::StyleNotString(name).to_string())
+                })?;
+
+                theme.set_style(name, style)?;
+            }
+        }
+
+        // e.g. `"cache": { "enabled": true, "ttl": { "anilibria/searchTitles": 600 } }`
+        let cache = if args.no_cache || file["cache"]["enabled"] == Value::Bool(false) {
+            Cache::new(None)
+        } else {
+            let mut cache = Cache::load();
+            if let Some(ttls) = file["cache"]["ttl"].as_object() {
+                for (endpoint, ttl) in ttls {
+                    let ttl = ttl.as_u64().ok_or_else(|| {
+                        ConfigError::ParseError(Message::InvalidTtl(endpoint).to_string())
+                    })?;
+
+                    cache = cache.with_ttl(endpoint, Duration::from_secs(ttl));
+                }
+            }
+            cache
+        };
+
+        let mut http = http::parse(&file["http"])?;
+        if let Some(proxy) = args.proxy {
+            http.proxy = Some(proxy);
+        }
+        if let Some(timeout) = args.timeout {
+            http.read_timeout = timeout;
+        }
+
+        let mut relay = relay::parse(&file["relay"])?;
+        let mut api_bind = match file["api"]["bind"].as_str() {
+            Some(bind) => relay::parse_bind(bind)?,
+            None => SocketAddr::from((Ipv4Addr::LOCALHOST, 8788)),
+        };
+        // `--bind` is the address of the server of the command
+        if let Some(bind) = args.bind {
+            relay.bind = bind;
+            api_bind = bind;
+        }
+        if let Some(idle_timeout) = args.idle_timeout {
+            relay.idle_timeout = idle_timeout;
+        }
+        relay.qr |= args.qr;
+
+        let mut sync = sync::parse(&file["sync"])?;
+        if let Some(bandwidth) = args.bandwidth {
+            sync.bandwidth = Some(bandwidth);
+        }
+
+        let mut export = export::parse(&file["export"])?;
+        if let Some(dir) = args.output {
+            export.dir = Some(dir);
+        }
+
+        let mut watch = watch::parse(&file["watch"])?;
+        if let Some(interval) = args.interval {
+            if interval.is_zero() {
+                return Err(ConfigError::InvalidArgument(
+                    Message::InvalidInterval.to_string(),
+                ));
+            }
+            watch.interval = interval;
+        }
+
+        Ok(Self {
+            tui,
+            favorites: args.favorites,
+            picker,
+            color,
+            theme,
+            cache,
+            http,
+            check_stream: !args.no_stream_check && file["check_stream"].as_bool().unwrap_or(true),
+            relay,
+            api_bind,
+            plugins_dir: match file["plugins_dir"].as_str() {
+                Some(dir) => Some(PathBuf::from(dir)),
+                None => plugin::default_dir(),
+            },
+            sources: rest::parse(&file["sources"])?,
+            html_sources: html::parse(&file["html_sources"])?,
+            watch,
+            sync,
+            export,
+            shikimori: shikimori::parse(&file["shikimori"])?,
+        })
+    }
+
+    /// Whether the full-screen interface should be used instead of the line-by-line dialog
+    #[must_use]
+    pub fn tui(&self) -> bool {
+        self.tui
+    }
+
+    /// Whether anime are selected from favorites of the logged in accounts instead of searching by name
+    #[must_use]
+    pub fn favorites(&self) -> bool {
+        self.favorites
+    }
+
+    /// External picker to select variants in the line-by-line dialog
+    #[must_use]
+    pub fn picker(&self) -> Option<Picker> {
+        self.picker
+    }
+
+    #[must_use]
+    pub fn color(&self) -> ColorMode {
+        self.color
+    }
+
+    #[must_use]
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Cache of API responses, disabled by `--no-cache`
+    #[must_use]
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Settings of the HTTP client used by sources
+    #[must_use]
+    pub fn http(&self) -> &HttpConfig {
+        &self.http
+    }
+
+    /// Whether streams should be checked before they are passed to a player
+    #[must_use]
+    pub fn check_stream(&self) -> bool {
+        self.check_stream
+    }
+
+    /// Settings of the relay of `serve-stream`
+    #[must_use]
+    pub fn relay(&self) -> &RelayConfig {
+        &self.relay
+    }
+
+    /// Address of the API of `serve`
+    #[must_use]
+    pub fn api_bind(&self) -> SocketAddr {
+        self.api_bind
+    }
+
+    /// Directory of source plugins, `None` if there is no user data directory
+    #[must_use]
+    pub fn plugins_dir(&self) -> Option<&Path> {
+        self.plugins_dir.as_deref()
+    }
+
+    /// Sources defined in the config file
+    #[must_use]
+    pub fn sources(&self) -> &[RestSpec] {
+        &self.sources
+    }
+
+    /// Sources scraping HTML sites defined in the config file
+    #[must_use]
+    pub fn html_sources(&self) -> &[HtmlSpec] {
+        &self.html_sources
+    }
+
+    /// Settings of the new episode watcher of `watch`
+    #[must_use]
+    pub fn watch(&self) -> &WatchConfig {
+        &self.watch
+    }
+
+    /// Settings of auto-downloads of `sync`
+    #[must_use]
+    pub fn sync(&self) -> &SyncConfig {
+        &self.sync
+    }
+
+    /// Settings of `export-library`
+    #[must_use]
+    pub fn export(&self) -> &ExportConfig {
+        &self.export
+    }
+
+    /// Settings of the Shikimori tracker
+    #[must_use]
+    pub fn shikimori(&self) -> &ShikimoriConfig {
+        &self.shikimori
+    }
+}
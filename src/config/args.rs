@@ -0,0 +1,243 @@
+use crate::{
+    enums::{color_mode::ColorMode, locale::Locale, picker::Picker},
+    errors::ConfigError,
+    locale::Message,
+};
+
+use super::relay::parse_bind;
+
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+pub const USAGE: &str = "\
+Usage: ani_cli_rs [OPTIONS] [COMMAND]
+
+Commands:
+    cache clear         Remove cached API responses
+    serve-stream        Relay the selected episode over HTTP to play it on another device
+    serve               Serve a JSON API over the sources for other front-ends
+    watch               Check the watchlist for new episodes and notify about them
+    sync                Download new episodes by the rules of the config file
+    library             Play, delete or verify downloaded episodes
+    export-library      Export the watchlist as a Kodi or Jellyfin library of .strm and NFO files
+    shikimori login     Link a Shikimori account to update the progress of watched episodes
+    shikimori logout    Unlink the Shikimori account
+    shikimori import    Add anime of the Shikimori watching list to the watchlist
+    anilibria login     Log in to an Anilibria account to use its favorites
+    anilibria logout    Forget the Anilibria session
+    anilibria favorites Browse, add and remove Anilibria favorites
+
+Options:
+    --tui               Use the full-screen interface
+    --favorites         Select anime from favorites of the logged in accounts instead of searching
+    --picker <NAME>     Select variants with an external picker: fzf, skim, none
+    --color <WHEN>      When to use colors: auto, always, never [default: auto]
+    --theme <NAME>      Palette of the output: default, light, monochrome
+    --locale <NAME>     Language of the interface: en, ru [default: from LANG]
+    --config <PATH>     Path to the config file
+    --no-cache          Don't use cached API responses
+    --proxy <URL>       HTTP, HTTPS or SOCKS5 proxy, e.g. socks5://127.0.0.1:9050
+    --timeout <SECS>    Time to wait for a response of the API [default: 30]
+    --no-stream-check   Don't check streams before they are passed to a player
+    --bind <ADDR>       Address of the relay or the API
+                        [default: 0.0.0.0:8787 for serve-stream, 127.0.0.1:8788 for serve]
+    --idle-timeout <SECS>
+                        Stop the relay after this time without requests [default: 600]
+    --qr                Show a QR code of the relay URL
+    --interval <SECS>   Time between checks of the watchlist [default: 3600]
+    --bandwidth <KB/S>  Limit of the total download speed of sync
+    --output <DIR>      Directory of export-library [default: ~/Videos/Anime library]
+    -h, --help          Print help
+";
+
+/// Command to run instead of the dialog
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Command {
+    ClearCache,
+    ServeStream,
+    Serve,
+    Watch,
+    Sync,
+    Library,
+    ExportLibrary,
+    ShikimoriLogin,
+    ShikimoriLogout,
+    ShikimoriImport,
+    AnilibriaLogin,
+    AnilibriaLogout,
+    AnilibriaFavorites,
+}
+
+/// Command line arguments. \
+/// Options that aren't set override nothing in the config file.
+#[derive(Default)]
+pub struct Args {
+    pub command: Option<Command>,
+    pub help: bool,
+    pub tui: bool,
+    pub favorites: bool,
+    /// `Some(None)` if the picker is disabled explicitly
+    pub picker: Option<Option<Picker>>,
+    pub config_path: Option<PathBuf>,
+    pub color: Option<ColorMode>,
+    pub theme: Option<String>,
+    pub locale: Option<Locale>,
+    pub no_cache: bool,
+    pub proxy: Option<String>,
+    pub timeout: Option<Duration>,
+    pub no_stream_check: bool,
+    pub bind: Option<SocketAddr>,
+    pub idle_timeout: Option<Duration>,
+    pub qr: bool,
+    pub interval: Option<Duration>,
+    /// Limit of the download speed in bytes per second
+    pub bandwidth: Option<u64>,
+    /// Directory of the exported library
+    pub output: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parse arguments without the program name
+    /// # Arguments
+    /// * `args` - Arguments in `--option value` or `--option=value` form
+    pub fn parse<I>(args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        let mut words = Vec::new();
+
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+
+            let mut value = || {
+                inline_value.clone().or_else(|| args.next()).ok_or_else(|| {
+                    ConfigError::InvalidArgument(format!("Option `{name}` requires a value"))
+                })
+            };
+
+            match name.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--tui" => parsed.tui = true,
+                "--favorites" => parsed.favorites = true,
+                "--picker" => parsed.picker = Some(parse_picker(&value()?)?),
+                "--config" => parsed.config_path = Some(PathBuf::from(value()?)),
+                "--color" | "--colour" => {
+                    parsed.color = Some(ColorMode::try_from(value()?.as_str())?);
+                }
+                "--theme" => parsed.theme = Some(value()?),
+                "--no-cache" => parsed.no_cache = true,
+                "--proxy" => parsed.proxy = Some(value()?),
+                "--no-stream-check" => parsed.no_stream_check = true,
+                "--timeout" => parsed.timeout = Some(parse_secs(&value()?)?),
+                "--bind" => parsed.bind = Some(parse_bind(&value()?)?),
+                "--idle-timeout" => parsed.idle_timeout = Some(parse_secs(&value()?)?),
+                "--qr" => parsed.qr = true,
+                "--interval" => parsed.interval = Some(parse_secs(&value()?)?),
+                "--bandwidth" => parsed.bandwidth = Some(parse_bandwidth(&value()?)?),
+                "--output" | "-o" => parsed.output = Some(PathBuf::from(value()?)),
+                "--locale" | "--lang" => {
+                    parsed.locale = Some(Locale::try_from(value()?.as_str())?);
+                }
+                _ if !name.starts_with('-') && inline_value.is_none() => words.push(name),
+                _ => {
+                    return Err(ConfigError::InvalidArgument(format!(
+                        "Unknown argument `{name}`\n\n{USAGE}"
+                    )))
+                }
+            }
+        }
+
+        parsed.command = match words
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            [] => None,
+            ["cache", "clear"] => Some(Command::ClearCache),
+            ["serve-stream"] => Some(Command::ServeStream),
+            ["serve"] => Some(Command::Serve),
+            ["watch"] => Some(Command::Watch),
+            ["sync"] => Some(Command::Sync),
+            ["library"] => Some(Command::Library),
+            ["export-library"] => Some(Command::ExportLibrary),
+            ["shikimori", "login"] => Some(Command::ShikimoriLogin),
+            ["shikimori", "logout"] => Some(Command::ShikimoriLogout),
+            ["shikimori", "import"] => Some(Command::ShikimoriImport),
+            ["anilibria", "login"] => Some(Command::AnilibriaLogin),
+            ["anilibria", "logout"] => Some(Command::AnilibriaLogout),
+            ["anilibria", "favorites"] => Some(Command::AnilibriaFavorites),
+            words => {
+                return Err(ConfigError::InvalidArgument(format!(
+                    "Unknown command `{}`\n\n{USAGE}",
+                    words.join(" ")
+                )))
+            }
+        };
+
+        Ok(parsed)
+    }
+}
+
+fn parse_secs(secs: &str) -> Result<Duration, ConfigError> {
+    secs.parse().map(Duration::from_secs).map_err(|_| {
+        ConfigError::InvalidArgument(format!("Expected a number of seconds, got `{secs}`"))
+    })
+}
+
+/// Parse a speed in kilobytes per second
+/// # Returns
+/// The speed in bytes per second
+pub fn parse_bandwidth(kilobytes: &str) -> Result<u64, ConfigError> {
+    kilobytes
+        .parse::<u64>()
+        .ok()
+        .filter(|kilobytes| *kilobytes > 0)
+        .and_then(|kilobytes| kilobytes.checked_mul(1024))
+        .ok_or_else(|| {
+            ConfigError::InvalidArgument(Message::InvalidBandwidth(kilobytes).to_string())
+        })
+}
+
+/// Parse a picker name, `none` disables the picker
+pub fn parse_picker(picker: &str) -> Result<Option<Picker>, ConfigError> {
+    match picker.to_lowercase().as_str() {
+        "none" | "builtin" => Ok(None),
+        _ => Ok(Some(Picker::try_from(picker)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bandwidth_is_converted_to_bytes() {
+        assert_eq!(parse_bandwidth("2048").unwrap(), 2048 * 1024);
+        assert_eq!(
+            parse_bandwidth(&(u64::MAX / 1024).to_string()).unwrap(),
+            u64::MAX / 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn invalid_bandwidth_is_rejected() {
+        for kilobytes in [
+            "0",
+            "-1",
+            "fast",
+            "18014398509481984",
+            &u64::MAX.to_string(),
+        ] {
+            let err = parse_bandwidth(kilobytes).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                Message::InvalidBandwidth(kilobytes).to_string()
+            );
+        }
+    }
+}
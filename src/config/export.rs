@@ -0,0 +1,32 @@
+use super::file::expand_home;
+
+use crate::{errors::ConfigError, export::ExportConfig};
+
+use serde_json::Value;
+
+/// Parse settings of the library export, e.g. `"export": { "dir": "~/Media/Anime", "quality": "hd" }`
+/// # Arguments
+/// * `value` - Value of the `export` key, the directory is `Anime library` in the videos directory by default
+pub fn parse(value: &Value) -> Result<ExportConfig, ConfigError> {
+    let dir = match &value["dir"] {
+        Value::Null => dirs::video_dir().map(|dir| dir.join("Anime library")),
+        Value::String(dir) if !dir.is_empty() => Some(expand_home(dir)),
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`export.dir` must be a path",
+            )))
+        }
+    };
+
+    let quality = match &value["quality"] {
+        Value::Null => None,
+        Value::String(quality) if !quality.is_empty() => Some(quality.clone()),
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`export.quality` must be a string",
+            )))
+        }
+    };
+
+    Ok(ExportConfig { dir, quality })
+}
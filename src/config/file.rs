@@ -0,0 +1,37 @@
+use crate::errors::ConfigError;
+
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Get path of the config file in the user config directory,
+/// e.g. `~/.config/ani_cli_rs/config.json` on Linux
+#[must_use]
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join("config.json"))
+}
+
+/// Read the config file
+/// # Returns
+/// * `Ok(None)` - If the file doesn't exist
+pub fn read(path: &Path) -> Result<Option<Value>, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(ConfigError::ReadError(format!(
+            "Can't read config file `{path}`: {err}",
+            path = path.display()
+        ))),
+    }
+}
+
+/// Replace `~` at the start of the path with the home directory
+#[must_use]
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
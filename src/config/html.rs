@@ -0,0 +1,143 @@
+use super::rest::parse_request;
+
+use crate::{
+    enums::language::Language,
+    errors::ConfigError,
+    sources::{
+        rest::RequestSpec,
+        scrape::{
+            page::{self, Extract},
+            EpisodesSpec, HtmlSpec, ListingSpec, SearchSpec, StreamsSpec,
+        },
+    },
+};
+
+use serde_json::Value;
+
+/// Pages of a list loaded by default
+const MAX_PAGES: usize = 5;
+/// Levels of embedded players followed by default
+const MAX_EMBED_DEPTH: usize = 3;
+
+/// Parse definitions of sources scraping HTML sites, e.g.
+/// `"html_sources": [{ "name": "Example", "language": "en", "search": { ... }, "episodes": { ... } }]`,
+/// see the README for all keys
+/// # Arguments
+/// * `value` - Value of the `html_sources` key
+pub fn parse(value: &Value) -> Result<Vec<HtmlSpec>, ConfigError> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(sources) => sources.iter().map(parse_source).collect(),
+        _ => Err(ConfigError::ParseError(String::from(
+            "`html_sources` must be an array",
+        ))),
+    }
+}
+
+fn parse_source(value: &Value) -> Result<HtmlSpec, ConfigError> {
+    let name = value["name"]
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| {
+            ConfigError::ParseError(String::from("Each HTML source must have a `name`"))
+        })?
+        .to_string();
+    let key = |key: &str| format!("html_sources.{name}.{key}");
+
+    let language = match value["language"].as_str().map(Language::try_from) {
+        Some(Ok(Language::All) | Err(_)) | None => {
+            return Err(ConfigError::ParseError(format!(
+                "`{}` must be a language name or its ISO code, e.g. `en`",
+                key("language")
+            )))
+        }
+        Some(Ok(language)) => language,
+    };
+
+    let search = &value["search"];
+    let episodes = &value["episodes"];
+    let streams = &value["streams"];
+
+    Ok(HtmlSpec {
+        search: SearchSpec {
+            request: parse_request(search, &key("search"))?,
+            listing: parse_listing(search, &key("search"))?,
+            title: parse_extract(search, &key("search"), "title")?,
+            link: parse_extract(search, &key("search"), "link")?,
+        },
+        episodes: EpisodesSpec {
+            request: parse_optional_request(episodes, &key("episodes"))?,
+            listing: parse_listing(episodes, &key("episodes"))?,
+            number: parse_extract(episodes, &key("episodes"), "number")?,
+            link: parse_extract(episodes, &key("episodes"), "link")?,
+        },
+        streams: match streams {
+            Value::Null => None,
+            _ => Some(StreamsSpec {
+                request: parse_optional_request(streams, &key("streams"))?,
+                listing: parse_listing(streams, &key("streams"))?,
+                quality: parse_extract(streams, &key("streams"), "quality")?,
+                stream_url: parse_extract(streams, &key("streams"), "stream_url")?,
+            }),
+        },
+        max_embed_depth: parse_count(value, &key("max_embed_depth"), "max_embed_depth")?
+            .unwrap_or(MAX_EMBED_DEPTH),
+        name,
+        language,
+    })
+}
+
+fn parse_optional_request(value: &Value, key: &str) -> Result<Option<RequestSpec>, ConfigError> {
+    match &value["url"] {
+        Value::Null => Ok(None),
+        _ => parse_request(value, key).map(Some),
+    }
+}
+
+fn parse_listing(value: &Value, key: &str) -> Result<ListingSpec, ConfigError> {
+    let selector = |name: &str| -> Result<Option<_>, ConfigError> {
+        match &value[name] {
+            Value::Null => Ok(None),
+            Value::String(selector) => page::selector(selector)
+                .map(Some)
+                .map_err(|err| ConfigError::ParseError(format!("`{key}.{name}`: {err}"))),
+            _ => Err(ConfigError::ParseError(format!(
+                "`{key}.{name}` must be a CSS selector"
+            ))),
+        }
+    };
+
+    Ok(ListingSpec {
+        items: selector("items")?.ok_or_else(|| {
+            ConfigError::ParseError(format!("`{key}.items` must be a CSS selector"))
+        })?,
+        next_page: selector("next_page")?,
+        max_pages: parse_count(value, &format!("{key}.max_pages"), "max_pages")?
+            .unwrap_or(MAX_PAGES),
+    })
+}
+
+fn parse_extract(value: &Value, key: &str, name: &str) -> Result<Extract, ConfigError> {
+    let extract = value[name].as_str().ok_or_else(|| {
+        ConfigError::ParseError(format!(
+            "`{key}.{name}` must be a CSS selector with an optional attribute, e.g. `a.title@href`"
+        ))
+    })?;
+
+    Extract::parse(extract).map_err(|err| ConfigError::ParseError(format!("`{key}.{name}`: {err}")))
+}
+
+/// # Arguments
+/// * `key` - Full key in error messages
+/// * `name` - Key in the value
+fn parse_count(value: &Value, key: &str, name: &str) -> Result<Option<usize>, ConfigError> {
+    match &value[name] {
+        Value::Null => Ok(None),
+        count => count
+            .as_u64()
+            .and_then(|count| usize::try_from(count).ok())
+            .filter(|count| *count > 0)
+            .map(Some)
+            .ok_or_else(|| ConfigError::ParseError(format!("`{key}` must be a positive number"))),
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{errors::ConfigError, sources::http::HttpConfig};
+
+use serde_json::Value;
+use std::time::Duration;
+
+/// Parse settings of the HTTP client, e.g.
+/// `"http": { "connect_timeout": 10, "read_timeout": 30, "retries": 3, "proxy": "socks5://127.0.0.1:9050" }`
+/// # Arguments
+/// * `value` - Value of the `http` key, missing settings are taken from [`HttpConfig::default`]
+pub fn parse(value: &Value) -> Result<HttpConfig, ConfigError> {
+    let mut config = HttpConfig::default();
+
+    if let Some(secs) = number(value, "connect_timeout")? {
+        config.connect_timeout = Duration::from_secs(secs);
+    }
+    if let Some(secs) = number(value, "read_timeout")? {
+        config.read_timeout = Duration::from_secs(secs);
+    }
+    if let Some(retries) = number(value, "retries")? {
+        config.retries = retries.try_into().unwrap_or(u32::MAX);
+    }
+    if let Some(millis) = number(value, "backoff_ms")? {
+        config.backoff = Duration::from_millis(millis);
+    }
+    // `0` disables the rate limiting
+    if let Some(millis) = number(value, "rate_limit_ms")? {
+        config.rate_limit = (millis > 0).then(|| Duration::from_millis(millis));
+    }
+    if let Some(user_agent) = string(value, "user_agent")? {
+        config.user_agent = user_agent;
+    }
+    config.proxy = string(value, "proxy")?;
+
+    Ok(config)
+}
+
+fn number(value: &Value, key: &str) -> Result<Option<u64>, ConfigError> {
+    match &value[key] {
+        Value::Null => Ok(None),
+        value => value.as_u64().map(Some).ok_or_else(|| {
+            ConfigError::ParseError(format!("`http.{key}` must be a non-negative number"))
+        }),
+    }
+}
+
+fn string(value: &Value, key: &str) -> Result<Option<String>, ConfigError> {
+    match &value[key] {
+        Value::Null => Ok(None),
+        value => value
+            .as_str()
+            .map(|value| Some(value.to_string()))
+            .ok_or_else(|| ConfigError::ParseError(format!("`http.{key}` must be a string"))),
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{errors::ConfigError, server::relay::RelayConfig};
+
+use serde_json::Value;
+use std::{net::SocketAddr, time::Duration};
+
+/// Parse settings of the stream relay, e.g.
+/// `"relay": { "bind": "0.0.0.0:8787", "idle_timeout": 600, "qr": true }`
+/// # Arguments
+/// * `value` - Value of the `relay` key, missing settings are taken from [`RelayConfig::default`]
+pub fn parse(value: &Value) -> Result<RelayConfig, ConfigError> {
+    let mut config = RelayConfig::default();
+
+    match &value["bind"] {
+        Value::Null => {}
+        Value::String(bind) => config.bind = parse_bind(bind)?,
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`relay.bind` must be a string",
+            )))
+        }
+    }
+    match &value["idle_timeout"] {
+        Value::Null => {}
+        value => {
+            let secs = value.as_u64().ok_or_else(|| {
+                ConfigError::ParseError(String::from(
+                    "`relay.idle_timeout` must be a non-negative number",
+                ))
+            })?;
+            config.idle_timeout = Duration::from_secs(secs);
+        }
+    }
+    match &value["qr"] {
+        Value::Null => {}
+        value => {
+            config.qr = value.as_bool().ok_or_else(|| {
+                ConfigError::ParseError(String::from("`relay.qr` must be a boolean"))
+            })?;
+        }
+    }
+
+    Ok(config)
+}
+
+/// Parse an address to listen on, e.g. `0.0.0.0:8787`
+pub fn parse_bind(bind: &str) -> Result<SocketAddr, ConfigError> {
+    bind.parse().map_err(|_| {
+        ConfigError::InvalidArgument(format!(
+            "Address to listen on must be like `0.0.0.0:8787`, got `{bind}`"
+        ))
+    })
+}
@@ -0,0 +1,128 @@
+use crate::{
+    enums::language::Language,
+    errors::ConfigError,
+    sources::rest::{EpisodesSpec, JsonPath, RequestSpec, RestSpec, SearchSpec, StreamsSpec},
+};
+
+use reqwest::Url;
+use serde_json::Value;
+
+/// Parse definitions of sources backed by JSON APIs, e.g.
+/// `"sources": [{ "name": "Example", "language": "en", "search": { ... }, "episodes": { ... }, "streams": { ... } }]`,
+/// see the README for all keys
+/// # Arguments
+/// * `value` - Value of the `sources` key
+pub fn parse(value: &Value) -> Result<Vec<RestSpec>, ConfigError> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(sources) => sources.iter().map(parse_source).collect(),
+        _ => Err(ConfigError::ParseError(String::from(
+            "`sources` must be an array",
+        ))),
+    }
+}
+
+fn parse_source(value: &Value) -> Result<RestSpec, ConfigError> {
+    let name = value["name"]
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| ConfigError::ParseError(String::from("Each source must have a `name`")))?
+        .to_string();
+    let key = |key: &str| format!("sources.{name}.{key}");
+
+    let language = match value["language"].as_str().map(Language::try_from) {
+        Some(Ok(Language::All) | Err(_)) | None => {
+            return Err(ConfigError::ParseError(format!(
+                "`{}` must be a language name or its ISO code, e.g. `en`",
+                key("language")
+            )))
+        }
+        Some(Ok(language)) => language,
+    };
+
+    let search = &value["search"];
+    let episodes = &value["episodes"];
+    let streams = &value["streams"];
+
+    Ok(RestSpec {
+        search: SearchSpec {
+            request: parse_request(search, &key("search"))?,
+            items: parse_path(search, &key("search"), "items")?,
+            id: match &search["id"] {
+                Value::Null => None,
+                _ => Some(parse_path(search, &key("search"), "id")?),
+            },
+            title: parse_path(search, &key("search"), "title")?,
+        },
+        episodes: EpisodesSpec {
+            request: parse_request(episodes, &key("episodes"))?,
+            items: parse_path(episodes, &key("episodes"), "items")?,
+            number: parse_path(episodes, &key("episodes"), "number")?,
+        },
+        streams: StreamsSpec {
+            request: match &streams["url"] {
+                Value::Null => None,
+                _ => Some(parse_request(streams, &key("streams"))?),
+            },
+            items: parse_path(streams, &key("streams"), "items")?,
+            quality: parse_path(streams, &key("streams"), "quality")?,
+            stream_url: parse_path(streams, &key("streams"), "stream_url")?,
+            base_url: match &streams["base_url"] {
+                Value::Null => None,
+                base_url => Some(
+                    base_url
+                        .as_str()
+                        .and_then(|base_url| Url::parse(base_url).ok())
+                        .ok_or_else(|| {
+                            ConfigError::ParseError(format!(
+                                "`{}.base_url` must be an absolute URL",
+                                key("streams")
+                            ))
+                        })?,
+                ),
+            },
+        },
+        name,
+        language,
+    })
+}
+
+/// Parse `"url"` and `"params"` of the endpoint
+/// # Arguments
+/// * `key` - Key of the endpoint in error messages, e.g. `sources.Example.search`
+pub fn parse_request(value: &Value, key: &str) -> Result<RequestSpec, ConfigError> {
+    let url = value["url"]
+        .as_str()
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+        .ok_or_else(|| ConfigError::ParseError(format!("`{key}.url` must be an HTTP URL")))?
+        .to_string();
+
+    let params = match &value["params"] {
+        Value::Null => Vec::new(),
+        Value::Object(params) => params
+            .iter()
+            .map(|(name, param)| match param {
+                Value::String(param) => Ok((name.clone(), param.clone())),
+                Value::Number(param) => Ok((name.clone(), param.to_string())),
+                _ => Err(ConfigError::ParseError(format!(
+                    "`{key}.params.{name}` must be a string"
+                ))),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => {
+            return Err(ConfigError::ParseError(format!(
+                "`{key}.params` must be an object"
+            )))
+        }
+    };
+
+    Ok(RequestSpec { url, params })
+}
+
+fn parse_path(value: &Value, key: &str, name: &str) -> Result<JsonPath, ConfigError> {
+    let path = value[name].as_str().ok_or_else(|| {
+        ConfigError::ParseError(format!("`{key}.{name}` must be a path like `$.list[*].id`"))
+    })?;
+
+    JsonPath::parse(path).map_err(|err| ConfigError::ParseError(format!("`{key}.{name}`: {err}")))
+}
@@ -0,0 +1,41 @@
+use crate::{errors::ConfigError, tracker::shikimori::ShikimoriConfig};
+
+use serde_json::Value;
+
+/// Parse settings of Shikimori, e.g. `"shikimori": { "client_id": "...", "client_secret": "..." }`
+/// # Arguments
+/// * `value` - Value of the `shikimori` key, `url` is `https://shikimori.one` by default
+pub fn parse(value: &Value) -> Result<ShikimoriConfig, ConfigError> {
+    let mut config = ShikimoriConfig::default();
+
+    match &value["url"] {
+        Value::Null => {}
+        Value::String(url) if url.starts_with("http://") || url.starts_with("https://") => {
+            config.url = url.trim_end_matches('/').to_string();
+        }
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`shikimori.url` must be an HTTP or HTTPS URL",
+            )))
+        }
+    }
+
+    for (key, field) in [
+        ("client_id", &mut config.client_id),
+        ("client_secret", &mut config.client_secret),
+    ] {
+        match &value[key] {
+            Value::Null => {}
+            Value::String(credential) if !credential.is_empty() => {
+                *field = Some(credential.clone());
+            }
+            _ => {
+                return Err(ConfigError::ParseError(format!(
+                    "`shikimori.{key}` must be a string"
+                )))
+            }
+        }
+    }
+
+    Ok(config)
+}
@@ -0,0 +1,188 @@
+use super::file::expand_home;
+
+use crate::{
+    download::ffmpeg::Container,
+    errors::ConfigError,
+    sync::{file_stem, SyncConfig, SyncRule},
+};
+
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Parse settings of auto-downloads, e.g.
+/// `"sync": { "dir": "~/Anime", "concurrency": 2, "bandwidth": 2048, "remux": "mkv", "rules": [{ "source": "Anilibria", "anime": "Madoka", "quality": "hd", "keep": 3 }] }`
+/// # Arguments
+/// * `value` - Value of the `sync` key, missing settings are taken from [`SyncConfig::default`]
+pub fn parse(value: &Value) -> Result<SyncConfig, ConfigError> {
+    let mut config = SyncConfig::default();
+
+    match &value["concurrency"] {
+        Value::Null => {}
+        value => {
+            config.concurrency = value
+                .as_u64()
+                .and_then(|concurrency| usize::try_from(concurrency).ok())
+                .filter(|concurrency| *concurrency > 0)
+                .ok_or_else(|| {
+                    ConfigError::ParseError(String::from(
+                        "`sync.concurrency` must be a positive number",
+                    ))
+                })?;
+        }
+    }
+    match &value["bandwidth"] {
+        Value::Null => {}
+        value => {
+            let bytes = value
+                .as_u64()
+                .filter(|kilobytes| *kilobytes > 0)
+                .and_then(|kilobytes| kilobytes.checked_mul(1024))
+                .ok_or_else(|| {
+                    ConfigError::ParseError(String::from(
+                        "`sync.bandwidth` must be a positive number of kilobytes per second",
+                    ))
+                })?;
+            config.bandwidth = Some(bytes);
+        }
+    }
+
+    config.remux = match &value["remux"] {
+        Value::Null => None,
+        Value::String(format) if format.eq_ignore_ascii_case("mp4") => Some(Container::Mp4),
+        Value::String(format) if format.eq_ignore_ascii_case("mkv") => Some(Container::Mkv),
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`sync.remux` must be `mp4` or `mkv`",
+            )))
+        }
+    };
+
+    // Directories of anime are created in the base directory by default
+    let base_dir = match &value["dir"] {
+        Value::Null => dirs::video_dir().map(|dir| dir.join("Anime")),
+        Value::String(dir) => Some(expand_home(dir)),
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`sync.dir` must be a path",
+            )))
+        }
+    };
+
+    match &value["rules"] {
+        Value::Null => {}
+        Value::Array(rules) => {
+            config.rules = rules
+                .iter()
+                .map(|rule| parse_rule(rule, base_dir.as_ref()))
+                .collect::<Result<_, _>>()?;
+        }
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`sync.rules` must be an array",
+            )))
+        }
+    }
+
+    Ok(config)
+}
+
+fn parse_rule(value: &Value, base_dir: Option<&PathBuf>) -> Result<SyncRule, ConfigError> {
+    let string = |key: &str| -> Result<Option<String>, ConfigError> {
+        match &value[key] {
+            Value::Null => Ok(None),
+            Value::String(value) if !value.is_empty() => Ok(Some(value.clone())),
+            _ => Err(ConfigError::ParseError(format!(
+                "`sync.rules.{key}` must be a string"
+            ))),
+        }
+    };
+
+    let anime = string("anime")?.ok_or_else(|| {
+        ConfigError::ParseError(String::from("Each of `sync.rules` must have an `anime`"))
+    })?;
+    let source = string("source")?.ok_or_else(|| {
+        ConfigError::ParseError(format!("Rule of `{anime}` must have a `source`"))
+    })?;
+
+    let dir = match string("dir")? {
+        Some(dir) => expand_home(&dir),
+        None => base_dir
+            .map(|dir| dir.join(file_stem(&anime)))
+            .ok_or_else(|| {
+                ConfigError::ParseError(format!(
+                    "Rule of `{anime}` must have a `dir`, there is no videos directory"
+                ))
+            })?,
+    };
+
+    let keep = match &value["keep"] {
+        Value::Null => None,
+        keep => Some(
+            keep.as_u64()
+                .and_then(|keep| usize::try_from(keep).ok())
+                .filter(|keep| *keep > 0)
+                .ok_or_else(|| {
+                    ConfigError::ParseError(format!(
+                        "`keep` of the rule of `{anime}` must be a positive number"
+                    ))
+                })?,
+        ),
+    };
+
+    Ok(SyncRule {
+        quality: string("quality")?,
+        source,
+        anime,
+        dir,
+        keep,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn rules_are_parsed_with_defaults_of_the_section() {
+        let config = parse(&json!({
+            "dir": "/anime",
+            "bandwidth": 2048,
+            "remux": "MKV",
+            "rules": [
+                { "source": "Anilibria", "anime": "Madoka", "keep": 3 },
+                { "source": "Anilibria", "anime": "Evangelion 1.0", "dir": "/eva", "quality": "hd" },
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(config.concurrency, 2);
+        assert_eq!(config.bandwidth, Some(2048 * 1024));
+        assert!(config.remux == Some(Container::Mkv));
+
+        let [madoka, evangelion] = &config.rules[..] else {
+            panic!("Two rules are expected");
+        };
+        assert_eq!(madoka.dir, PathBuf::from("/anime/Madoka"));
+        assert_eq!(madoka.keep, Some(3));
+        assert_eq!(madoka.quality, None);
+        assert_eq!(evangelion.dir, PathBuf::from("/eva"));
+        assert_eq!(evangelion.quality.as_deref(), Some("hd"));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        for value in [
+            json!({ "concurrency": 0 }),
+            json!({ "bandwidth": u64::MAX }),
+            json!({ "remux": "avi" }),
+            json!({ "rules": {} }),
+            json!({ "dir": "/anime", "rules": [{ "source": "Anilibria" }] }),
+            json!({ "dir": "/anime", "rules": [{ "anime": "Madoka" }] }),
+            json!({ "dir": "/anime", "rules": [{ "source": "Anilibria", "anime": "Madoka", "keep": 0 }] }),
+        ] {
+            assert!(parse(&value).is_err(), "{value} is accepted");
+        }
+    }
+}
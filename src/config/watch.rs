@@ -0,0 +1,67 @@
+use crate::{
+    errors::ConfigError,
+    watch::{hook::Hook, WatchConfig},
+};
+
+use serde_json::Value;
+use std::time::Duration;
+
+/// Parse settings of the new episode watcher, e.g.
+/// `"watch": { "interval": 3600, "hooks": [{ "command": ["notify-send", "{anime}", "{message}"] }, { "webhook": "https://example.com/hook" }] }`
+/// # Arguments
+/// * `value` - Value of the `watch` key, missing settings are taken from [`WatchConfig::default`]
+pub fn parse(value: &Value) -> Result<WatchConfig, ConfigError> {
+    let mut config = WatchConfig::default();
+
+    match &value["interval"] {
+        Value::Null => {}
+        value => {
+            let secs = value.as_u64().filter(|secs| *secs > 0).ok_or_else(|| {
+                ConfigError::ParseError(String::from(
+                    "`watch.interval` must be a positive number of seconds",
+                ))
+            })?;
+            config.interval = Duration::from_secs(secs);
+        }
+    }
+    match &value["hooks"] {
+        Value::Null => {}
+        Value::Array(hooks) => {
+            config.hooks = hooks.iter().map(parse_hook).collect::<Result<_, _>>()?;
+        }
+        _ => {
+            return Err(ConfigError::ParseError(String::from(
+                "`watch.hooks` must be an array",
+            )))
+        }
+    }
+
+    Ok(config)
+}
+
+/// Parse a hook, a command is a program with arguments or a path to a script
+fn parse_hook(value: &Value) -> Result<Hook, ConfigError> {
+    match (&value["command"], &value["webhook"]) {
+        (Value::String(script), Value::Null) if !script.is_empty() => {
+            Ok(Hook::Command(vec![script.clone()]))
+        }
+        (Value::Array(command), Value::Null) if !command.is_empty() => command
+            .iter()
+            .map(|arg| arg.as_str().map(ToString::to_string))
+            .collect::<Option<_>>()
+            .map(Hook::Command)
+            .ok_or_else(|| {
+                ConfigError::ParseError(String::from(
+                    "Arguments of `watch.hooks.command` must be strings",
+                ))
+            }),
+        (Value::Null, Value::String(url))
+            if url.starts_with("http://") || url.starts_with("https://") =>
+        {
+            Ok(Hook::Webhook(url.clone()))
+        }
+        _ => Err(ConfigError::ParseError(String::from(
+            "Each of `watch.hooks` must have a `command` or an HTTP `webhook` URL",
+        ))),
+    }
+}
@@ -1,5 +1,4 @@
-mod common;
+pub(crate) mod common;
 
 pub mod cli;
-
-pub use common::state::ResultState;
+pub mod tui;
@@ -1,5 +1,18 @@
+mod account;
+mod favorites;
+mod interrupt;
+mod library;
 mod output;
+mod picker;
 mod prompt;
+mod qr;
 mod run;
+mod terminal;
+mod theme;
 
-pub use run::{finish, run};
+pub use account::login;
+pub use favorites::run as favorites;
+pub use library::run as library;
+pub use run::run;
+pub use terminal::Terminal;
+pub use theme::Theme;
@@ -0,0 +1,40 @@
+use super::{output, prompt, terminal::Terminal};
+
+use crate::{locale::Message, sources::ru::anilibria::Anilibria};
+
+use std::io::BufRead;
+use termcolor::WriteColor;
+
+/// Ask the login and the password of the Anilibria account and log in, the password isn't echoed
+/// # Returns
+/// `true` if the user is logged in
+pub async fn login<R, W>(terminal: &mut Terminal<R, W>, source: &Anilibria<'_>) -> bool
+where
+    R: BufRead,
+    W: WriteColor,
+{
+    let Some(login) =
+        prompt::read_line_or_none(terminal, &format!("{}: ", Message::EnterLogin), None)
+    else {
+        return false;
+    };
+    let Some(password) =
+        prompt::read_secret_or_none(terminal, &format!("{}: ", Message::EnterPassword))
+    else {
+        return false;
+    };
+
+    match source.login(&login, &password).await {
+        Ok(()) => {
+            output::info_msg(
+                terminal,
+                &format!("{}\n", Message::LoggedIn(&source.to_string())),
+            );
+            true
+        }
+        Err(err) => {
+            output::error_msg(terminal, &format!("{}\n", Message::CantLogIn(&err)));
+            false
+        }
+    }
+}
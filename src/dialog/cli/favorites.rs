@@ -0,0 +1,118 @@
+use super::{library::select_variant, output, prompt, terminal::Terminal};
+
+use crate::{dialog::common::state::ResultState, locale::Message, sources::base::Source};
+
+use std::{io::BufRead, iter};
+use termcolor::WriteColor;
+
+/// Browse favorite anime of the account of the source to remove them or to add found anime
+pub async fn run<S, R, W>(terminal: &mut Terminal<R, W>, source: &mut S)
+where
+    S: Source,
+    R: BufRead,
+    W: WriteColor,
+{
+    loop {
+        let favorites = match source.favorite_anime_list().await {
+            Ok(Some(_)) => source.anime_list(),
+            Ok(None) => {
+                output::error_msg(terminal, &format!("{}\n", Message::NoFavorites));
+                break;
+            }
+            Err(err) => {
+                output::error_msg(terminal, &format!("{err}\n"));
+                break;
+            }
+        };
+        if favorites.is_empty() {
+            output::info_msg(terminal, &format!("{}\n", Message::EmptyFavorites));
+        }
+
+        // The first variant adds anime, the others are favorites by their sequence numbers
+        let variants = iter::once(Message::AddFavorite.to_string())
+            .chain(favorites.iter().cloned())
+            .collect::<Vec<_>>();
+        let ResultState::Success(index) = select_variant(
+            terminal,
+            &Message::Favorites.to_string(),
+            &Message::SelectAnime.to_string(),
+            &variants,
+        ) else {
+            break;
+        };
+
+        if index == 0 {
+            add(terminal, source).await;
+        } else if let ResultState::Success(_) = select_variant(
+            terminal,
+            &favorites[index - 1],
+            &Message::SelectAction.to_string(),
+            &[Message::RemoveFavorite.to_string()],
+        ) {
+            edit(terminal, source, index, false).await;
+        }
+        writeln!(terminal.output()).unwrap();
+    }
+
+    super::run::finish(terminal);
+}
+
+/// Search anime by the name and add the selected one to favorites
+async fn add<S, R, W>(terminal: &mut Terminal<R, W>, source: &mut S)
+where
+    S: Source,
+    R: BufRead,
+    W: WriteColor,
+{
+    let Some(anime_name) =
+        prompt::read_line_or_none(terminal, &format!("{}: ", Message::EnterAnimeName), None)
+    else {
+        return;
+    };
+
+    output::info_msg(terminal, &format!("\t{}\n", Message::Searching));
+    if let Err(err) = source.search_anime_list(&anime_name).await {
+        output::error_msg(terminal, &format!("{err}\n"));
+        return;
+    }
+
+    if let ResultState::Success(index) = select_variant(
+        terminal,
+        &Message::AnimeList.to_string(),
+        &Message::SelectAnime.to_string(),
+        &source.anime_list(),
+    ) {
+        edit(terminal, source, index + 1, true).await;
+    }
+}
+
+/// Select the anime of the last list by its sequence number and add it to favorites or remove it from them
+async fn edit<S, R, W>(
+    terminal: &mut Terminal<R, W>,
+    source: &mut S,
+    seq_num: usize,
+    favorite: bool,
+) where
+    S: Source,
+    W: WriteColor,
+{
+    if let Err(err) = source.select_anime_as_current(seq_num.to_string()).await {
+        output::error_msg(terminal, &format!("{err}\n"));
+        return;
+    }
+    let anime = source
+        .anime_info()
+        .map(|anime| anime.to_string())
+        .unwrap_or_default();
+
+    match source.set_favorite(favorite).await {
+        Ok(()) if favorite => {
+            output::info_msg(terminal, &format!("{}\n", Message::FavoriteAdded(&anime)));
+        }
+        Ok(()) => output::info_msg(terminal, &format!("{}\n", Message::FavoriteRemoved(&anime))),
+        Err(err) => output::error_msg(
+            terminal,
+            &format!("{}\n", Message::CantEditFavorites(&anime, &err)),
+        ),
+    }
+}
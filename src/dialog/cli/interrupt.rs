@@ -0,0 +1,58 @@
+use std::{
+    future::Future,
+    mem, process,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::oneshot;
+
+/// Handler of `Ctrl-C`. \
+/// While an operation waits for the interrupt, `Ctrl-C` cancels the operation
+/// and the user returns to the prompt, otherwise the program is terminated as usual
+#[derive(Clone)]
+pub struct Interrupt {
+    waiters: Arc<Mutex<Vec<oneshot::Sender<()>>>>,
+}
+
+impl Interrupt {
+    /// Start listening `Ctrl-C`. \
+    /// Must be called inside the tokio runtime
+    #[must_use]
+    pub fn listen() -> Self {
+        let interrupt = Self {
+            waiters: Arc::default(),
+        };
+
+        let waiters = Arc::clone(&interrupt.waiters);
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                let waiters = mem::take(&mut *waiters.lock().unwrap())
+                    .into_iter()
+                    .filter(|waiter| !waiter.is_closed())
+                    .collect::<Vec<_>>();
+
+                if waiters.is_empty() {
+                    process::exit(130);
+                }
+                for waiter in waiters {
+                    // The waiter can be dropped after the check
+                    let _ = waiter.send(());
+                }
+            }
+        });
+
+        interrupt
+    }
+
+    /// Wait for `Ctrl-C`
+    pub fn interrupted(&self) -> impl Future<Output = ()> {
+        let (sender, receiver) = oneshot::channel();
+        self.waiters.lock().unwrap().push(sender);
+
+        async move {
+            if receiver.await.is_err() {
+                // The listener is stopped, so `Ctrl-C` never comes
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
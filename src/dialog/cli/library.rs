@@ -0,0 +1,283 @@
+use super::{output, prompt, run::select_player, terminal::Terminal};
+
+use crate::{
+    dialog::common::state::ResultState,
+    enums::player::Player,
+    locale::Message,
+    players::mpv,
+    storage::{
+        downloads::{Downloads, Entry, FileState},
+        history::History,
+    },
+};
+
+use std::io::BufRead;
+use termcolor::WriteColor;
+
+/// Browse downloaded episodes to play them from disk, delete them or verify their files
+pub fn run<R, W>(terminal: &mut Terminal<R, W>, downloads: &mut Downloads, history: &mut History)
+where
+    R: BufRead,
+    W: WriteColor,
+{
+    loop {
+        let titles = titles(downloads);
+        if titles.is_empty() {
+            output::info_msg(terminal, &format!("{}\n", Message::NoDownloads));
+            break;
+        }
+
+        let variants = titles
+            .iter()
+            .map(|(source, anime)| {
+                let episodes = episodes_of(downloads, source, anime);
+                let size = episodes.iter().map(|entry| entry.size).sum::<u64>();
+                format!(
+                    "[{source}] {anime} | {}",
+                    Message::DownloadedTitle(episodes.len(), &(size / 1_000_000))
+                )
+            })
+            .collect::<Vec<_>>();
+        let ResultState::Success(index) = select_variant(
+            terminal,
+            &Message::Downloads.to_string(),
+            &Message::SelectAnime.to_string(),
+            &variants,
+        ) else {
+            break;
+        };
+
+        let (source, anime) = &titles[index];
+        browse_title(terminal, downloads, history, source, anime);
+        writeln!(terminal.output()).unwrap();
+    }
+
+    super::run::finish(terminal);
+}
+
+/// Select episodes of the title and actions on them until the user backs to the titles
+fn browse_title<R, W>(
+    terminal: &mut Terminal<R, W>,
+    downloads: &mut Downloads,
+    history: &mut History,
+    source: &str,
+    anime: &str,
+) where
+    R: BufRead,
+    W: WriteColor,
+{
+    loop {
+        let (episodes, variants): (Vec<_>, Vec<_>) = episodes_of(downloads, source, anime)
+            .into_iter()
+            .map(|entry| {
+                let mut label = Message::DownloadedEpisode(
+                    &entry.episode,
+                    &entry.quality,
+                    &(entry.size / 1_000_000),
+                )
+                .to_string();
+                if history.is_watched(source, anime, &entry.episode) {
+                    label.push_str(" ✓");
+                }
+                (entry.episode.clone(), label)
+            })
+            .unzip();
+        if episodes.is_empty() {
+            return;
+        }
+
+        let ResultState::Success(index) = select_variant(
+            terminal,
+            anime,
+            &Message::SelectEpisode.to_string(),
+            &variants,
+        ) else {
+            return;
+        };
+        let episode = &episodes[index];
+
+        let actions = [
+            Message::PlayDownload.to_string(),
+            Message::DeleteDownload.to_string(),
+            Message::VerifyDownload.to_string(),
+        ];
+        match select_variant(
+            terminal,
+            &Message::SelectedEpisode(episode).to_string(),
+            &Message::SelectAction.to_string(),
+            &actions,
+        ) {
+            ResultState::Success(0) => play(terminal, downloads, history, source, anime, episode),
+            ResultState::Success(1) => match downloads.delete(source, anime, episode) {
+                Ok(Some(entry)) => output::info_msg(
+                    terminal,
+                    &format!("{}\n", Message::RemovedDownload(&entry.path.display())),
+                ),
+                Ok(None) => {}
+                Err(err) => output::error_msg(
+                    terminal,
+                    &format!("{}\n", Message::CantDeleteDownload(anime, episode, &err)),
+                ),
+            },
+            ResultState::Success(_) => {
+                if let Some(entry) = downloads.find(source, anime, episode) {
+                    verify(terminal, entry);
+                }
+            }
+            ResultState::Break => {}
+        }
+        writeln!(terminal.output()).unwrap();
+    }
+}
+
+/// Play the downloaded file of the episode and mark the episode as watched
+fn play<R, W>(
+    terminal: &mut Terminal<R, W>,
+    downloads: &Downloads,
+    history: &mut History,
+    source: &str,
+    anime: &str,
+    episode: &str,
+) where
+    R: BufRead,
+    W: WriteColor,
+{
+    let Some(entry) = downloads.find(source, anime, episode) else {
+        return;
+    };
+    if !verify(terminal, entry) {
+        return;
+    }
+
+    let ResultState::Success(player) = select_player(terminal) else {
+        return;
+    };
+
+    output::info_msg(terminal, &format!("{}\n", Message::LaunchingPlayer));
+
+    let path = entry.path.to_string_lossy();
+    match player {
+        Player::Mpv => {
+            if let Err(err) = mpv::launch(&path) {
+                output::error_msg(terminal, &format!("{err}\n"));
+                return;
+            }
+        }
+    }
+
+    if let Err(err) = history.add(source, anime, episode) {
+        output::warning_msg(terminal, &format!("{}\n", Message::CantSaveHistory(&err)));
+    }
+
+    output::info_msg(terminal, &format!("{}\n", Message::PlayerFinished));
+}
+
+/// Check the file of the downloaded episode and print its state
+/// # Returns
+/// `true` if the file is fine
+fn verify<R, W>(terminal: &mut Terminal<R, W>, entry: &Entry) -> bool
+where
+    W: WriteColor,
+{
+    let path = entry.path.display();
+
+    match entry.check() {
+        Ok(FileState::Ok) => {
+            output::info_msg(terminal, &format!("{}\n", Message::DownloadOk(&path)));
+            true
+        }
+        Ok(FileState::Missing) => {
+            output::error_msg(terminal, &format!("{}\n", Message::DownloadMissing(&path)));
+            false
+        }
+        Ok(FileState::SizeMismatch(size)) => {
+            output::error_msg(
+                terminal,
+                &format!(
+                    "{}\n",
+                    Message::DownloadSizeMismatch(&path, &entry.size, &size)
+                ),
+            );
+            false
+        }
+        Err(err) => {
+            output::error_msg(terminal, &format!("{path}: {err}\n"));
+            false
+        }
+    }
+}
+
+/// Select a variant with the picker or by its sequence number
+pub(super) fn select_variant<R, W>(
+    terminal: &mut Terminal<R, W>,
+    headline: &str,
+    input_msg: &str,
+    variants: &[String],
+) -> ResultState<usize>
+where
+    R: BufRead,
+    W: WriteColor,
+{
+    if let Some(result) = prompt::pick_or_none(terminal, &format!("{input_msg}: "), variants) {
+        return result;
+    }
+
+    output::variant_headline_msg(terminal, headline);
+    output::info_msg(terminal, &format!(" ({}):\n", Message::BackOnEmptyInput));
+    for (seq_num, variant) in variants.iter().enumerate() {
+        output::variant_msg(
+            terminal,
+            &format!("\t{seq_num}. {variant}\n", seq_num = seq_num + 1),
+        );
+    }
+
+    loop {
+        let Some(seq_num) = prompt::read_line_or_none(terminal, &format!("{input_msg}: "), None)
+        else {
+            return ResultState::Break;
+        };
+
+        match seq_num.parse::<usize>().ok().and_then(|seq_num| {
+            seq_num
+                .checked_sub(1)
+                .filter(|index| *index < variants.len())
+        }) {
+            Some(index) => return ResultState::Success(index),
+            None => {
+                output::warning_msg(terminal, &format!("{}\n", Message::UnknownSeqNum(&seq_num)))
+            }
+        }
+    }
+}
+
+/// Get sources and titles of downloaded anime in the order they were first downloaded
+fn titles(downloads: &Downloads) -> Vec<(String, String)> {
+    let mut titles: Vec<(String, String)> = Vec::new();
+    for entry in downloads.entries() {
+        if !titles
+            .iter()
+            .any(|(source, anime)| *source == entry.source && *anime == entry.anime)
+        {
+            titles.push((entry.source.clone(), entry.anime.clone()));
+        }
+    }
+
+    titles
+}
+
+/// Get downloaded episodes of the anime sorted by their numbers
+fn episodes_of<'a>(downloads: &'a Downloads, source: &str, anime: &str) -> Vec<&'a Entry> {
+    let mut episodes = downloads
+        .entries()
+        .iter()
+        .filter(|entry| entry.source == source && entry.anime == anime)
+        .collect::<Vec<_>>();
+    episodes.sort_by(
+        |a, b| match (a.episode.parse::<f64>(), b.episode.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.total_cmp(&b),
+            _ => a.episode.cmp(&b.episode),
+        },
+    );
+
+    episodes
+}
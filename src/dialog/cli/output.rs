@@ -1,102 +1,121 @@
-use std::io::Write as _;
-use termcolor::{self, WriteColor as _};
+use super::terminal::Terminal;
 
-pub fn input_msg(msg: &str) {
-    let mut stdout = termcolor::StandardStream::stdout(termcolor::ColorChoice::Always);
-    stdout
-        .set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Green)))
-        .unwrap();
+use termcolor::WriteColor;
+
+pub fn input_msg<R, W>(terminal: &mut Terminal<R, W>, msg: &str)
+where
+    W: WriteColor,
+{
+    let spec = terminal.theme().input.clone();
+
+    let output = terminal.output();
+    output.set_color(&spec).unwrap();
 
     let msg = if msg.starts_with('\n') {
-        writeln!(stdout).unwrap();
+        writeln!(output).unwrap();
         msg.replacen('\n', "", 1)
     } else {
         msg.to_string()
     };
 
-    write!(stdout, "> ").unwrap();
-    stdout.reset().unwrap();
-    write!(stdout, "{msg}").unwrap();
-    stdout.flush().unwrap();
+    write!(output, "> ").unwrap();
+    output.reset().unwrap();
+    write!(output, "{msg}").unwrap();
+    output.flush().unwrap();
 }
 
-pub fn warning_msg(msg: &str) {
-    let mut stdout = termcolor::StandardStream::stdout(termcolor::ColorChoice::Always);
-    stdout
-        .set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Yellow)))
-        .unwrap();
+pub fn warning_msg<R, W>(terminal: &mut Terminal<R, W>, msg: &str)
+where
+    W: WriteColor,
+{
+    let spec = terminal.theme().warning.clone();
+
+    let output = terminal.output();
+    output.set_color(&spec).unwrap();
 
     let msg = if msg.starts_with('\n') {
-        writeln!(stdout).unwrap();
+        writeln!(output).unwrap();
         msg.replacen('\n', "", 1)
     } else {
         msg.to_string()
     };
 
-    write!(stdout, "\t<-> ").unwrap();
-    stdout.reset().unwrap();
-    write!(stdout, "{msg}").unwrap();
-    stdout.flush().unwrap();
+    write!(output, "\t<-> ").unwrap();
+    output.reset().unwrap();
+    write!(output, "{msg}").unwrap();
+    output.flush().unwrap();
 }
 
-pub fn error_msg(msg: &str) {
-    let mut stdout = termcolor::StandardStream::stdout(termcolor::ColorChoice::Always);
-    stdout
-        .set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Red)))
-        .unwrap();
+pub fn error_msg<R, W>(terminal: &mut Terminal<R, W>, msg: &str)
+where
+    W: WriteColor,
+{
+    let spec = terminal.theme().error.clone();
+
+    let output = terminal.output();
+    output.set_color(&spec).unwrap();
 
     let msg = if msg.starts_with('\n') {
-        writeln!(stdout).unwrap();
+        writeln!(output).unwrap();
         msg.replacen('\n', "", 1)
     } else {
         msg.to_string()
     };
 
-    write!(stdout, "\t<-> ").unwrap();
-    stdout.reset().unwrap();
-    write!(stdout, "{msg}").unwrap();
-    stdout.flush().unwrap();
+    write!(output, "\t<-> ").unwrap();
+    output.reset().unwrap();
+    write!(output, "{msg}").unwrap();
+    output.flush().unwrap();
 }
 
-pub fn info_msg(msg: &str) {
-    let mut stdout = termcolor::StandardStream::stdout(termcolor::ColorChoice::Always);
-    stdout
-        .set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Blue)))
-        .unwrap();
+pub fn info_msg<R, W>(terminal: &mut Terminal<R, W>, msg: &str)
+where
+    W: WriteColor,
+{
+    let spec = terminal.theme().info.clone();
 
-    write!(stdout, "{msg}").unwrap();
+    let output = terminal.output();
+    output.set_color(&spec).unwrap();
 
-    stdout.reset().unwrap();
-    stdout.flush().unwrap();
+    write!(output, "{msg}").unwrap();
+
+    output.reset().unwrap();
+    output.flush().unwrap();
 }
 
-pub fn variant_msg(msg: &str) {
-    let mut stdout = termcolor::StandardStream::stdout(termcolor::ColorChoice::Always);
-    stdout
-        .set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::White)))
-        .unwrap();
+pub fn variant_msg<R, W>(terminal: &mut Terminal<R, W>, msg: &str)
+where
+    W: WriteColor,
+{
+    let spec = terminal.theme().variant.clone();
+
+    let output = terminal.output();
+    output.set_color(&spec).unwrap();
 
-    write!(stdout, "{msg}").unwrap();
+    write!(output, "{msg}").unwrap();
 
-    stdout.reset().unwrap();
-    stdout.flush().unwrap();
+    output.reset().unwrap();
+    output.flush().unwrap();
 }
 
-pub fn variant_headline_msg(msg: &str) {
-    let mut stdout = termcolor::StandardStream::stdout(termcolor::ColorChoice::Always);
-    stdout
-        .set_color(termcolor::ColorSpec::new().set_fg(Some(termcolor::Color::Cyan)))
-        .unwrap();
+pub fn variant_headline_msg<R, W>(terminal: &mut Terminal<R, W>, msg: &str)
+where
+    W: WriteColor,
+{
+    let spec = terminal.theme().headline.clone();
+
+    let output = terminal.output();
+    output.set_color(&spec).unwrap();
 
     let msg = if msg.starts_with('\n') {
-        writeln!(stdout).unwrap();
+        writeln!(output).unwrap();
         msg.replacen('\n', "", 1)
     } else {
         msg.to_string()
     };
 
-    write!(stdout, "> ").unwrap();
-    stdout.reset().unwrap();
-    write!(stdout, "{msg}").unwrap();
-    stdout.flush().unwrap();
+    write!(output, "> ").unwrap();
+    output.reset().unwrap();
+    write!(output, "{msg}").unwrap();
+    output.flush().unwrap();
 }
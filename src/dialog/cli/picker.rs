@@ -0,0 +1,83 @@
+use crate::{enums::picker::Picker, errors::PickerError, locale::Message};
+
+use std::{
+    io::{self, Write as _},
+    process::{Command, Stdio},
+};
+
+/// Select a variant with the external picker. \
+/// Variants are passed to the picker with their sequence numbers,
+/// so variants with the same names can be distinguished.
+/// # Returns
+/// * `Ok(Some(index))` - Index of the selected variant
+/// * `Ok(None)` - If the user cancelled the picker
+pub fn pick(
+    picker: Picker,
+    prompt: &str,
+    variants: &[String],
+) -> Result<Option<usize>, PickerError> {
+    let input = variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| format!("{seq_num}\t{variant}\n", seq_num = index + 1))
+        .collect::<String>();
+
+    let launch_error = |err: io::Error| {
+        PickerError::LaunchError(Message::CantLaunchPicker(&picker, &err).to_string())
+    };
+
+    let mut child = Command::new(picker.command())
+        .args([
+            "--delimiter",
+            "\t",
+            "--with-nth",
+            "2..",
+            "--height",
+            "40%",
+            "--prompt",
+            prompt,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => PickerError::NotInstalled(
+                Message::PickerNotInstalled(&picker, picker.command()).to_string(),
+            ),
+            _ => launch_error(err),
+        })?;
+
+    // The picker can finish before reading all variants, e.g. if the user cancelled it
+    if let Some(mut stdin) = child.stdin.take() {
+        match stdin.write_all(input.as_bytes()) {
+            Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(launch_error(err)),
+            _ => {}
+        }
+    }
+
+    let output = child.wait_with_output().map_err(launch_error)?;
+
+    match output.status.code() {
+        Some(0) => {}
+        // No match or the picker was interrupted by the user
+        Some(1 | 130) => return Ok(None),
+        _ => {
+            return Err(PickerError::LaunchError(
+                Message::PickerFailed(&picker, &output.status).to_string(),
+            ))
+        }
+    }
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let seq_num = output
+        .split('\t')
+        .next()
+        .and_then(|seq_num| seq_num.trim().parse::<usize>().ok())
+        .ok_or_else(|| {
+            PickerError::LaunchError(Message::UnexpectedPickerOutput(&picker, &output).to_string())
+        })?;
+
+    Ok(seq_num
+        .checked_sub(1)
+        .filter(|index| *index < variants.len()))
+}
@@ -1,23 +1,32 @@
-use super::output;
+use super::{output, picker, terminal::Terminal};
 
-use std::io;
+use crate::{dialog::common::state::ResultState, errors::PickerError, locale::Message};
 
-/// Reads a line from stdin
+use std::io::BufRead;
+use termcolor::WriteColor;
+
+/// Reads a line from the terminal input
 /// # Arguments
 /// * `empty_warning_msg` - A message to print if the input is empty, `None` if no message should be printed
 /// # Returns
 /// * `Some(String)` - The trimmed input
-/// * `None` - If the input is empty or an error occurred
+/// * `None` - If the input is empty, closed or an error occurred
 #[must_use]
-pub fn read_line_or_none(input_msg: &str, empty_warning_msg: Option<&str>) -> Option<String> {
-    let mut input = String::new();
-
+pub fn read_line_or_none<R, W>(
+    terminal: &mut Terminal<R, W>,
+    input_msg: &str,
+    empty_warning_msg: Option<&str>,
+) -> Option<String>
+where
+    R: BufRead,
+    W: WriteColor,
+{
     // Print the input message
-    output::input_msg(input_msg);
+    output::input_msg(terminal, input_msg);
 
-    // Read a line from stdin
-    match io::stdin().read_line(&mut input) {
-        Ok(_) => {
+    // Read a line from the terminal input
+    match terminal.read_line() {
+        Ok(Some(input)) => {
             let trimmed_input = input.trim();
 
             // If the input is empty
@@ -25,7 +34,7 @@ pub fn read_line_or_none(input_msg: &str, empty_warning_msg: Option<&str>) -> Op
                 // If a warning message should be printed
                 if let Some(msg) = empty_warning_msg {
                     // Print the warning message
-                    output::warning_msg(msg);
+                    output::warning_msg(terminal, msg);
                 }
                 return None;
             }
@@ -33,6 +42,75 @@ pub fn read_line_or_none(input_msg: &str, empty_warning_msg: Option<&str>) -> Op
             // Return the trimmed input
             Some(trimmed_input.to_string())
         }
-        Err(_) => None,
+        Ok(None) | Err(_) => None,
+    }
+}
+
+/// Reads a secret, e.g. a password, without echoing it, see [`Terminal::read_secret`]
+/// # Returns
+/// * `Some(String)` - The input without the line break
+/// * `None` - If the input is empty, closed, cancelled by `Esc` or `Ctrl-C` or an error occurred
+#[must_use]
+pub fn read_secret_or_none<R, W>(terminal: &mut Terminal<R, W>, input_msg: &str) -> Option<String>
+where
+    R: BufRead,
+    W: WriteColor,
+{
+    output::input_msg(terminal, input_msg);
+
+    match terminal.read_secret() {
+        // Spaces may be a part of the secret
+        Ok(Some(secret)) => Some(secret.trim_end_matches(['\r', '\n']).to_string())
+            .filter(|secret| !secret.is_empty()),
+        Ok(None) | Err(_) => None,
+    }
+}
+
+/// Selects a variant with the picker of the terminal
+/// # Returns
+/// * `Some(ResultState::Success(usize))` - Index of the selected variant
+/// * `Some(ResultState::Break)` - If the user cancelled the picker
+/// * `None` - If the terminal has no picker or the picker failed, so the built-in prompt should be used
+#[must_use]
+pub fn pick_or_none<R, W>(
+    terminal: &mut Terminal<R, W>,
+    input_msg: &str,
+    variants: &[String],
+) -> Option<ResultState<usize>>
+where
+    W: WriteColor,
+{
+    let picker = terminal.picker()?;
+
+    match picker::pick(picker, input_msg, variants) {
+        Ok(Some(index)) => Some(ResultState::Success(index)),
+        Ok(None) => Some(ResultState::Break),
+        Err(err) => {
+            if let PickerError::NotInstalled(_) = err {
+                terminal.disable_picker();
+            }
+            output::warning_msg(terminal, &format!("{}\n", Message::PickerFallback(&err)));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use termcolor::NoColor;
+
+    #[test]
+    fn secret_is_read_from_scripted_input() {
+        let mut terminal = Terminal::new(" pass word \n\n".as_bytes(), NoColor::new(Vec::new()));
+
+        let secret = read_secret_or_none(&mut terminal, "Password: ");
+        assert_eq!(secret.as_deref(), Some(" pass word "));
+        assert_eq!(read_secret_or_none(&mut terminal, "Password: "), None);
+        assert_eq!(read_secret_or_none(&mut terminal, "Password: "), None);
+
+        let transcript = String::from_utf8(terminal.output().get_ref().clone()).unwrap();
+        assert_eq!(transcript.matches("Password: ").count(), 3);
     }
 }
@@ -0,0 +1,16 @@
+use qrcode::{render::unicode::Dense1x2, QrCode};
+
+/// Render a QR code of the text with half-block characters, two modules per character. \
+/// Colors are inverted, so the code is readable on dark terminals
+#[must_use]
+pub fn render(text: &str) -> Option<String> {
+    let code = QrCode::new(text).ok()?;
+
+    Some(
+        code.render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .quiet_zone(true)
+            .build(),
+    )
+}
@@ -1,30 +1,65 @@
-use super::{output, prompt};
+use super::{interrupt::Interrupt, output, prompt, qr, terminal::Terminal};
 
 use crate::{
     dialog::common::{
+        data::Data,
+        languages::{count_sources_by_language, source_label, sources_of_language},
+        local::local_copy,
         state::{ResultState, State},
         state_machine::StateMachine,
+        stream::{checked_url_for_stream, describe_qualities, qualities_menu},
+        track::track_current_episode,
+        watched::mark_current_episode_watched,
     },
     enums::{
         language::Language,
         player::{players, Player},
     },
+    errors::SourceError,
+    locale::Message,
     players::mpv,
-    sources::base::Source,
+    server::relay::Relay,
+    sources::{
+        base::{find_in_anime_list, Source},
+        search::{search_all, Hit, Query, Search},
+        stream::StreamCheck,
+    },
+    storage::{downloads::Downloads, history::History},
+    tracker::Tracker,
 };
 
-use std::collections::HashMap;
+use std::io::BufRead;
+use termcolor::WriteColor;
 
-pub fn run<S>(sources: &[S])
-where
+/// Run the dialog
+/// # Arguments
+/// * `downloads` - Downloaded episodes, they're played from disk instead of streaming
+/// * `tracker` - Tracker whose progress is updated when an episode is watched, `None` to not track progress
+/// * `relay` - Relay to serve selected episodes instead of launching a player
+/// * `favorites` - Select anime from favorites of the logged in accounts instead of searching by name
+#[allow(clippy::too_many_arguments)]
+pub async fn run<S, T, R, W>(
+    sources: &[S],
+    terminal: &mut Terminal<R, W>,
+    history: &mut History,
+    downloads: &Downloads,
+    tracker: Option<&T>,
+    stream_check: &StreamCheck,
+    relay: Option<&Relay>,
+    favorites: bool,
+) where
     S: Source,
+    T: Tracker,
+    R: BufRead,
+    W: WriteColor,
 {
     let mut state_machine = StateMachine::default();
+    let interrupt = Interrupt::listen();
 
     loop {
         match state_machine.current_state() {
             State::SelectLanguage => {
-                match select_language(sources.iter().map(Source::language).collect()) {
+                match select_language(terminal, &count_sources_by_language(sources)) {
                     ResultState::Success(language) => {
                         state_machine.data().set_language(language);
                         state_machine.set_state(State::SelectSource);
@@ -33,27 +68,32 @@ where
                 }
             }
             State::SelectSource => {
-                let language = state_machine.data().language();
+                let language = state_machine.data().language().clone();
                 match select_source(
-                    &sources
-                        .iter()
-                        .filter(|source| source.language().eq(language))
-                        .collect::<Vec<&S>>(),
+                    terminal,
+                    &sources_of_language(sources, &language),
+                    &language,
                 ) {
-                    ResultState::Success(source) => {
-                        state_machine.data().set_source(source.clone());
+                    ResultState::Success(sources) => {
+                        state_machine
+                            .data()
+                            .set_sources(sources.into_iter().cloned().collect());
                         state_machine.set_state(State::SelectAnime);
                     }
                     ResultState::Break => state_machine.set_previous_state(),
                 }
             }
             State::SelectAnime => {
-                let source = state_machine.data().source_mut().unwrap();
+                let data = state_machine.data();
 
-                match select_anime(source) {
+                match select_anime(terminal, &interrupt, data, favorites).await {
                     ResultState::Success(_) => {
+                        let source = data.source().unwrap();
                         let anime_info = source.anime_info().expect("Anime isn't set");
-                        output::info_msg(&format!("\tSelected anime `{anime_info}`\n"));
+                        output::info_msg(
+                            terminal,
+                            &format!("\t{}\n", Message::SelectedAnime(&anime_info)),
+                        );
 
                         state_machine.set_state(State::SelectEpisode);
                     }
@@ -63,10 +103,13 @@ where
             State::SelectEpisode => {
                 let source = state_machine.data().source_mut().unwrap();
 
-                match select_episode(source) {
+                match select_episode(terminal, source).await {
                     ResultState::Success(_) => {
                         let episode_info = source.episode_info().expect("Episode isn't set");
-                        output::info_msg(&format!("\tSelected episode `{episode_info}`\n"));
+                        output::info_msg(
+                            terminal,
+                            &format!("\t{}\n", Message::SelectedEpisode(&episode_info)),
+                        );
 
                         state_machine.set_state(State::SelectQuality);
                     }
@@ -76,14 +119,18 @@ where
             State::SelectQuality => {
                 let source = state_machine.data().source_mut().unwrap();
 
-                match select_quality(source) {
+                match select_quality(terminal, stream_check, source).await {
+                    // The relay is used instead of a player
+                    ResultState::Success(_) if relay.is_some() => {
+                        state_machine.set_state(State::LaunchPlayer);
+                    }
                     ResultState::Success(_) => {
                         state_machine.set_state(State::SelectPlayer);
                     }
                     ResultState::Break => state_machine.set_previous_state(),
                 }
             }
-            State::SelectPlayer => match select_player() {
+            State::SelectPlayer => match select_player(terminal) {
                 ResultState::Success(player) => {
                     state_machine.data().set_player(player);
                     state_machine.set_state(State::LaunchPlayer);
@@ -93,11 +140,37 @@ where
             State::LaunchPlayer => {
                 let data = state_machine.data();
 
-                let player = data.player().unwrap().clone();
-                let source = data.source_mut().unwrap();
+                let result = if let Some(relay) = relay {
+                    let source = data.source_mut().unwrap();
+
+                    serve_stream(
+                        terminal,
+                        history,
+                        tracker,
+                        stream_check,
+                        source,
+                        relay,
+                        &interrupt,
+                    )
+                    .await
+                } else {
+                    let player = data.player().unwrap().clone();
+                    let source = data.source_mut().unwrap();
+
+                    launch_player(
+                        terminal,
+                        history,
+                        downloads,
+                        tracker,
+                        stream_check,
+                        source,
+                        &player,
+                    )
+                    .await
+                };
 
-                match launch_player(source, &player) {
-                    ResultState::Success(_) => match select_state() {
+                match result {
+                    ResultState::Success(_) => match select_state(terminal) {
                         ResultState::Success(State::SelectAnime) => {
                             state_machine.set_previous_state_and_truncate_next(State::SelectAnime);
                         }
@@ -116,59 +189,94 @@ where
                 }
             }
         }
-        println!();
+        writeln!(terminal.output()).unwrap();
     }
 
-    finish();
+    finish(terminal);
 }
 
-pub fn finish() {
-    output::info_msg("\nBye, peach!\n");
+pub fn finish<R, W>(terminal: &mut Terminal<R, W>)
+where
+    W: WriteColor,
+{
+    output::info_msg(terminal, &format!("\n{}\n", Message::Bye));
 }
 
+/// Select a language of sources or all languages
+/// # Arguments
+/// * `languages` - Languages with counts of their sources
 #[must_use]
-fn select_language(sources_languages: Vec<&Language>) -> ResultState<Language> {
-    let mut languages: HashMap<&Language, u16> = HashMap::new();
-
-    for language in sources_languages {
-        languages
-            .entry(language)
-            .and_modify(|count| *count += 1)
-            .or_insert(1);
+fn select_language<R, W>(
+    terminal: &mut Terminal<R, W>,
+    languages: &[(Language, usize)],
+) -> ResultState<Language>
+where
+    R: BufRead,
+    W: WriteColor,
+{
+    let variants = languages
+        .iter()
+        .map(|(language, count)| Message::LanguageSources(language, *count).to_string())
+        .collect::<Vec<_>>();
+    if let Some(result) = prompt::pick_or_none(
+        terminal,
+        &format!("{}: ", Message::SelectLanguage),
+        &variants,
+    ) {
+        return match result {
+            ResultState::Success(index) => ResultState::Success(languages[index].0.clone()),
+            ResultState::Break => ResultState::Break,
+        };
     }
 
-    let mut languages: Vec<(&Language, u16)> = languages.into_iter().collect();
-    languages.sort_by(|(_, a), (_, b)| b.cmp(a));
-
-    output::variant_headline_msg("Available languages");
-    output::info_msg(" (enter empty input to back previous state):\n");
+    output::variant_headline_msg(terminal, &Message::AvailableLanguages.to_string());
+    output::info_msg(terminal, &format!(" ({}):\n", Message::BackOnEmptyInput));
 
     for (seq_num, (language, count)) in languages.iter().enumerate() {
-        output::variant_msg(&format!(
-            "\t{seq_num}. {language} ({count} sources)\n",
-            seq_num = seq_num + 1
-        ));
+        output::variant_msg(
+            terminal,
+            &format!(
+                "\t{seq_num}. {}\n",
+                Message::LanguageSources(language, *count),
+                seq_num = seq_num + 1
+            ),
+        );
     }
 
     loop {
-        return match prompt::read_line_or_none("Select a language: ", None) {
+        return match prompt::read_line_or_none(
+            terminal,
+            &format!("{}: ", Message::SelectLanguage),
+            None,
+        ) {
             Some(lang_or_seq_num) => match Language::try_from(lang_or_seq_num.as_str()) {
-                Ok(language) => ResultState::Success(language),
+                Ok(language) => {
+                    if languages.iter().any(|(known, _)| known == &language) {
+                        ResultState::Success(language)
+                    } else {
+                        output::warning_msg(
+                            terminal,
+                            &format!("{}\n", Message::NoSourcesOfLanguage(&language)),
+                        );
+                        continue;
+                    }
+                }
                 Err(err) => {
                     if let Ok(seq_num) = lang_or_seq_num.parse::<usize>() {
                         if let Some((language, _)) = seq_num
                             .checked_sub(1)
                             .and_then(|seq_num| languages.get(seq_num))
                         {
-                            ResultState::Success((*language).clone())
+                            ResultState::Success(language.clone())
                         } else {
-                            output::warning_msg(&format!(
-                                "Unknown language sequence number `{seq_num}`\n"
-                            ));
+                            output::warning_msg(
+                                terminal,
+                                &format!("{}\n", Message::UnknownLanguageSeqNum(seq_num)),
+                            );
                             continue;
                         }
                     } else {
-                        output::warning_msg(&format!("{err}\n"));
+                        output::warning_msg(terminal, &format!("{err}\n"));
                         continue;
                     }
                 }
@@ -178,39 +286,87 @@ fn select_language(sources_languages: Vec<&Language>) -> ResultState<Language> {
     }
 }
 
+/// Select a source or all sources to search anime in all of them at once
+/// # Arguments
+/// * `sources` - Sources of the language
+/// * `language` - Selected language, sources are tagged with their languages in [`Language::All`] mode
 #[must_use]
-fn select_source<'a, S>(sources: &[&'a S]) -> ResultState<&'a S>
+fn select_source<'a, S, R, W>(
+    terminal: &mut Terminal<R, W>,
+    sources: &[&'a S],
+    language: &Language,
+) -> ResultState<Vec<&'a S>>
 where
     S: Source,
+    R: BufRead,
+    W: WriteColor,
 {
-    output::variant_headline_msg("Available sources:\n");
+    let all_sources_seq_num = sources.len() + 1;
 
-    for (seq_num, source) in sources.iter().enumerate() {
-        output::variant_msg(&format!("\t{seq_num}. {source}\n", seq_num = seq_num + 1));
+    let mut variants = sources
+        .iter()
+        .map(|source| source_label(*source, language))
+        .collect::<Vec<_>>();
+    variants.push(Message::AllSources.to_string());
+    if let Some(result) =
+        prompt::pick_or_none(terminal, &format!("{}: ", Message::SelectSource), &variants)
+    {
+        return match result {
+            ResultState::Success(index) => match sources.get(index) {
+                Some(source) => ResultState::Success(vec![*source]),
+                None => ResultState::Success(sources.to_vec()),
+            },
+            ResultState::Break => ResultState::Break,
+        };
     }
 
+    output::variant_headline_msg(terminal, &format!("{}:\n", Message::AvailableSources));
+
+    for (seq_num, variant) in variants.iter().take(sources.len()).enumerate() {
+        output::variant_msg(
+            terminal,
+            &format!("\t{seq_num}. {variant}\n", seq_num = seq_num + 1),
+        );
+    }
+    output::variant_msg(
+        terminal,
+        &format!("\t{all_sources_seq_num}. {}\n", Message::AllSources),
+    );
+
     loop {
-        return match prompt::read_line_or_none("Select a source: ", None) {
+        return match prompt::read_line_or_none(
+            terminal,
+            &format!("{}: ", Message::SelectSource),
+            None,
+        ) {
             Some(source_name_or_seq_num) => {
                 if let Some(source) = sources
                     .iter()
                     .find(|source| (**source).eq(&source_name_or_seq_num))
                 {
-                    ResultState::Success(source)
+                    ResultState::Success(vec![*source])
+                } else if source_name_or_seq_num.to_lowercase() == "all" {
+                    ResultState::Success(sources.to_vec())
                 } else if let Ok(seq_num) = source_name_or_seq_num.parse::<usize>() {
                     if let Some(source) = seq_num
                         .checked_sub(1)
                         .and_then(|seq_num| sources.get(seq_num))
                     {
-                        ResultState::Success(source)
+                        ResultState::Success(vec![*source])
+                    } else if seq_num == all_sources_seq_num {
+                        ResultState::Success(sources.to_vec())
                     } else {
-                        output::warning_msg(&format!(
-                            "Unknown source sequence number `{seq_num}`\n"
-                        ));
+                        output::warning_msg(
+                            terminal,
+                            &format!("{}\n", Message::UnknownSourceSeqNum(seq_num)),
+                        );
                         continue;
                     }
                 } else {
-                    output::warning_msg(&format!("Unknown source `{source_name_or_seq_num}`\n"));
+                    output::warning_msg(
+                        terminal,
+                        &format!("{}\n", Message::UnknownSource(&source_name_or_seq_num)),
+                    );
                     continue;
                 }
             }
@@ -219,61 +375,206 @@ where
     }
 }
 
-fn select_anime<S>(source: &mut S) -> ResultState<()>
+/// Search anime in the selected sources and select one of the found anime. \
+/// The source of the selected anime is set as current source
+/// # Arguments
+/// * `favorites` - List favorites of the logged in accounts instead of asking the anime name
+async fn select_anime<S, R, W>(
+    terminal: &mut Terminal<R, W>,
+    interrupt: &Interrupt,
+    data: &mut Data<S>,
+    favorites: bool,
+) -> ResultState<()>
 where
     S: Source,
+    R: BufRead,
+    W: WriteColor,
 {
     loop {
-        let anime_name = match prompt::read_line_or_none("Enter anime name: ", None) {
-            Some(anime_name) => anime_name,
-            None => return ResultState::Break,
+        let anime_name = if favorites {
+            None
+        } else {
+            match prompt::read_line_or_none(
+                terminal,
+                &format!("{}: ", Message::EnterAnimeName),
+                None,
+            ) {
+                Some(anime_name) => Some(anime_name),
+                None => return ResultState::Break,
+            }
         };
+        let search_query = anime_name.as_deref().map_or(Query::Favorites, Query::Name);
 
-        let anime_list_info = match source.search_anime_list(&anime_name) {
-            Ok(anime_list_info) => anime_list_info,
-            Err(err) => {
-                output::error_msg(&format!("{err}\n"));
+        let sources = data.sources_mut();
+        // Show the source of each anime only if there are several sources
+        let tagged = sources.len() > 1;
+        let print_hits = terminal.picker().is_none();
+
+        output::info_msg(terminal, &format!("\t{}\n", Message::Searching));
+        output::variant_headline_msg(terminal, &format!("{}:\n", Message::AnimeList));
+
+        let mut seq_num = 0;
+        // Whether any source has loaded its favorites
+        let mut has_favorites = false;
+        let search = search_all(
+            sources,
+            search_query,
+            interrupt.interrupted(),
+            |source, result| match result {
+                Ok(hits) => {
+                    has_favorites = true;
+                    if !print_hits {
+                        return;
+                    }
+                    for hit in hits {
+                        seq_num += 1;
+                        output::variant_msg(
+                            terminal,
+                            &format!("\t{seq_num}. {}\n", hit_label(hit, source, tagged)),
+                        );
+                    }
+                }
+                Err(err) if tagged => output::error_msg(terminal, &format!("{source}: {err}\n")),
+                Err(err) => output::error_msg(terminal, &format!("{err}\n")),
+            },
+        )
+        .await;
+
+        if let Search::Cancelled(_) = search {
+            output::warning_msg(terminal, &format!("{}\n", Message::SearchCancelled));
+        }
+
+        let hits = search.into_hits();
+        if hits.is_empty() {
+            if search_query != Query::Favorites {
                 continue;
             }
-        };
+            let message = if has_favorites {
+                Message::EmptyFavorites
+            } else {
+                Message::NoFavorites
+            };
+            output::warning_msg(terminal, &format!("{message}\n"));
+            // Favorites won't appear by listing them again
+            return ResultState::Break;
+        }
 
-        output::variant_headline_msg(&format!("Anime list:\n{anime_list_info}"));
+        let sources = data.sources_mut();
+        let variants = hits
+            .iter()
+            .map(|hit| hit_label(hit, &sources[hit.source_index], tagged))
+            .collect::<Vec<_>>();
 
-        loop {
-            return match prompt::read_line_or_none("Select anime: ", None) {
-                Some(anime_name_or_seq_num) => {
-                    if let Err(err) = source.select_anime_as_current(anime_name_or_seq_num) {
-                        output::warning_msg(&format!("{err}\n"));
-                        continue;
-                    }
+        let hit = if let Some(result) =
+            prompt::pick_or_none(terminal, &format!("{}: ", Message::SelectAnime), &variants)
+        {
+            match result {
+                ResultState::Success(index) => &hits[index],
+                ResultState::Break => return ResultState::Break,
+            }
+        } else {
+            loop {
+                let Some(anime_name_or_seq_num) = prompt::read_line_or_none(
+                    terminal,
+                    &format!("{}: ", Message::SelectAnime),
+                    None,
+                ) else {
+                    return ResultState::Break;
+                };
 
-                    ResultState::Success(())
+                match find_hit(&hits, &anime_name_or_seq_num) {
+                    Ok(hit) => break hit,
+                    Err(err) => output::warning_msg(terminal, &format!("{err}\n")),
                 }
-                None => ResultState::Break,
-            };
+            }
+        };
+
+        let source = &mut sources[hit.source_index];
+        if let Err(err) = source
+            .select_anime_as_current((hit.anime_index + 1).to_string())
+            .await
+        {
+            output::warning_msg(terminal, &format!("{err}\n"));
+            continue;
         }
+
+        let source = source.clone();
+        data.set_source(source);
+
+        return ResultState::Success(());
     }
 }
 
-fn select_episode<S>(source: &mut S) -> ResultState<()>
+fn hit_label<S>(hit: &Hit, source: &S, tagged: bool) -> String
+where
+    S: Source,
+{
+    if tagged {
+        format!("[{source}] {title}", title = hit.title)
+    } else {
+        hit.title.clone()
+    }
+}
+
+/// Find a hit by its sequence number or a part of its title
+fn find_hit<'a>(hits: &'a [Hit], title_or_seq_num: &str) -> Result<&'a Hit, SourceError> {
+    find_in_anime_list(hits, title_or_seq_num, |hit, title| {
+        hit.title.to_lowercase().contains(title)
+    })
+}
+
+async fn select_episode<S, R, W>(terminal: &mut Terminal<R, W>, source: &mut S) -> ResultState<()>
 where
     S: Source,
+    R: BufRead,
+    W: WriteColor,
 {
     let episode_list_info = match source.episodes_info() {
         Ok(episode_list_info) => episode_list_info,
         Err(err) => {
-            output::error_msg(&format!("{err}"));
+            output::error_msg(terminal, &format!("{err}"));
             return ResultState::Break;
         }
     };
 
-    output::variant_headline_msg(&format!("Episodes: {episode_list_info}"));
+    if let Ok(episodes) = source.episodes() {
+        if let Some(result) = prompt::pick_or_none(
+            terminal,
+            &format!("{}: ", Message::SelectEpisode),
+            &episodes,
+        ) {
+            return match result {
+                ResultState::Success(index) => {
+                    match source
+                        .select_episode_as_current(episodes[index].clone())
+                        .await
+                    {
+                        Ok(()) => ResultState::Success(()),
+                        Err(err) => {
+                            output::warning_msg(terminal, &format!("{err}\n"));
+                            ResultState::Break
+                        }
+                    }
+                }
+                ResultState::Break => ResultState::Break,
+            };
+        }
+    }
+
+    output::variant_headline_msg(terminal, &Message::Episodes(&episode_list_info).to_string());
 
     loop {
-        return match prompt::read_line_or_none("Select an episode: ", None) {
+        return match prompt::read_line_or_none(
+            terminal,
+            &format!("{}: ", Message::SelectEpisode),
+            None,
+        ) {
             Some(episode_name_or_seq_num) => {
-                if let Err(err) = source.select_episode_as_current(episode_name_or_seq_num) {
-                    output::warning_msg(&format!("{err}\n"));
+                if let Err(err) = source
+                    .select_episode_as_current(episode_name_or_seq_num)
+                    .await
+                {
+                    output::warning_msg(terminal, &format!("{err}\n"));
                     continue;
                 }
 
@@ -284,25 +585,69 @@ where
     }
 }
 
-fn select_quality<S>(source: &mut S) -> ResultState<()>
+async fn select_quality<S, R, W>(
+    terminal: &mut Terminal<R, W>,
+    stream_check: &StreamCheck,
+    source: &mut S,
+) -> ResultState<()>
 where
     S: Source,
+    R: BufRead,
+    W: WriteColor,
 {
     let quality_list_info = match source.qualities_info() {
         Ok(quality_list_info) => quality_list_info,
         Err(err) => {
-            output::error_msg(&format!("{err}"));
+            output::error_msg(terminal, &format!("{err}"));
             return ResultState::Break;
         }
     };
 
-    output::variant_headline_msg(&format!("Qualities:\n{quality_list_info}"));
+    if stream_check.is_enabled() {
+        output::info_msg(terminal, &format!("\t{}\n", Message::DescribingStreams));
+    }
+    let qualities = describe_qualities(source, stream_check).await;
+    let variants = qualities
+        .iter()
+        .map(|(quality, info)| match info {
+            Some(info) => format!("{quality} | {info}"),
+            None => quality.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    if !qualities.is_empty() {
+        if let Some(result) = prompt::pick_or_none(
+            terminal,
+            &format!("{}: ", Message::SelectQuality),
+            &variants,
+        ) {
+            return match result {
+                ResultState::Success(index) => {
+                    match source.select_quality_as_current(qualities[index].0.clone()) {
+                        Ok(()) => ResultState::Success(()),
+                        Err(err) => {
+                            output::warning_msg(terminal, &format!("{err}\n"));
+                            ResultState::Break
+                        }
+                    }
+                }
+                ResultState::Break => ResultState::Break,
+            };
+        }
+    }
+
+    let menu = qualities_menu(&qualities).unwrap_or_else(|| quality_list_info.to_string());
+    output::variant_headline_msg(terminal, &format!("{}:\n{menu}", Message::Qualities));
 
     loop {
-        return match prompt::read_line_or_none("Select a quality: ", None) {
+        return match prompt::read_line_or_none(
+            terminal,
+            &format!("{}: ", Message::SelectQuality),
+            None,
+        ) {
             Some(quality_name_or_seq_num) => {
                 if let Err(err) = source.select_quality_as_current(quality_name_or_seq_num) {
-                    output::warning_msg(&format!("{err}\n"));
+                    output::warning_msg(terminal, &format!("{err}\n"));
                     continue;
                 }
 
@@ -313,17 +658,44 @@ where
     }
 }
 
-fn select_player() -> ResultState<Player> {
+pub(super) fn select_player<R, W>(terminal: &mut Terminal<R, W>) -> ResultState<Player>
+where
+    R: BufRead,
+    W: WriteColor,
+{
     let players = players();
 
-    output::variant_headline_msg("Available players:\n");
+    let variants = players.iter().map(ToString::to_string).collect::<Vec<_>>();
+    while let Some(result) =
+        prompt::pick_or_none(terminal, &format!("{}: ", Message::SelectPlayer), &variants)
+    {
+        let ResultState::Success(index) = result else {
+            return ResultState::Break;
+        };
+
+        let player = players[index].clone();
+        if is_player_installed(&player) {
+            return ResultState::Success(player);
+        }
+
+        output::error_msg(terminal, &format!("{}\n", player.doc()));
+    }
+
+    output::variant_headline_msg(terminal, &format!("{}:\n", Message::AvailablePlayers));
 
     for (seq_num, player) in players.iter().enumerate() {
-        output::variant_msg(&format!("\t{seq_num}. {player}\n", seq_num = seq_num + 1));
+        output::variant_msg(
+            terminal,
+            &format!("\t{seq_num}. {player}\n", seq_num = seq_num + 1),
+        );
     }
 
     loop {
-        let player = match prompt::read_line_or_none("Select a player: ", None) {
+        let player = match prompt::read_line_or_none(
+            terminal,
+            &format!("{}: ", Message::SelectPlayer),
+            None,
+        ) {
             Some(player_name_or_seq_num) => match Player::try_from(player_name_or_seq_num.as_str())
             {
                 Ok(player) => player,
@@ -335,13 +707,14 @@ fn select_player() -> ResultState<Player> {
                         {
                             player.clone()
                         } else {
-                            output::warning_msg(&format!(
-                                "Unknown player sequence number `{seq_num}`\n"
-                            ));
+                            output::warning_msg(
+                                terminal,
+                                &format!("{}\n", Message::UnknownPlayerSeqNum(seq_num)),
+                            );
                             continue;
                         }
                     } else {
-                        output::warning_msg(&format!("{err}\n"));
+                        output::warning_msg(terminal, &format!("{err}\n"));
                         continue;
                     }
                 }
@@ -349,86 +722,362 @@ fn select_player() -> ResultState<Player> {
             None => return ResultState::Break,
         };
 
-        match player {
-            Player::Mpv => {
-                if mpv::is_installed() {
-                    return ResultState::Success(player);
-                }
-            }
+        if is_player_installed(&player) {
+            return ResultState::Success(player);
         }
 
-        output::error_msg(&format!("{}\n", player.doc()));
+        output::error_msg(terminal, &format!("{}\n", player.doc()));
+    }
+}
+
+fn is_player_installed(player: &Player) -> bool {
+    match player {
+        Player::Mpv => mpv::is_installed(),
     }
 }
 
-fn launch_player<S>(source: &mut S, player: &Player) -> ResultState<()>
+/// Launch the player with the stream of current episode
+/// or with its downloaded file if the episode is downloaded
+async fn launch_player<S, T, R, W>(
+    terminal: &mut Terminal<R, W>,
+    history: &mut History,
+    downloads: &Downloads,
+    tracker: Option<&T>,
+    stream_check: &StreamCheck,
+    source: &mut S,
+    player: &Player,
+) -> ResultState<()>
 where
     S: Source,
+    T: Tracker,
+    W: WriteColor,
 {
-    let url = match source.url_for_stream() {
-        Ok(url) => url,
-        Err(err) => {
-            output::error_msg(&format!("{err}"));
-            return ResultState::Break;
+    let url = if let Some(path) = local_copy(downloads, source) {
+        output::info_msg(
+            terminal,
+            &format!("{}\n", Message::PlayingLocalCopy(&path.display())),
+        );
+        path.to_string_lossy().into_owned()
+    } else {
+        output::info_msg(terminal, &format!("{}\n", Message::CheckingStream));
+
+        match checked_url_for_stream(source, stream_check, |quality, next, err| {
+            output::warning_msg(
+                terminal,
+                &format!("{}\n", Message::QualityFallback(quality, next, err)),
+            );
+        })
+        .await
+        {
+            Ok(url) => url,
+            Err(err) => {
+                output::error_msg(terminal, &format!("{err}"));
+                return ResultState::Break;
+            }
         }
     };
 
-    output::info_msg("Launch the process! Wait opening...\n");
+    output::info_msg(terminal, &format!("{}\n", Message::LaunchingPlayer));
 
     match player {
         Player::Mpv => {
             if let Err(err) = mpv::launch(&url) {
-                output::error_msg(&format!("{err}"));
+                output::error_msg(terminal, &format!("{err}"));
                 return ResultState::Break;
             }
         }
     }
 
-    output::info_msg("Process finished!\n\n");
+    if let Err(err) = mark_current_episode_watched(history, source) {
+        output::warning_msg(terminal, &format!("{}\n", Message::CantSaveHistory(&err)));
+    }
+    if let Some(tracker) = tracker {
+        track_progress(terminal, tracker, source).await;
+    }
+
+    output::info_msg(terminal, &format!("{}\n\n", Message::PlayerFinished));
 
     ResultState::Success(())
 }
 
-fn select_state() -> ResultState<State> {
+/// Relay the stream of the current episode until the relay is idle or `Ctrl-C` is pressed
+async fn serve_stream<S, T, R, W>(
+    terminal: &mut Terminal<R, W>,
+    history: &mut History,
+    tracker: Option<&T>,
+    stream_check: &StreamCheck,
+    source: &mut S,
+    relay: &Relay,
+    interrupt: &Interrupt,
+) -> ResultState<()>
+where
+    S: Source,
+    T: Tracker,
+    W: WriteColor,
+{
+    output::info_msg(terminal, &format!("{}\n", Message::CheckingStream));
+
+    let url = match checked_url_for_stream(source, stream_check, |quality, next, err| {
+        output::warning_msg(
+            terminal,
+            &format!("{}\n", Message::QualityFallback(quality, next, err)),
+        );
+    })
+    .await
+    {
+        Ok(url) => url,
+        Err(err) => {
+            output::error_msg(terminal, &format!("{err}"));
+            return ResultState::Break;
+        }
+    };
+
+    let serving = match relay.bind(&url) {
+        Ok(serving) => serving,
+        Err(err) => {
+            output::error_msg(terminal, &format!("{err}\n"));
+            return ResultState::Break;
+        }
+    };
+
+    let urls = serving.urls();
+    for url in &urls {
+        output::info_msg(terminal, &format!("{}\n", Message::RelayUrl(url)));
+    }
+    // The URL for other devices is the last one
+    if let Some(code) = urls
+        .last()
+        .filter(|_| relay.qr())
+        .and_then(|url| qr::render(url))
+    {
+        output::variant_msg(terminal, &format!("{code}\n"));
+    }
+    output::info_msg(
+        terminal,
+        &format!(
+            "{}\n",
+            Message::RelayHint(&relay.idle_timeout().as_secs().div_ceil(60))
+        ),
+    );
+
+    if let Err(err) = mark_current_episode_watched(history, source) {
+        output::warning_msg(terminal, &format!("{}\n", Message::CantSaveHistory(&err)));
+    }
+    if let Some(tracker) = tracker {
+        track_progress(terminal, tracker, source).await;
+    }
+
+    if let Err(err) = serving.run(interrupt.interrupted()).await {
+        output::error_msg(terminal, &format!("{err}\n"));
+        return ResultState::Break;
+    }
+
+    output::info_msg(terminal, &format!("{}\n\n", Message::RelayStopped));
+
+    ResultState::Success(())
+}
+
+/// Update progress of current anime on the tracker and print the result
+async fn track_progress<S, T, R, W>(terminal: &mut Terminal<R, W>, tracker: &T, source: &S)
+where
+    S: Source,
+    T: Tracker,
+    W: WriteColor,
+{
+    match track_current_episode(tracker, source).await {
+        Ok(Some(progress)) => output::info_msg(terminal, &format!("{progress}\n")),
+        Ok(None) => {}
+        Err(err) => output::warning_msg(
+            terminal,
+            &format!("{}\n", Message::CantUpdateProgress(tracker.name(), &err)),
+        ),
+    }
+}
+
+fn select_state<R, W>(terminal: &mut Terminal<R, W>) -> ResultState<State>
+where
+    R: BufRead,
+    W: WriteColor,
+{
     let states = [
         State::SelectAnime,
         State::SelectEpisode,
         State::SelectQuality,
     ];
 
-    output::variant_headline_msg("What do you want to do next?");
-    output::info_msg(" (enter empty input to back previous state)\n");
+    let variants = states
+        .iter()
+        .map(|state| Message::StateName(state).to_string())
+        .collect::<Vec<_>>();
+    if let Some(result) =
+        prompt::pick_or_none(terminal, &format!("{}: ", Message::SelectState), &variants)
+    {
+        return match result {
+            ResultState::Success(index) => ResultState::Success(states[index].clone()),
+            ResultState::Break => ResultState::Break,
+        };
+    }
+
+    output::variant_headline_msg(terminal, &Message::WhatNext.to_string());
+    output::info_msg(terminal, &format!(" ({})\n", Message::BackOnEmptyInput));
 
-    for (seq_num, state) in states.iter().enumerate() {
-        output::variant_msg(&format!("\t{seq_num}. {state}\n", seq_num = seq_num + 1));
+    for (seq_num, variant) in variants.iter().enumerate() {
+        output::variant_msg(
+            terminal,
+            &format!("\t{seq_num}. {variant}\n", seq_num = seq_num + 1),
+        );
     }
 
     loop {
-        let state = match prompt::read_line_or_none("Select a state: ", None) {
-            Some(state_name_or_seq_num) => match State::try_from(state_name_or_seq_num.as_str()) {
-                Ok(state) => state,
-                Err(err) => {
-                    if let Ok(seq_num) = state_name_or_seq_num.parse::<usize>() {
-                        if let Some(state) = seq_num
-                            .checked_sub(1)
-                            .and_then(|seq_num| states.get(seq_num))
-                        {
-                            state.clone()
-                        } else {
-                            output::warning_msg(&format!(
-                                "Unknown state sequence number `{seq_num}`\n"
-                            ));
-                            continue;
+        let state =
+            match prompt::read_line_or_none(terminal, &format!("{}: ", Message::SelectState), None)
+            {
+                Some(state_name_or_seq_num) => {
+                    match State::try_from(state_name_or_seq_num.as_str()) {
+                        Ok(state) => state,
+                        Err(err) => {
+                            if let Ok(seq_num) = state_name_or_seq_num.parse::<usize>() {
+                                if let Some(state) = seq_num
+                                    .checked_sub(1)
+                                    .and_then(|seq_num| states.get(seq_num))
+                                {
+                                    state.clone()
+                                } else {
+                                    output::warning_msg(
+                                        terminal,
+                                        &format!("{}\n", Message::UnknownStateSeqNum(seq_num)),
+                                    );
+                                    continue;
+                                }
+                            } else {
+                                output::warning_msg(terminal, &format!("{err}\n"));
+                                continue;
+                            }
                         }
-                    } else {
-                        output::warning_msg(&format!("{err}\n"));
-                        continue;
                     }
                 }
-            },
-            None => return ResultState::Break,
-        };
+                None => return ResultState::Break,
+            };
 
         return ResultState::Success(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::MockSource, tracker::shikimori::Shikimori};
+
+    use termcolor::NoColor;
+
+    /// Run the dialog on scripted input and get the transcript
+    async fn run_script(sources: &[MockSource], input: &str) -> String {
+        run_script_with_favorites(sources, input, false).await
+    }
+
+    async fn run_script_with_favorites(
+        sources: &[MockSource],
+        input: &str,
+        favorites: bool,
+    ) -> String {
+        let mut terminal = Terminal::new(input.as_bytes(), NoColor::new(Vec::new()));
+
+        run(
+            sources,
+            &mut terminal,
+            &mut History::new(None),
+            &Downloads::new(None),
+            None::<&Shikimori>,
+            &StreamCheck::disabled(),
+            None,
+            favorites,
+        )
+        .await;
+
+        String::from_utf8(terminal.output().get_ref().clone()).unwrap()
+    }
+
+    fn sources() -> Vec<MockSource> {
+        vec![MockSource::new("Mock", "http://127.0.0.1")
+            .with_anime("Мадока | Madoka", &["1", "2"])
+            .with_anime("Ева | Evangelion", &["1"])]
+    }
+
+    #[tokio::test]
+    async fn selects_anime_episode_and_quality() {
+        let transcript = run_script(&sources(), "ru\n1\nmadoka\n1\n2\n720p\n").await;
+
+        assert!(transcript.contains("\t1. Мадока | Madoka\n"));
+        assert!(transcript.contains("Selected anime `Мадока | Madoka`"));
+        assert!(transcript.contains("Selected episode `2`"));
+        assert!(transcript.contains(&format!("> {}: ", Message::SelectPlayer)));
+        assert!(transcript.ends_with(&format!("\n{}\n", Message::Bye)));
+    }
+
+    #[tokio::test]
+    async fn empty_input_returns_to_previous_state() {
+        // Back from the player to the quality and the episode, then another episode is selected
+        let transcript = run_script(&sources(), "ru\n1\nmadoka\n1\n1\n480p\n\n\n2\n").await;
+
+        let selected = transcript
+            .lines()
+            .filter_map(|line| {
+                line.split('\t')
+                    .find(|part| part.starts_with("Selected episode"))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(selected, ["Selected episode `1`", "Selected episode `2`"]);
+
+        let prompts = transcript
+            .split("> ")
+            .filter_map(|prompt| prompt.split(':').next())
+            .filter(|prompt| prompt.starts_with("Select"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prompts[5..9],
+            [
+                "Select a player",
+                "Select a quality",
+                "Select an episode",
+                "Select a quality"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_variants_are_asked_again() {
+        let transcript = run_script(&sources(), "ru\n7\n1\nnaruto\nmadoka\n3\n1\n").await;
+
+        assert!(transcript.contains(&Message::UnknownSourceSeqNum(7).to_string()));
+        assert!(transcript.contains(&Message::EmptyAnimeList("naruto").to_string()));
+        assert!(transcript.contains(&Message::UnknownAnimeSeqNum(&3).to_string()));
+        assert!(transcript.contains("Selected anime `Мадока | Madoka`"));
+    }
+
+    #[tokio::test]
+    async fn hit_is_selected_by_number_when_titles_contain_digits() {
+        let sources = [MockSource::new("Mock", "http://127.0.0.1")
+            .with_anime("Gintama 2", &["1"])
+            .with_anime("Gintama", &["1", "2"])];
+        let transcript = run_script(&sources, "ru\n1\ngintama\n2\n").await;
+
+        assert!(transcript.contains("Selected anime `Gintama`"));
+    }
+
+    #[tokio::test]
+    async fn anime_is_selected_from_favorites() {
+        let logged_in = [sources().remove(0).with_favorites(&["Ева | Evangelion"])];
+        let transcript = run_script_with_favorites(&logged_in, "ru\n1\n1\n", true).await;
+
+        assert!(!transcript.contains(&Message::EnterAnimeName.to_string()));
+        assert!(transcript.contains("\t1. Ева | Evangelion\n"));
+        assert!(transcript.contains("Selected anime `Ева | Evangelion`"));
+
+        // Without an account the source is selected again
+        let transcript = run_script_with_favorites(&sources(), "ru\n1\n", true).await;
+        let (_, after_warning) = transcript
+            .split_once(&Message::NoFavorites.to_string())
+            .unwrap();
+        assert!(after_warning.contains(&format!("> {}: ", Message::SelectSource)));
+    }
+}
@@ -0,0 +1,154 @@
+use super::theme::Theme;
+
+use crate::enums::picker::Picker;
+
+use ratatui::crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use std::io::{self, BufRead, IsTerminal as _, StdinLock};
+use termcolor::{ColorChoice, StandardStream, WriteColor};
+
+/// Input and output of the dialog. \
+/// The dialog reads user input only from `input` and writes messages only to `output`,
+/// so it can be run on any reader and writer, e.g. on scripted input with a buffer as a transcript.
+pub struct Terminal<R, W> {
+    input: R,
+    output: W,
+    theme: Theme,
+    picker: Option<Picker>,
+    /// Whether secrets are read from keys of the terminal without echo instead of `input`
+    hidden_input: bool,
+}
+
+impl<R, W> Terminal<R, W> {
+    #[must_use]
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            theme: Theme::default(),
+            picker: None,
+            hidden_input: false,
+        }
+    }
+
+    /// Set a palette of the output messages
+    #[must_use]
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    #[must_use]
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Set an external picker to select variants instead of numbered lists
+    #[must_use]
+    pub fn with_picker(mut self, picker: Option<Picker>) -> Self {
+        self.picker = picker;
+        self
+    }
+
+    #[must_use]
+    pub fn picker(&self) -> Option<Picker> {
+        self.picker
+    }
+
+    /// Fall back to the built-in prompt, e.g. if the picker isn't installed
+    pub fn disable_picker(&mut self) {
+        self.picker = None;
+    }
+}
+
+impl<R, W> Terminal<R, W>
+where
+    R: BufRead,
+{
+    /// Reads a line from the input
+    /// # Returns
+    /// * `Ok(None)` - If the input is closed
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+
+        match self.input.read_line(&mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(line)),
+        }
+    }
+}
+
+impl<R, W> Terminal<R, W>
+where
+    W: WriteColor,
+{
+    #[must_use]
+    pub fn output(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
+
+impl<R, W> Terminal<R, W>
+where
+    R: BufRead,
+    W: WriteColor,
+{
+    /// Reads a line without echoing it, e.g. a password. \
+    /// Keys are read in raw mode only if the standard input is a terminal,
+    /// otherwise the line is read from the input like [`Self::read_line`]
+    /// # Returns
+    /// * `Ok(None)` - If the input is closed or cancelled by `Esc` or `Ctrl-C`
+    pub fn read_secret(&mut self) -> io::Result<Option<String>> {
+        if !self.hidden_input {
+            return self.read_line();
+        }
+
+        enable_raw_mode()?;
+        let secret = read_hidden_line();
+        disable_raw_mode()?;
+        writeln!(self.output)?;
+
+        secret
+    }
+}
+
+impl Terminal<StdinLock<'static>, StandardStream> {
+    /// Terminal on the standard input and output
+    /// # Arguments
+    /// * `color_choice` - Whether the output should be colored,
+    ///   see [`ColorMode::color_choice`](crate::enums::color_mode::ColorMode::color_choice)
+    #[must_use]
+    pub fn stdio(color_choice: ColorChoice) -> Self {
+        let mut terminal = Self::new(io::stdin().lock(), StandardStream::stdout(color_choice));
+        // Piped secrets are read as lines
+        terminal.hidden_input = io::stdin().is_terminal();
+        terminal
+    }
+}
+
+/// Read keys of the terminal in raw mode until `Enter`
+fn read_hidden_line() -> io::Result<Option<String>> {
+    let mut line = String::new();
+
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Enter => return Ok(Some(line)),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Char(char) => line.push(char),
+            _ => {}
+        }
+    }
+}
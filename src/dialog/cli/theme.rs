@@ -0,0 +1,173 @@
+use crate::{errors::ThemeError, locale::Message};
+
+use std::str::FromStr as _;
+use termcolor::{Color, ColorSpec};
+
+/// Palette of the dialog messages. \
+/// Each style is used by the message function with the same name in `output`.
+#[derive(Clone)]
+pub struct Theme {
+    pub input: ColorSpec,
+    pub warning: ColorSpec,
+    pub error: ColorSpec,
+    pub info: ColorSpec,
+    pub variant: ColorSpec,
+    pub headline: ColorSpec,
+}
+
+impl Theme {
+    pub const NAMES: &'static [&'static str] = &["default", "light", "monochrome"];
+
+    /// Get a built-in theme by name
+    pub fn by_name(name: &str) -> Result<Self, ThemeError> {
+        match name.to_lowercase().as_str() {
+            "default" | "dark" => Ok(Self::default()),
+            "light" => Ok(Self {
+                input: fg(Color::Green).set_bold(true).clone(),
+                warning: fg(Color::Magenta),
+                error: fg(Color::Red).set_bold(true).clone(),
+                info: fg(Color::Blue),
+                variant: fg(Color::Black),
+                headline: fg(Color::Blue).set_bold(true).clone(),
+            }),
+            "monochrome" | "mono" => Ok(Self {
+                input: ColorSpec::new().set_bold(true).clone(),
+                warning: ColorSpec::new().set_underline(true).clone(),
+                error: ColorSpec::new().set_bold(true).set_underline(true).clone(),
+                info: ColorSpec::new(),
+                variant: ColorSpec::new(),
+                headline: ColorSpec::new().set_bold(true).clone(),
+            }),
+            _ => Err(ThemeError::UnknownTheme(
+                Message::UnknownTheme(name, &Self::NAMES.join(", ")).to_string(),
+            )),
+        }
+    }
+
+    /// Override a style of the theme
+    /// # Arguments
+    /// * `name` - Style name, e.g. `input`, `warning` or `headline`
+    /// * `style` - Whitespace separated color and attributes, e.g. `green`, `bold red`, `underline 208`
+    pub fn set_style(&mut self, name: &str, style: &str) -> Result<(), ThemeError> {
+        let spec = parse_style(style)?;
+
+        match name.to_lowercase().as_str() {
+            "input" => self.input = spec,
+            "warning" => self.warning = spec,
+            "error" => self.error = spec,
+            "info" => self.info = spec,
+            "variant" => self.variant = spec,
+            "headline" => self.headline = spec,
+            _ => {
+                return Err(ThemeError::InvalidStyle(
+                    Message::UnknownStyle(name).to_string(),
+                ))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            input: fg(Color::Green),
+            warning: fg(Color::Yellow),
+            error: fg(Color::Red),
+            info: fg(Color::Blue),
+            variant: fg(Color::White),
+            headline: fg(Color::Cyan),
+        }
+    }
+}
+
+fn fg(color: Color) -> ColorSpec {
+    ColorSpec::new().set_fg(Some(color)).clone()
+}
+
+fn parse_style(style: &str) -> Result<ColorSpec, ThemeError> {
+    let mut spec = ColorSpec::new();
+
+    for token in style.split_whitespace() {
+        match token.to_lowercase().as_str() {
+            "bold" => spec.set_bold(true),
+            "dimmed" | "dim" => spec.set_dimmed(true),
+            "italic" => spec.set_italic(true),
+            "underline" => spec.set_underline(true),
+            "intense" => spec.set_intense(true),
+            "none" | "default" => spec.set_fg(None),
+            color => match Color::from_str(color) {
+                Ok(color) => spec.set_fg(Some(color)),
+                Err(err) => {
+                    return Err(ThemeError::InvalidStyle(
+                        Message::InvalidStyle(style, &err).to_string(),
+                    ))
+                }
+            },
+        };
+    }
+
+    Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn themes_are_found_by_names_and_aliases() {
+        for name in Theme::NAMES {
+            assert!(Theme::by_name(name).is_ok(), "{name}");
+        }
+
+        let dark = Theme::by_name("DARK").unwrap();
+        assert_eq!(dark.warning, Theme::default().warning);
+        let mono = Theme::by_name("mono").unwrap();
+        assert_eq!(mono.info, ColorSpec::new());
+        assert!(mono.error.bold() && mono.error.underline());
+    }
+
+    #[test]
+    fn unknown_theme_is_rejected() {
+        let Err(err) = Theme::by_name("neon") else {
+            panic!("Theme `neon` is found");
+        };
+        assert_eq!(
+            err.to_string(),
+            Message::UnknownTheme("neon", "default, light, monochrome").to_string()
+        );
+    }
+
+    #[test]
+    fn styles_are_overridden() {
+        let mut theme = Theme::default();
+
+        theme.set_style("Warning", "bold red").unwrap();
+        assert_eq!(theme.warning.fg(), Some(&Color::Red));
+        assert!(theme.warning.bold());
+
+        theme.set_style("info", "underline 208").unwrap();
+        assert_eq!(theme.info.fg(), Some(&Color::Ansi256(208)));
+        assert!(theme.info.underline());
+
+        theme.set_style("input", "none").unwrap();
+        assert_eq!(theme.input.fg(), None);
+        // Other styles are kept
+        assert_eq!(theme.error, Theme::default().error);
+    }
+
+    #[test]
+    fn invalid_styles_are_rejected() {
+        let mut theme = Theme::default();
+
+        let Err(err) = theme.set_style("prompt", "green") else {
+            panic!("Style `prompt` is set");
+        };
+        assert_eq!(err.to_string(), Message::UnknownStyle("prompt").to_string());
+
+        assert!(theme.set_style("warning", "bold sparkly").is_err());
+        assert!(theme.set_style("warning", "256").is_err());
+        assert_eq!(theme.warning, Theme::default().warning);
+    }
+}
@@ -1,3 +1,8 @@
 pub mod data;
+pub mod languages;
+pub mod local;
 pub mod state;
 pub mod state_machine;
+pub mod stream;
+pub mod track;
+pub mod watched;
@@ -8,6 +8,9 @@ where
     S: Source,
 {
     language: Language,
+    /// Sources to search anime in
+    sources: Vec<S>,
+    /// Source of the selected anime
     source: Option<S>,
     player: Option<Player>,
 }
@@ -16,15 +19,6 @@ impl<S> Data<S>
 where
     S: Source,
 {
-    #[must_use]
-    pub fn new(language: Language, source: S, player: Player) -> Self {
-        Self {
-            language,
-            source: Some(source),
-            player: Some(player),
-        }
-    }
-
     #[must_use]
     pub fn language(&self) -> &Language {
         &self.language
@@ -34,6 +28,15 @@ where
         self.language = language;
     }
 
+    #[must_use]
+    pub fn sources_mut(&mut self) -> &mut [S] {
+        &mut self.sources
+    }
+
+    pub fn set_sources(&mut self, sources: Vec<S>) {
+        self.sources = sources;
+    }
+
     #[must_use]
     pub fn source(&self) -> Option<&S> {
         self.source.as_ref()
@@ -62,10 +65,10 @@ impl<S> Default for Data<S>
 where
     S: Source,
 {
-    #[must_use]
     fn default() -> Self {
         Self {
             language: Language::default(),
+            sources: Vec::new(),
             source: None,
             player: None,
         }
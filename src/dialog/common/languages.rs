@@ -0,0 +1,56 @@
+use crate::{enums::language::Language, sources::base::Source};
+
+use std::collections::HashMap;
+
+/// Count sources of each language
+/// # Returns
+/// Languages sorted by the count of sources and the name,
+/// followed by [`Language::All`] with the count of all sources
+#[must_use]
+pub fn count_sources_by_language<S>(sources: &[S]) -> Vec<(Language, usize)>
+where
+    S: Source,
+{
+    let mut languages: HashMap<&Language, usize> = HashMap::new();
+    for source in sources {
+        *languages.entry(source.language()).or_default() += 1;
+    }
+
+    let mut languages = languages
+        .into_iter()
+        .filter(|(language, _)| **language != Language::All)
+        .map(|(language, count)| (language.clone(), count))
+        .collect::<Vec<_>>();
+    languages.sort_by(|(a_language, a_count), (b_language, b_count)| {
+        b_count
+            .cmp(a_count)
+            .then_with(|| a_language.to_string().cmp(&b_language.to_string()))
+    });
+    languages.push((Language::All, sources.len()));
+
+    languages
+}
+
+/// Sources that can be used in the language mode
+#[must_use]
+pub fn sources_of_language<'a, S>(sources: &'a [S], language: &Language) -> Vec<&'a S>
+where
+    S: Source,
+{
+    sources
+        .iter()
+        .filter(|source| language.includes(source.language()))
+        .collect()
+}
+
+/// Name of the source with its language tag in [`Language::All`] mode, e.g. `Anilibria [ru]`
+#[must_use]
+pub fn source_label<S>(source: &S, language: &Language) -> String
+where
+    S: Source,
+{
+    match (language, source.language().code()) {
+        (Language::All, Some(code)) => format!("{source} [{code}]"),
+        _ => source.to_string(),
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{sources::base::Source, storage::downloads::Downloads};
+
+use std::path::PathBuf;
+
+/// Find the downloaded file of current episode of current anime of the source
+/// # Returns
+/// Path of the file, `None` if the episode isn't downloaded or its file is missing or damaged
+#[must_use]
+pub fn local_copy<S>(downloads: &Downloads, source: &S) -> Option<PathBuf>
+where
+    S: Source,
+{
+    let anime = source.anime_info().ok()?;
+    let episode = source.episode_info().ok()?;
+
+    downloads
+        .find_file(
+            &source.to_string(),
+            &anime.to_string(),
+            &episode.to_string(),
+        )
+        .map(PathBuf::from)
+}
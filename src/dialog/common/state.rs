@@ -1,9 +1,10 @@
-use crate::errors::StateError;
+use crate::{errors::StateError, locale::Message};
 
 use std::fmt::{self, Display};
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub enum State {
+    #[default]
     SelectLanguage,
     SelectSource,
     SelectAnime,
@@ -13,13 +14,6 @@ pub enum State {
     LaunchPlayer,
 }
 
-impl Default for State {
-    #[must_use]
-    fn default() -> Self {
-        Self::SelectLanguage
-    }
-}
-
 impl Display for State {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -46,7 +40,9 @@ impl TryFrom<&str> for State {
             "quality" => Ok(Self::SelectQuality),
             "player" => Ok(Self::SelectPlayer),
             "launch" => Ok(Self::LaunchPlayer),
-            _ => Err(StateError::UnknownState(format!("Unknown state `{state}`"))),
+            _ => Err(StateError::UnknownState(
+                Message::UnknownState(state).to_string(),
+            )),
         }
     }
 }
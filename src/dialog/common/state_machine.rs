@@ -75,7 +75,6 @@ impl<S> Default for StateMachine<S>
 where
     S: Source,
 {
-    #[must_use]
     fn default() -> Self {
         Self {
             previous_states: Vec::default(),
@@ -84,3 +83,30 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enums::language::Language, testing::MockSource};
+
+    #[test]
+    fn previous_states_are_restored_in_reverse_order() {
+        let mut data = Data::default();
+        data.set_language(Language::Russian);
+        data.set_source(MockSource::new("Mock", "http://127.0.0.1"));
+        let mut state_machine = StateMachine::new(State::SelectAnime, data);
+
+        state_machine.set_state(State::SelectEpisode);
+        state_machine.set_state(State::SelectQuality);
+        state_machine.set_state(State::SelectPlayer);
+
+        state_machine.set_previous_state_and_truncate_next(State::SelectEpisode);
+        assert!(state_machine.current_state() == &State::SelectEpisode);
+
+        state_machine.set_previous_state();
+        assert!(state_machine.current_state() == &State::SelectAnime);
+        // There is no state before the first one
+        state_machine.set_previous_state();
+        assert!(state_machine.current_state() == &State::SelectAnime);
+    }
+}
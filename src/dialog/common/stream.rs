@@ -0,0 +1,216 @@
+use crate::{
+    errors::SourceError,
+    locale::Message,
+    sources::{base::Source, hls::StreamInfo, stream::StreamCheck},
+};
+
+/// Get URL for streaming current episode in current quality. \
+/// If the stream is unavailable, lower qualities are tried, e.g. `fhd` → `hd` → `sd`,
+/// and the quality of the playable stream is selected as current quality
+/// # Arguments
+/// * `on_fallback` - Called with the unavailable quality, the next quality and the error
+pub async fn checked_url_for_stream<S, F>(
+    source: &mut S,
+    check: &StreamCheck,
+    mut on_fallback: F,
+) -> Result<String, SourceError>
+where
+    S: Source,
+    F: FnMut(&str, &str, &SourceError),
+{
+    let qualities = source.qualities()?;
+    let current = source.quality_info()?.to_string();
+    let position = qualities
+        .iter()
+        .position(|quality| quality == &current)
+        .unwrap_or(qualities.len().saturating_sub(1));
+
+    // The selected quality and then lower ones
+    let mut candidates = qualities.into_iter().take(position + 1).rev().peekable();
+
+    let mut last_err = None;
+
+    while let Some(quality) = candidates.next() {
+        if quality != current {
+            source.select_quality_as_current(quality.clone())?;
+        }
+
+        let result = match source.url_for_stream().await {
+            Ok(url) => check.check(&url).await.map(|()| url),
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(url) => return Ok(url),
+            Err(err) => {
+                if let Some(next) = candidates.peek() {
+                    on_fallback(&quality, next, &err);
+                }
+                last_err = Some(err);
+            }
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| {
+        SourceError::UnknownVariant(Message::UnknownQuality(&current).to_string())
+    }))
+}
+
+/// Describe streams of qualities of current episode, e.g. their resolution, bitrate and size. \
+/// Each quality is selected as current quality to get its stream
+/// # Returns
+/// Qualities from [`Source::qualities`] with descriptions of their streams,
+/// `None` if the stream can't be described or the check is disabled
+pub async fn describe_qualities<S>(
+    source: &mut S,
+    check: &StreamCheck,
+) -> Vec<(String, Option<StreamInfo>)>
+where
+    S: Source,
+{
+    let qualities = source.qualities().unwrap_or_default();
+    if !check.is_enabled() {
+        return qualities
+            .into_iter()
+            .map(|quality| (quality, None))
+            .collect();
+    }
+
+    let mut described = Vec::with_capacity(qualities.len());
+    for quality in qualities {
+        let info = match source.select_quality_as_current(quality.clone()) {
+            Ok(()) => match source.url_for_stream().await {
+                Ok(url) => check.info(&url).await.ok().flatten(),
+                Err(_) => None,
+            },
+            Err(_) => None,
+        };
+
+        described.push((quality, info));
+    }
+
+    described
+}
+
+/// Menu of qualities with descriptions of their streams from [`describe_qualities`],
+/// e.g. `\tfhd | 1920x1080 · 4.2 Mbps · avc1.640028 · 23:40 · ~745 MB`
+/// # Returns
+/// `None` if no stream is described, e.g. if the check is disabled,
+/// so the menu of the source from [`Source::qualities_info`] should be shown
+#[must_use]
+pub fn qualities_menu(qualities: &[(String, Option<StreamInfo>)]) -> Option<String> {
+    if qualities.iter().all(|(_, info)| info.is_none()) {
+        return None;
+    }
+
+    Some(
+        qualities
+            .iter()
+            .map(|(quality, info)| match info {
+                Some(info) => format!("\t{quality} | {info}\n"),
+                None => format!("\t{quality}\n"),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{http, MockServer, MockSource};
+
+    #[tokio::test]
+    async fn lower_quality_is_selected_if_stream_is_unavailable() {
+        // Only streams of `480p` are available
+        let server = MockServer::start(|request| {
+            if request.path.contains("480p") {
+                (200, String::from("#EXTM3U\n#EXTINF:10.0,\nseg1.ts\n"))
+            } else if request.path.ends_with(".ts") {
+                (200, String::from("x"))
+            } else {
+                (404, String::new())
+            }
+        });
+        let mut source = MockSource::new("Mock", server.url()).with_anime("Madoka", &["1"]);
+        source.search_anime_list("madoka").await.unwrap();
+        source
+            .select_anime_as_current(String::from("1"))
+            .await
+            .unwrap();
+        source
+            .select_episode_as_current(String::from("1"))
+            .await
+            .unwrap();
+        source
+            .select_quality_as_current(String::from("720p"))
+            .unwrap();
+
+        let mut fallbacks = Vec::new();
+        let url = checked_url_for_stream(&mut source, &StreamCheck::new(http()), |from, to, _| {
+            fallbacks.push((from.to_string(), to.to_string()));
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(url, format!("{}/1/1/480p.m3u8", server.url()));
+        assert_eq!(source.quality_info().unwrap(), "480p");
+        assert_eq!(fallbacks, [(String::from("720p"), String::from("480p"))]);
+    }
+
+    #[tokio::test]
+    async fn last_error_is_returned_if_no_stream_is_available() {
+        let server = MockServer::start(|_| (404, String::new()));
+        let mut source = MockSource::new("Mock", server.url()).with_anime("Madoka", &["1"]);
+        source.search_anime_list("madoka").await.unwrap();
+        source
+            .select_anime_as_current(String::from("1"))
+            .await
+            .unwrap();
+        source
+            .select_episode_as_current(String::from("1"))
+            .await
+            .unwrap();
+        source
+            .select_quality_as_current(String::from("720p"))
+            .unwrap();
+
+        let result =
+            checked_url_for_stream(&mut source, &StreamCheck::new(http()), |_, _, _| {}).await;
+
+        assert!(result.is_err());
+        assert_eq!(server.log(), ["GET /1/1/720p.m3u8", "GET /1/1/480p.m3u8"]);
+    }
+
+    #[tokio::test]
+    async fn menu_is_built_from_playlists_of_streams() {
+        // `720p` is a master playlist, the stream of `480p` is unavailable
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/1/1/720p.m3u8" => (
+                200,
+                String::from(
+                    "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=2100000,RESOLUTION=1280x720\nhd.m3u8\n",
+                ),
+            ),
+            "/1/1/hd.m3u8" => (200, String::from("#EXTM3U\n#EXTINF:60.0,\nseg1.ts\n")),
+            _ => (404, String::new()),
+        });
+        let mut source = MockSource::new("Mock", server.url()).with_anime("Madoka", &["1"]);
+        source.search_anime_list("madoka").await.unwrap();
+        source
+            .select_anime_as_current(String::from("1"))
+            .await
+            .unwrap();
+        source
+            .select_episode_as_current(String::from("1"))
+            .await
+            .unwrap();
+
+        let qualities = describe_qualities(&mut source, &StreamCheck::new(http())).await;
+
+        assert_eq!(
+            qualities_menu(&qualities).unwrap(),
+            "\t480p\n\t720p | 1280x720 · 2.1 Mbps · 1:00 · ~15 MB\n"
+        );
+        assert!(qualities_menu(&[(String::from("480p"), None)]).is_none());
+    }
+}
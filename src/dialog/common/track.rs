@@ -0,0 +1,44 @@
+use crate::{
+    errors::TrackerError,
+    locale::Message,
+    sources::base::Source,
+    tracker::{names_of, Tracker},
+};
+
+/// Update progress of current anime of the source on the tracker up to current episode
+/// # Returns
+/// Message about the new progress, `Ok(None)` if the episode isn't a number or the progress isn't changed
+pub async fn track_current_episode<S, T>(
+    tracker: &T,
+    source: &S,
+) -> Result<Option<String>, TrackerError>
+where
+    S: Source,
+    T: Tracker,
+{
+    let episode = source
+        .episode_info()
+        .expect("Episode isn't set")
+        .to_string();
+    // Specials like `5.5` aren't counted by trackers
+    let Ok(episode) = episode.trim().parse::<u32>() else {
+        return Ok(None);
+    };
+
+    let details = source.anime_details()?;
+    let names = names_of(&details);
+
+    let Some(anime) = tracker.find_anime(&names).await? else {
+        return Err(TrackerError::NotFound(
+            Message::NotFoundOnTracker(&details.title, tracker.name()).to_string(),
+        ));
+    };
+
+    if tracker.update_progress(anime.id, episode).await? {
+        Ok(Some(
+            Message::TrackerProgress(tracker.name(), &details.title, episode).to_string(),
+        ))
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{sources::base::Source, storage::history::History};
+
+use std::io;
+
+/// Check if the episode of current anime of the source is watched
+#[must_use]
+pub fn is_episode_watched<S>(history: &History, source: &S, episode: &str) -> bool
+where
+    S: Source,
+{
+    match source.anime_info() {
+        Ok(anime) => history.is_watched(&source.to_string(), &anime.to_string(), episode),
+        Err(_) => false,
+    }
+}
+
+/// Mark current episode of current anime of the source as watched
+pub fn mark_current_episode_watched<S>(history: &mut History, source: &S) -> io::Result<()>
+where
+    S: Source,
+{
+    let anime = source.anime_info().expect("Anime isn't set");
+    let episode = source.episode_info().expect("Episode isn't set");
+
+    history.add(
+        &source.to_string(),
+        &anime.to_string(),
+        &episode.to_string(),
+    )
+}
@@ -0,0 +1,5 @@
+mod app;
+mod run;
+mod ui;
+
+pub use run::run;
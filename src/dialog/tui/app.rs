@@ -0,0 +1,966 @@
+use crate::{
+    dialog::common::{
+        data::Data,
+        languages::{count_sources_by_language, source_label, sources_of_language},
+        local::local_copy,
+        state::State,
+        state_machine::StateMachine,
+        stream::{checked_url_for_stream, describe_qualities},
+        track::track_current_episode,
+        watched::{is_episode_watched, mark_current_episode_watched},
+    },
+    enums::{
+        language::Language,
+        player::{players, Player},
+    },
+    locale::Message,
+    players::mpv,
+    sources::{
+        base::Source,
+        hls::StreamInfo,
+        search::{search_all, Hit, Query},
+        stream::StreamCheck,
+    },
+    storage::{downloads::Downloads, history::History},
+    tracker::Tracker,
+};
+
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    widgets::ListState,
+};
+use std::future;
+
+/// An operation that may need network,
+/// so it's performed after the screen is redrawn and the user sees what the program is waiting for
+pub enum Pending {
+    Search,
+    /// List favorites of the logged in accounts instead of searching
+    Favorites,
+    /// Describe the highlighted anime of the search results or streams of qualities
+    Details,
+    /// Select the highlighted item
+    Select,
+}
+
+pub struct Status {
+    pub message: String,
+    pub is_error: bool,
+}
+
+pub struct App<'a, S, T>
+where
+    S: Source,
+    T: Tracker,
+{
+    sources: &'a [S],
+    history: &'a mut History,
+    downloads: &'a Downloads,
+    tracker: Option<&'a T>,
+    stream_check: &'a StreamCheck,
+    state_machine: StateMachine<S>,
+
+    languages: Vec<(Language, usize)>,
+    hits: Vec<Hit>,
+    /// Descriptions of streams of current episode, loaded once per episode
+    streams: Option<Vec<(String, Option<StreamInfo>)>>,
+    /// Labels of the current list pane or cells of the episode grid
+    items: Vec<String>,
+    list_state: ListState,
+    /// Columns of the episode grid, updated on each draw
+    grid_columns: usize,
+    details: String,
+
+    query: String,
+    editing: bool,
+    /// Whether favorites are listed instead of asking the query when the sources are selected
+    favorites: bool,
+
+    pending: Option<Pending>,
+    status: Option<Status>,
+    quit: bool,
+}
+
+impl<'a, S, T> App<'a, S, T>
+where
+    S: Source,
+    T: Tracker,
+{
+    #[must_use]
+    pub fn new(
+        sources: &'a [S],
+        history: &'a mut History,
+        downloads: &'a Downloads,
+        tracker: Option<&'a T>,
+        stream_check: &'a StreamCheck,
+        favorites: bool,
+    ) -> Self {
+        let languages = count_sources_by_language(sources);
+
+        let mut app = Self {
+            sources,
+            history,
+            downloads,
+            tracker,
+            stream_check,
+            state_machine: StateMachine::new(State::default(), Data::default()),
+            languages,
+            hits: Vec::new(),
+            streams: None,
+            items: Vec::new(),
+            list_state: ListState::default(),
+            grid_columns: 1,
+            details: String::new(),
+            query: String::new(),
+            editing: false,
+            favorites,
+            pending: None,
+            status: None,
+            quit: false,
+        };
+        app.refresh();
+        app
+    }
+
+    #[must_use]
+    pub fn state(&self) -> &State {
+        self.state_machine.current_state()
+    }
+
+    #[must_use]
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    #[must_use]
+    pub fn list_state(&mut self) -> &mut ListState {
+        &mut self.list_state
+    }
+
+    #[must_use]
+    pub fn selected(&self) -> Option<usize> {
+        self.list_state.selected()
+    }
+
+    pub fn set_grid_columns(&mut self, columns: usize) {
+        self.grid_columns = columns.max(1);
+    }
+
+    #[must_use]
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+
+    #[must_use]
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    #[must_use]
+    pub fn editing(&self) -> bool {
+        self.editing
+    }
+
+    #[must_use]
+    pub fn pending(&self) -> Option<&Pending> {
+        self.pending.as_ref()
+    }
+
+    #[must_use]
+    pub fn status(&self) -> Option<&Status> {
+        self.status.as_ref()
+    }
+
+    #[must_use]
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Check if the episode of the episode grid is watched
+    #[must_use]
+    pub fn is_watched(&mut self, episode: &str) -> bool {
+        match self.state_machine.data().source() {
+            Some(source) => is_episode_watched(self.history, source, episode),
+            None => false,
+        }
+    }
+
+    /// Names of the states passed to get the current state, e.g. `Russian › Anilibria`
+    #[must_use]
+    pub fn breadcrumbs(&mut self) -> Vec<String> {
+        let state = self.state().clone();
+        let data = self.state_machine.data();
+
+        let mut breadcrumbs = Vec::new();
+        if state == State::SelectLanguage {
+            return breadcrumbs;
+        }
+        breadcrumbs.push(data.language().to_string());
+
+        if state == State::SelectSource {
+            return breadcrumbs;
+        }
+        if state == State::SelectAnime {
+            let sources = data.sources_mut();
+            breadcrumbs.push(match sources {
+                [source] => source.to_string(),
+                _ => Message::AllSources.to_string(),
+            });
+            return breadcrumbs;
+        }
+
+        let Some(source) = data.source() else {
+            return breadcrumbs;
+        };
+        breadcrumbs.push(source.to_string());
+
+        if let Ok(anime) = source.anime_info() {
+            breadcrumbs.push(anime.to_string());
+        }
+        if matches!(state, State::SelectQuality | State::SelectPlayer) {
+            if let Ok(episode) = source.episode_info() {
+                breadcrumbs.push(Message::EpisodeNumber(&episode.to_string()).to_string());
+            }
+        }
+
+        breadcrumbs
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        if self.editing {
+            self.handle_input_key(key);
+            return;
+        }
+
+        self.status = None;
+
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc | KeyCode::Backspace => self.back(),
+            KeyCode::Char('/' | 's') if self.state() == &State::SelectAnime => self.editing = true,
+            KeyCode::Char('f') if self.state() == &State::SelectAnime => self.request_favorites(),
+            KeyCode::Enter => self.pending = Some(Pending::Select),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-self.row_step()),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(self.row_step()),
+            KeyCode::Left | KeyCode::Char('h') if self.is_grid() => self.move_selection(-1),
+            KeyCode::Right | KeyCode::Char('l') if self.is_grid() => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-10 * self.row_step()),
+            KeyCode::PageDown => self.move_selection(10 * self.row_step()),
+            KeyCode::Home | KeyCode::Char('g') => self.select_index(0),
+            KeyCode::End | KeyCode::Char('G') => {
+                self.select_index(self.items.len().saturating_sub(1));
+            }
+            _ => {}
+        }
+    }
+
+    /// Perform the pending operation
+    pub async fn perform_pending(&mut self) {
+        match self.pending.take() {
+            Some(Pending::Search) => {
+                let query = self.query.trim().to_string();
+                self.search(Query::Name(&query)).await;
+            }
+            Some(Pending::Favorites) => self.search(Query::Favorites).await,
+            Some(Pending::Details) if self.state() == &State::SelectQuality => {
+                self.describe_streams().await;
+            }
+            Some(Pending::Details) => self.describe_hit().await,
+            Some(Pending::Select) => self.select().await,
+            None => {}
+        }
+    }
+
+    #[must_use]
+    pub fn is_grid(&self) -> bool {
+        self.state() == &State::SelectEpisode
+    }
+
+    fn row_step(&self) -> isize {
+        if self.is_grid() {
+            self.grid_columns.try_into().unwrap_or(1)
+        } else {
+            1
+        }
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                if self.query.trim().is_empty() {
+                    return;
+                }
+                self.editing = false;
+                self.pending = Some(Pending::Search);
+                self.status = Some(Status {
+                    message: Message::SearchingQuery(self.query.trim()).to_string(),
+                    is_error: false,
+                });
+            }
+            KeyCode::Esc => {
+                if self.items.is_empty() {
+                    self.editing = false;
+                    self.back();
+                } else {
+                    self.editing = false;
+                }
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+            }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.request_favorites();
+            }
+            KeyCode::Char(char) => self.query.push(char),
+            _ => {}
+        }
+    }
+
+    fn request_favorites(&mut self) {
+        self.editing = false;
+        self.query.clear();
+        self.pending = Some(Pending::Favorites);
+        self.status = Some(Status {
+            message: Message::LoadingFavorites.to_string(),
+            is_error: false,
+        });
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let current = self.selected().unwrap_or(0);
+        let last = self.items.len() - 1;
+        let next = current.saturating_add_signed(delta).min(last);
+
+        self.select_index(next);
+    }
+
+    fn select_index(&mut self, index: usize) {
+        if self.items.is_empty() {
+            self.list_state.select(None);
+            return;
+        }
+
+        self.list_state
+            .select(Some(index.min(self.items.len() - 1)));
+        self.update_details();
+    }
+
+    fn back(&mut self) {
+        if self.state() == &State::SelectLanguage {
+            self.quit = true;
+            return;
+        }
+
+        self.state_machine.set_previous_state();
+        self.refresh();
+        if self.state() == &State::SelectEpisode {
+            self.highlight_current_episode();
+        }
+    }
+
+    fn set_state(&mut self, state: State) {
+        self.state_machine.set_state(state);
+        self.refresh();
+    }
+
+    /// Fill the pane of the current state
+    fn refresh(&mut self) {
+        let state = self.state().clone();
+        let language = self.state_machine.data().language().clone();
+
+        let items = match state {
+            State::SelectLanguage => Ok(self
+                .languages
+                .iter()
+                .map(|(language, count)| Message::LanguageSources(language, *count).to_string())
+                .collect()),
+            State::SelectSource => Ok(sources_of_language(self.sources, &language)
+                .into_iter()
+                .map(|source| source_label(source, &language))
+                .chain([Message::AllSources.to_string()])
+                .collect()),
+            State::SelectAnime => {
+                let sources = self.state_machine.data().sources_mut();
+                // Show the source of each anime only if there are several sources
+                let tagged = sources.len() > 1;
+
+                Ok(self
+                    .hits
+                    .iter()
+                    .map(|hit| {
+                        if tagged {
+                            format!("[{}] {}", sources[hit.source_index], hit.title)
+                        } else {
+                            hit.title.clone()
+                        }
+                    })
+                    .collect())
+            }
+            State::SelectEpisode => self
+                .state_machine
+                .data()
+                .source()
+                .map_or(Ok(Vec::new()), Source::episodes),
+            State::SelectQuality => self
+                .state_machine
+                .data()
+                .source()
+                .map_or(Ok(Vec::new()), Source::qualities),
+            State::SelectPlayer | State::LaunchPlayer => {
+                Ok(players().iter().map(ToString::to_string).collect())
+            }
+        };
+
+        let items = items.unwrap_or_else(|err| {
+            self.set_error(err.to_string());
+            Vec::new()
+        });
+
+        self.editing = state == State::SelectAnime && items.is_empty();
+        self.items = items;
+        self.select_index(0);
+        if self.items.is_empty() {
+            self.details.clear();
+        }
+    }
+
+    /// Highlight the current episode when the user returns to the episode grid. \
+    /// The episode is set only after it's selected, so the grid isn't refreshed with it
+    fn highlight_current_episode(&mut self) {
+        let current_episode = self
+            .state_machine
+            .data()
+            .source()
+            .and_then(|source| source.episode_info().ok())
+            .map(|episode| episode.to_string());
+
+        if let Some(index) =
+            current_episode.and_then(|episode| self.items.iter().position(|item| item == &episode))
+        {
+            self.select_index(index);
+        }
+    }
+
+    /// Describe the highlighted item in the details panel
+    fn update_details(&mut self) {
+        match self.state() {
+            State::SelectAnime => self.pending = Some(Pending::Details),
+            State::SelectQuality if self.streams.is_none() => {
+                self.pending = Some(Pending::Details);
+            }
+            State::SelectEpisode | State::SelectQuality | State::SelectPlayer => {
+                self.details = match self.state_machine.data().source_mut() {
+                    Some(source) => describe_anime(source),
+                    None => String::new(),
+                };
+
+                if let (State::SelectQuality, Some(streams), Some(index)) =
+                    (self.state(), &self.streams, self.selected())
+                {
+                    if let Some((quality, Some(info))) = streams.get(index) {
+                        self.details.push_str(&format!("\n\n{quality}: {info}"));
+                    }
+                }
+            }
+            _ => self.details.clear(),
+        }
+    }
+
+    async fn describe_streams(&mut self) {
+        let source = self.state_machine.data().source_mut().unwrap();
+        self.streams = Some(describe_qualities(source, self.stream_check).await);
+        self.update_details();
+    }
+
+    async fn describe_hit(&mut self) {
+        let Some(hit) = self.selected().and_then(|index| self.hits.get(index)) else {
+            self.details.clear();
+            return;
+        };
+
+        // Selecting an anime only changes the current anime of the source
+        // and can be done again when the user presses `Enter`
+        let source = &mut self.state_machine.data().sources_mut()[hit.source_index];
+        self.details = match source
+            .select_anime_as_current((hit.anime_index + 1).to_string())
+            .await
+        {
+            Ok(()) => describe_anime(source),
+            Err(err) => err.to_string(),
+        };
+    }
+
+    async fn select(&mut self) {
+        let Some(index) = self.selected() else {
+            if self.state() == &State::SelectAnime {
+                self.editing = true;
+            }
+            return;
+        };
+        let item = self.items[index].clone();
+
+        match self.state().clone() {
+            State::SelectLanguage => {
+                let language = self.languages[index].0.clone();
+                self.state_machine.data().set_language(language);
+                self.set_state(State::SelectSource);
+            }
+            State::SelectSource => {
+                let language = self.state_machine.data().language().clone();
+                let sources = sources_of_language(self.sources, &language);
+                // The last item is all sources
+                let sources = match sources.get(index) {
+                    Some(source) => vec![(*source).clone()],
+                    None => sources.into_iter().cloned().collect(),
+                };
+
+                self.state_machine.data().set_sources(sources);
+                self.hits.clear();
+                self.query.clear();
+                self.set_state(State::SelectAnime);
+                if self.favorites {
+                    self.request_favorites();
+                }
+            }
+            State::SelectAnime => {
+                let hit = &self.hits[index];
+                let source = &mut self.state_machine.data().sources_mut()[hit.source_index];
+
+                match source
+                    .select_anime_as_current((hit.anime_index + 1).to_string())
+                    .await
+                {
+                    Ok(()) => {
+                        let source = source.clone();
+                        self.state_machine.data().set_source(source);
+                        self.set_state(State::SelectEpisode);
+                    }
+                    Err(err) => self.set_error(err.to_string()),
+                }
+            }
+            State::SelectEpisode => {
+                let source = self.state_machine.data().source_mut().unwrap();
+                match source.select_episode_as_current(item).await {
+                    Ok(()) => {
+                        self.streams = None;
+                        self.set_state(State::SelectQuality);
+                    }
+                    Err(err) => self.set_error(err.to_string()),
+                }
+            }
+            State::SelectQuality => {
+                let source = self.state_machine.data().source_mut().unwrap();
+                match source.select_quality_as_current(item) {
+                    Ok(()) => self.set_state(State::SelectPlayer),
+                    Err(err) => self.set_error(err.to_string()),
+                }
+            }
+            State::SelectPlayer | State::LaunchPlayer => {
+                let player = players()[index].clone();
+
+                let installed = match player {
+                    Player::Mpv => mpv::is_installed(),
+                };
+                if !installed {
+                    self.set_error(player.doc().to_string());
+                    return;
+                }
+
+                self.state_machine.data().set_player(player);
+                self.state_machine.set_state(State::LaunchPlayer);
+                self.launch_player().await;
+            }
+        }
+    }
+
+    async fn search(&mut self, query: Query<'_>) {
+        let sources = self.state_machine.data().sources_mut();
+        let tagged = sources.len() > 1;
+
+        let mut errors = Vec::new();
+        // Whether any source has loaded its favorites
+        let mut has_favorites = false;
+        self.hits = search_all(
+            sources,
+            query,
+            future::pending(),
+            |source, result| match result {
+                Ok(_) => has_favorites = true,
+                Err(err) => errors.push(if tagged {
+                    format!("{source}: {err}")
+                } else {
+                    err.to_string()
+                }),
+            },
+        )
+        .await
+        .into_hits();
+
+        if query == Query::Favorites && self.hits.is_empty() && errors.is_empty() {
+            errors.push(if has_favorites {
+                Message::EmptyFavorites.to_string()
+            } else {
+                Message::NoFavorites.to_string()
+            });
+        }
+
+        if errors.is_empty() {
+            self.status = None;
+        } else {
+            self.set_error(errors.join("; "));
+        }
+        self.refresh();
+    }
+
+    async fn launch_player(&mut self) {
+        let data = self.state_machine.data();
+        let player = data.player().unwrap().clone();
+        let source = data.source_mut().unwrap();
+
+        let mut fallbacks = Vec::new();
+        // The downloaded file is played instead of the stream
+        let local_path = local_copy(self.downloads, source);
+        let url = match &local_path {
+            Some(path) => Ok(path.to_string_lossy().into_owned()),
+            None => checked_url_for_stream(source, self.stream_check, |quality, next, err| {
+                fallbacks.push(Message::QualityFallback(quality, next, err).to_string());
+            })
+            .await
+            .map_err(|err| err.to_string()),
+        };
+        let launched = url.and_then(|url| match player {
+            Player::Mpv => mpv::launch(&url).map_err(|err| err.to_string()),
+        });
+        if let Some(path) = &local_path {
+            fallbacks.push(Message::PlayingLocalCopy(&path.display()).to_string());
+        }
+
+        let status = match launched {
+            Ok(()) => match mark_current_episode_watched(self.history, source) {
+                Ok(()) => {
+                    let mut messages = fallbacks;
+                    messages.push(
+                        Message::PlayingEpisode(
+                            &source.episode_info().expect("Episode isn't set"),
+                            &player,
+                        )
+                        .to_string(),
+                    );
+                    if let Some(tracker) = self.tracker {
+                        match track_current_episode(tracker, source).await {
+                            Ok(Some(progress)) => messages.push(progress),
+                            Ok(None) => {}
+                            Err(err) => messages.push(
+                                Message::CantUpdateProgress(tracker.name(), &err).to_string(),
+                            ),
+                        }
+                    }
+
+                    Status {
+                        message: messages.join("; "),
+                        is_error: false,
+                    }
+                }
+                Err(err) => Status {
+                    message: Message::CantSaveHistory(&err).to_string(),
+                    is_error: true,
+                },
+            },
+            Err(err) => {
+                self.set_error(err);
+                self.state_machine.set_previous_state();
+                self.refresh();
+                return;
+            }
+        };
+
+        // Return to the episode grid to pick the next episode
+        self.state_machine
+            .set_previous_state_and_truncate_next(State::SelectEpisode);
+        self.refresh();
+        self.highlight_current_episode();
+        self.move_selection(1);
+        self.status = Some(status);
+    }
+
+    fn set_error(&mut self, message: String) {
+        self.status = Some(Status {
+            message,
+            is_error: true,
+        });
+    }
+}
+
+fn describe_anime<S>(source: &mut S) -> String
+where
+    S: Source,
+{
+    let anime = match source.anime_info() {
+        Ok(anime) => anime.to_string(),
+        Err(err) => return err.to_string(),
+    };
+
+    match source.episodes_info() {
+        Ok(episodes) => format!(
+            "{anime}\n\n{}",
+            Message::Episodes(&episodes.to_string().trim())
+        ),
+        Err(err) => format!("{anime}\n\n{err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::MockSource, tracker::shikimori::Shikimori};
+
+    type MockApp<'a> = App<'a, MockSource, Shikimori>;
+
+    fn sources() -> Vec<MockSource> {
+        vec![MockSource::new("Mock", "http://127.0.0.1")
+            .with_anime("Мадока | Madoka", &["1", "2", "3", "4", "5"])
+            .with_anime("Madoka Movie", &["1"])]
+    }
+
+    /// Press the key and perform the operations it started like the dialog loop does
+    async fn press(app: &mut MockApp<'_>, code: KeyCode) {
+        press_with(app, code, KeyModifiers::NONE).await;
+    }
+
+    async fn press_with(app: &mut MockApp<'_>, code: KeyCode, modifiers: KeyModifiers) {
+        app.handle_key(KeyEvent::new(code, modifiers));
+        while app.pending().is_some() {
+            app.perform_pending().await;
+        }
+    }
+
+    async fn type_text(app: &mut MockApp<'_>, text: &str) {
+        for char in text.chars() {
+            press(app, KeyCode::Char(char)).await;
+        }
+    }
+
+    /// Select the language and the source, the anime query is asked after that
+    async fn select_source(app: &mut MockApp<'_>) {
+        press(app, KeyCode::Enter).await;
+        assert_eq!(app.state(), &State::SelectSource);
+        assert_eq!(app.items(), ["Mock", &Message::AllSources.to_string()]);
+        press(app, KeyCode::Enter).await;
+    }
+
+    #[tokio::test]
+    async fn anime_episode_and_quality_are_selected() {
+        let sources = sources();
+        let (mut history, downloads) = (History::new(None), Downloads::new(None));
+        let stream_check = StreamCheck::disabled();
+        let mut app = MockApp::new(
+            &sources,
+            &mut history,
+            &downloads,
+            None,
+            &stream_check,
+            false,
+        );
+
+        assert_eq!(app.state(), &State::SelectLanguage);
+        assert_eq!(app.selected(), Some(0));
+        select_source(&mut app).await;
+
+        assert_eq!(app.state(), &State::SelectAnime);
+        assert!(app.editing());
+        type_text(&mut app, "madoka").await;
+        assert_eq!(app.query(), "madoka");
+        press(&mut app, KeyCode::Enter).await;
+
+        assert!(!app.editing());
+        assert_eq!(app.items(), ["Мадока | Madoka", "Madoka Movie"]);
+        // The highlighted anime is described
+        assert!(app.details().starts_with("Мадока | Madoka"));
+        press(&mut app, KeyCode::Char('j')).await;
+        assert_eq!(app.selected(), Some(1));
+        assert!(app.details().starts_with("Madoka Movie"));
+        press(&mut app, KeyCode::Up).await;
+        press(&mut app, KeyCode::Enter).await;
+
+        assert_eq!(app.state(), &State::SelectEpisode);
+        assert!(app.is_grid());
+        assert_eq!(app.items(), ["1", "2", "3", "4", "5"]);
+        app.set_grid_columns(3);
+        // Rows of the grid are moved by the count of columns
+        press(&mut app, KeyCode::Down).await;
+        assert_eq!(app.selected(), Some(3));
+        press(&mut app, KeyCode::Char('h')).await;
+        assert_eq!(app.selected(), Some(2));
+        press(&mut app, KeyCode::End).await;
+        assert_eq!(app.selected(), Some(4));
+        press(&mut app, KeyCode::Home).await;
+        press(&mut app, KeyCode::Right).await;
+        press(&mut app, KeyCode::Enter).await;
+
+        assert_eq!(app.state(), &State::SelectQuality);
+        assert_eq!(app.items(), MockSource::QUALITIES);
+        press(&mut app, KeyCode::Char('G')).await;
+        press(&mut app, KeyCode::Enter).await;
+
+        assert_eq!(app.state(), &State::SelectPlayer);
+        assert_eq!(
+            app.breadcrumbs(),
+            [
+                Language::Russian.to_string(),
+                String::from("Mock"),
+                String::from("Мадока | Madoka"),
+                Message::EpisodeNumber("2").to_string(),
+            ]
+        );
+        assert!(!app.quit());
+    }
+
+    #[tokio::test]
+    async fn back_returns_to_previous_states() {
+        let sources = sources();
+        let (mut history, downloads) = (History::new(None), Downloads::new(None));
+        let stream_check = StreamCheck::disabled();
+        let mut app = MockApp::new(
+            &sources,
+            &mut history,
+            &downloads,
+            None,
+            &stream_check,
+            false,
+        );
+
+        select_source(&mut app).await;
+        // The empty query returns to the sources
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.state(), &State::SelectSource);
+        press(&mut app, KeyCode::Enter).await;
+        type_text(&mut app, "madoka").await;
+        press(&mut app, KeyCode::Enter).await;
+        press(&mut app, KeyCode::Enter).await;
+        press(&mut app, KeyCode::Char('l')).await;
+        press(&mut app, KeyCode::Char('l')).await;
+        press(&mut app, KeyCode::Enter).await;
+        assert_eq!(app.state(), &State::SelectQuality);
+
+        // The selected episode stays highlighted
+        press(&mut app, KeyCode::Backspace).await;
+        assert_eq!(app.state(), &State::SelectEpisode);
+        assert_eq!(app.selected(), Some(2));
+
+        // Search results are kept, `/` edits the query again
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.state(), &State::SelectAnime);
+        assert_eq!(app.items().len(), 2);
+        press(&mut app, KeyCode::Char('/')).await;
+        assert!(app.editing());
+        press(&mut app, KeyCode::Backspace).await;
+        assert_eq!(app.query(), "madok");
+        // Esc stops editing without leaving the results
+        press(&mut app, KeyCode::Esc).await;
+        assert!(!app.editing());
+        assert_eq!(app.state(), &State::SelectAnime);
+
+        press(&mut app, KeyCode::Esc).await;
+        press(&mut app, KeyCode::Esc).await;
+        assert_eq!(app.state(), &State::SelectLanguage);
+        assert!(!app.quit());
+        press(&mut app, KeyCode::Esc).await;
+        assert!(app.quit());
+    }
+
+    #[tokio::test]
+    async fn unknown_query_keeps_editing_and_ctrl_c_quits() {
+        let sources = sources();
+        let (mut history, downloads) = (History::new(None), Downloads::new(None));
+        let stream_check = StreamCheck::disabled();
+        let mut app = MockApp::new(
+            &sources,
+            &mut history,
+            &downloads,
+            None,
+            &stream_check,
+            false,
+        );
+
+        select_source(&mut app).await;
+        // The empty query isn't searched
+        press(&mut app, KeyCode::Enter).await;
+        assert!(app.editing());
+        assert!(app.status().is_none());
+
+        type_text(&mut app, "naruto").await;
+        press(&mut app, KeyCode::Enter).await;
+        assert!(app.items().is_empty());
+        assert!(app.editing());
+        let status = app.status().unwrap();
+        assert!(status.is_error);
+        assert_eq!(
+            status.message,
+            Message::EmptyAnimeList("naruto").to_string()
+        );
+
+        // `q` is typed into the query, Ctrl-C quits anyway
+        press(&mut app, KeyCode::Char('q')).await;
+        assert!(!app.quit());
+        press_with(&mut app, KeyCode::Char('c'), KeyModifiers::CONTROL).await;
+        assert!(app.quit());
+    }
+
+    #[tokio::test]
+    async fn favorites_are_listed_instead_of_search_results() {
+        let sources = vec![sources()[0].clone().with_favorites(&["Madoka Movie"])];
+        let (mut history, downloads) = (History::new(None), Downloads::new(None));
+        let stream_check = StreamCheck::disabled();
+        let mut app = MockApp::new(
+            &sources,
+            &mut history,
+            &downloads,
+            None,
+            &stream_check,
+            false,
+        );
+
+        select_source(&mut app).await;
+        press_with(&mut app, KeyCode::Char('f'), KeyModifiers::CONTROL).await;
+        assert_eq!(app.items(), ["Madoka Movie"]);
+        assert!(app.status().is_none());
+
+        type_text(&mut app, "/madoka").await;
+        press(&mut app, KeyCode::Enter).await;
+        assert_eq!(app.items().len(), 2);
+        press(&mut app, KeyCode::Char('f')).await;
+        assert_eq!(app.items(), ["Madoka Movie"]);
+        assert_eq!(app.query(), "");
+    }
+
+    #[tokio::test]
+    async fn favorites_require_logged_in_account() {
+        let sources = sources();
+        let (mut history, downloads) = (History::new(None), Downloads::new(None));
+        let stream_check = StreamCheck::disabled();
+        let mut app = MockApp::new(
+            &sources,
+            &mut history,
+            &downloads,
+            None,
+            &stream_check,
+            true,
+        );
+
+        // Favorites are requested as soon as the sources are selected
+        select_source(&mut app).await;
+        assert!(app.items().is_empty());
+        let status = app.status().unwrap();
+        assert!(status.is_error);
+        assert_eq!(status.message, Message::NoFavorites.to_string());
+    }
+}
@@ -0,0 +1,72 @@
+use super::{app::App, ui};
+
+use crate::{
+    sources::{base::Source, stream::StreamCheck},
+    storage::{downloads::Downloads, history::History},
+    tracker::Tracker,
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyEventKind},
+    DefaultTerminal,
+};
+use std::io;
+
+/// Run the full-screen dialog. \
+/// The terminal is restored on exit and on panic
+/// # Arguments
+/// * `downloads` - Downloaded episodes, they're played from disk instead of streaming
+/// * `tracker` - Tracker whose progress is updated when an episode is watched, `None` to not track progress
+/// * `favorites` - List favorites of the logged in accounts instead of asking the query
+pub async fn run<S, T>(
+    sources: &[S],
+    history: &mut History,
+    downloads: &Downloads,
+    tracker: Option<&T>,
+    stream_check: &StreamCheck,
+    favorites: bool,
+) -> io::Result<()>
+where
+    S: Source,
+    T: Tracker,
+{
+    let mut terminal = ratatui::init();
+    let result = run_app(
+        &mut terminal,
+        &mut App::new(
+            sources,
+            history,
+            downloads,
+            tracker,
+            stream_check,
+            favorites,
+        ),
+    )
+    .await;
+    ratatui::restore();
+
+    result
+}
+
+async fn run_app<S, T>(terminal: &mut DefaultTerminal, app: &mut App<'_, S, T>) -> io::Result<()>
+where
+    S: Source,
+    T: Tracker,
+{
+    while !app.quit() {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        if app.pending().is_some() {
+            app.perform_pending().await;
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use super::app::App;
+
+use crate::{
+    dialog::common::state::State, locale::Message, sources::base::Source, tracker::Tracker,
+};
+
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize as _},
+    text::{Line, Span},
+    widgets::{Block, List, Paragraph, Wrap},
+    Frame,
+};
+
+/// Width of a cell of the episode grid including the gap
+const GRID_CELL_WIDTH: u16 = 7;
+
+pub fn draw<S, T>(frame: &mut Frame, app: &mut App<S, T>)
+where
+    S: Source,
+    T: Tracker,
+{
+    let [header_area, main_area, status_area, help_area] = Layout::vertical([
+        Constraint::Length(if app.state() == &State::SelectAnime {
+            3
+        } else {
+            1
+        }),
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_header(frame, app, header_area);
+
+    let [pane_area, details_area] =
+        Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .areas(main_area);
+
+    if app.is_grid() {
+        draw_grid(frame, app, pane_area);
+    } else {
+        draw_list(frame, app, pane_area);
+    }
+
+    frame.render_widget(
+        Paragraph::new(app.details().to_string())
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(format!(" {} ", Message::Details))),
+        details_area,
+    );
+
+    if let Some(status) = app.status() {
+        let style = if status.is_error {
+            Style::new().fg(Color::Red)
+        } else {
+            Style::new().fg(Color::Blue)
+        };
+        frame.render_widget(
+            Paragraph::new(status.message.as_str()).style(style),
+            status_area,
+        );
+    }
+
+    frame.render_widget(
+        Paragraph::new(help(app)).style(Style::new().add_modifier(Modifier::DIM)),
+        help_area,
+    );
+}
+
+fn draw_header<S, T>(frame: &mut Frame, app: &mut App<S, T>, area: Rect)
+where
+    S: Source,
+    T: Tracker,
+{
+    let mut title = vec![Span::from(env!("CARGO_PKG_NAME")).bold().cyan()];
+    for breadcrumb in app.breadcrumbs() {
+        title.push(Span::from(" › "));
+        title.push(Span::from(breadcrumb));
+    }
+    title.push(Span::from(" › "));
+    title.push(Span::from(app.state().to_string()).bold());
+
+    if app.state() != &State::SelectAnime {
+        frame.render_widget(Paragraph::new(Line::from(title)), area);
+        return;
+    }
+
+    let input_style = if app.editing() {
+        Style::new().fg(Color::Green)
+    } else {
+        Style::new()
+    };
+    let cursor = if app.editing() { "_" } else { "" };
+
+    frame.render_widget(
+        Paragraph::new(format!("{query}{cursor}", query = app.query())).block(
+            Block::bordered()
+                .title(Line::from(title))
+                .border_style(input_style),
+        ),
+        area,
+    );
+}
+
+fn draw_list<S, T>(frame: &mut Frame, app: &mut App<S, T>, area: Rect)
+where
+    S: Source,
+    T: Tracker,
+{
+    let title = match app.state() {
+        State::SelectLanguage => Message::AvailableLanguages,
+        State::SelectSource => Message::AvailableSources,
+        State::SelectAnime => Message::AnimeList,
+        State::SelectEpisode => Message::EpisodeList,
+        State::SelectQuality => Message::Qualities,
+        State::SelectPlayer | State::LaunchPlayer => Message::AvailablePlayers,
+    };
+
+    let list = List::new(app.items().to_vec())
+        .block(Block::bordered().title(format!(" {title} ")))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, app.list_state());
+}
+
+fn draw_grid<S, T>(frame: &mut Frame, app: &mut App<S, T>, area: Rect)
+where
+    S: Source,
+    T: Tracker,
+{
+    let block = Block::bordered().title(format!(" {} ", Message::EpisodeList));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let columns = usize::from((inner.width / GRID_CELL_WIDTH).max(1));
+    app.set_grid_columns(columns);
+
+    let selected = app.selected();
+    let visible_rows = usize::from(inner.height.max(1));
+    // Scroll the grid so the row of the selected episode is visible
+    let first_row = selected
+        .map(|selected| (selected / columns + 1).saturating_sub(visible_rows))
+        .unwrap_or(0);
+
+    let items = app.items().to_vec();
+    let lines = items
+        .chunks(columns)
+        .enumerate()
+        .skip(first_row)
+        .take(visible_rows)
+        .map(|(row, episodes)| {
+            Line::from(
+                episodes
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(column, episode)| {
+                        let index = row * columns + column;
+                        let watched = app.is_watched(episode);
+
+                        let mut style = if watched {
+                            Style::new().fg(Color::Green)
+                        } else {
+                            Style::new()
+                        };
+                        if Some(index) == selected {
+                            style = style.add_modifier(Modifier::REVERSED);
+                        }
+
+                        let mark = if watched { "✓" } else { " " };
+                        let width = usize::from(GRID_CELL_WIDTH) - 3;
+                        [
+                            Span::styled(format!("{episode:>width$}{mark}"), style),
+                            Span::from("  "),
+                        ]
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn help<S, T>(app: &App<S, T>) -> String
+where
+    S: Source,
+    T: Tracker,
+{
+    if app.editing() {
+        return Message::KeysEditing.to_string();
+    }
+
+    match app.state() {
+        State::SelectAnime => Message::KeysAnimeList,
+        State::SelectEpisode => Message::KeysEpisodes,
+        _ => Message::KeysList,
+    }
+    .to_string()
+}
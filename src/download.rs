@@ -0,0 +1,234 @@
+pub mod ffmpeg;
+mod throttle;
+
+pub use throttle::Throttle;
+
+use crate::{
+    errors::DownloadError,
+    locale::Message,
+    sources::{
+        hls::{self, Playlist},
+        http::Http,
+    },
+};
+
+use reqwest::{header::CONTENT_TYPE, Response, Url};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{fs, io::AsyncWriteExt as _};
+
+/// Extensions of files that are kept as is, other streams are saved as MP4
+const VIDEO_EXTENSIONS: [&str; 4] = ["mp4", "mkv", "webm", "ts"];
+
+/// Downloaded file
+pub struct Download {
+    pub path: PathBuf,
+    /// Size in bytes
+    pub size: u64,
+    /// Duration of the video, `None` if it's unknown, e.g. for streams that aren't HLS
+    pub duration: Option<Duration>,
+}
+
+/// Downloader of streams to files. \
+/// Files are written with the `.part` suffix and renamed when the download is finished,
+/// so interrupted downloads aren't mistaken for complete ones
+#[derive(Clone)]
+pub struct Downloader {
+    http: Http,
+    /// `None` if the speed isn't limited
+    throttle: Option<Throttle>,
+}
+
+impl Downloader {
+    #[must_use]
+    pub fn new(http: Http) -> Self {
+        Self {
+            http,
+            throttle: None,
+        }
+    }
+
+    /// Limit the total speed of downloads of the downloader and its clones
+    #[must_use]
+    pub fn with_bandwidth(mut self, bytes_per_sec: u64) -> Self {
+        self.throttle = Some(Throttle::new(bytes_per_sec));
+        self
+    }
+
+    /// Download the stream. \
+    /// Segments of HLS streams are joined into an MPEG-TS file,
+    /// the variant with the highest bitrate of a master playlist is downloaded
+    /// # Arguments
+    /// * `path` - Path of the file without the extension, the extension depends on the stream
+    pub async fn download(&self, url: &str, path: &Path) -> Result<Download, DownloadError> {
+        let response = self.http.get(url).await?;
+
+        let mut duration = None;
+        let (path, body) = if is_playlist(url, &response) {
+            let (segments, total) = self.segments(url, &response.text().await?).await?;
+            duration = Some(total);
+            (path.with_extension("ts"), Body::Segments(segments))
+        } else {
+            let extension = Url::parse(url)
+                .ok()
+                .and_then(|url| {
+                    Path::new(url.path())
+                        .extension()
+                        .map(|extension| extension.to_string_lossy().to_lowercase())
+                })
+                .filter(|extension| VIDEO_EXTENSIONS.contains(&extension.as_str()))
+                .unwrap_or_else(|| String::from("mp4"));
+            (path.with_extension(extension), Body::Response(response))
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let mut part = path.clone().into_os_string();
+        part.push(".part");
+        let part = PathBuf::from(part);
+
+        match self.write(&part, body).await {
+            Ok(size) => {
+                fs::rename(&part, &path).await?;
+                Ok(Download {
+                    path,
+                    size,
+                    duration,
+                })
+            }
+            Err(err) => {
+                // The partial file is useless, the download starts over next time
+                let _ = fs::remove_file(&part).await;
+                Err(err)
+            }
+        }
+    }
+
+    /// Get URLs of segments of the media playlist
+    /// # Arguments
+    /// * `text` - Text of the playlist by the URL
+    /// # Returns
+    /// URLs of the segments and their total duration
+    async fn segments(&self, url: &str, text: &str) -> Result<(Vec<Url>, Duration), DownloadError> {
+        let mut url = parse_url(url)?;
+        let mut playlist = hls::parse(text)?;
+
+        if let Playlist::Master(variants) = &playlist {
+            let variant = variants
+                .iter()
+                .max_by_key(|variant| variant.bandwidth.unwrap_or_default())
+                .ok_or_else(|| {
+                    DownloadError::RequestError(Message::EmptyPlaylist(url.as_str()).to_string())
+                })?;
+
+            url = join_url(&url, &variant.uri)?;
+            playlist = hls::parse(&self.http.get_text(url.as_str(), &[]).await?)?;
+        }
+
+        let Playlist::Media(media) = playlist else {
+            return Err(DownloadError::RequestError(
+                Message::EmptyPlaylist(url.as_str()).to_string(),
+            ));
+        };
+        if media.encrypted {
+            return Err(DownloadError::Unsupported(
+                Message::EncryptedStream.to_string(),
+            ));
+        }
+
+        let segments = media
+            .segments
+            .iter()
+            .map(|segment| join_url(&url, &segment.uri))
+            .collect::<Result<_, _>>()?;
+
+        Ok((segments, media.duration()))
+    }
+
+    async fn write(&self, path: &Path, body: Body) -> Result<u64, DownloadError> {
+        let mut file = fs::File::create(path).await?;
+
+        let size = match body {
+            Body::Response(response) => self.copy(response, &mut file).await?,
+            Body::Segments(segments) => {
+                let mut size = 0;
+                for segment in segments {
+                    let response = self.http.get(segment.as_str()).await?;
+                    size += self.copy(response, &mut file).await?;
+                }
+                size
+            }
+        };
+        file.flush().await?;
+
+        Ok(size)
+    }
+
+    /// Write the response body to the file
+    /// # Returns
+    /// Count of written bytes
+    async fn copy(
+        &self,
+        mut response: Response,
+        file: &mut fs::File,
+    ) -> Result<u64, DownloadError> {
+        let mut size = 0;
+        while let Some(chunk) = self.http.chunk(&mut response).await? {
+            if let Some(throttle) = &self.throttle {
+                throttle.consume(chunk.len()).await;
+            }
+            file.write_all(&chunk).await?;
+            size += chunk.len() as u64;
+        }
+
+        Ok(size)
+    }
+}
+
+/// Replace characters that can't be in file names on some systems, e.g. `Fate/Zero` → `Fate_Zero`
+#[must_use]
+pub fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|char| match char {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            char => char,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Content of a downloaded file
+enum Body {
+    Response(Response),
+    /// URLs of HLS segments
+    Segments(Vec<Url>),
+}
+
+/// Check the response is an HLS playlist by the URL or the content type
+fn is_playlist(url: &str, response: &Response) -> bool {
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    content_type.contains("mpegurl")
+        || Url::parse(url).is_ok_and(|url| url.path().to_lowercase().ends_with(".m3u8"))
+}
+
+fn parse_url(url: &str) -> Result<Url, DownloadError> {
+    Url::parse(url)
+        .map_err(|err| DownloadError::RequestError(Message::ParseError(&err).to_string()))
+}
+
+/// Resolve the URI relative to the playlist
+fn join_url(playlist: &Url, uri: &str) -> Result<Url, DownloadError> {
+    playlist
+        .join(uri)
+        .map_err(|err| DownloadError::RequestError(Message::ParseError(&err).to_string()))
+}
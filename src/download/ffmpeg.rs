@@ -0,0 +1,209 @@
+use crate::{errors::DownloadError, locale::Message, sources::details::EpisodeDetails};
+
+use std::{
+    fmt::Write as _,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+use tokio::{fs, process::Command};
+
+pub fn is_installed() -> bool {
+    match subprocess::Exec::cmd("ffmpeg")
+        .args(&["-version"])
+        .stdout(subprocess::Redirection::Pipe)
+        .stderr(subprocess::Redirection::Merge)
+        .capture()
+    {
+        Ok(output) => output.exit_status.success(),
+        Err(_) => false,
+    }
+}
+
+/// Container of remuxed files
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    Mp4,
+    Mkv,
+}
+
+impl Container {
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Mkv => "mkv",
+        }
+    }
+
+    /// Name of the muxer of ffmpeg
+    fn format(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Mkv => "matroska",
+        }
+    }
+}
+
+/// Metadata embedded into remuxed files
+pub struct Metadata<'a> {
+    pub anime: &'a str,
+    pub episode: &'a str,
+    pub source: &'a str,
+    pub details: &'a EpisodeDetails,
+    /// Duration of the video to end the last chapter, `None` if it's unknown
+    pub duration: Option<Duration>,
+}
+
+/// Remux the video into the container without re-encoding and embed the metadata. \
+/// Chapters are added if the opening or the ending is known. \
+/// The input file is replaced by the remuxed one only if ffmpeg succeeds
+/// # Returns
+/// Path of the remuxed file and its size in bytes
+pub async fn remux(
+    input: &Path,
+    container: Container,
+    metadata: &Metadata<'_>,
+) -> Result<(PathBuf, u64), DownloadError> {
+    let output = input.with_extension(container.extension());
+    let mut part = output.clone().into_os_string();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let metadata_path = input.with_extension("ffmetadata");
+
+    fs::write(&metadata_path, ffmetadata(metadata)).await?;
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-v", "error", "-y", "-i"])
+        .arg(input)
+        .arg("-i")
+        .arg(&metadata_path)
+        .args(["-map", "0:v?", "-map", "0:a?"])
+        .args(["-map_metadata", "1", "-map_chapters", "1", "-c", "copy"])
+        .args(["-f", container.format()]);
+    if container == Container::Mp4 {
+        // Custom tags, e.g. `source` and `voice`, are dropped by the muxer without the flag
+        command.args(["-movflags", "+use_metadata_tags"]);
+    }
+    let result = command
+        .arg(&part)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await;
+
+    let _ = fs::remove_file(&metadata_path).await;
+
+    let failure = match result {
+        Ok(result) if result.status.success() => None,
+        Ok(result) => {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            let reason = stderr.lines().last().unwrap_or_default().trim().to_string();
+            Some(DownloadError::RemuxError(
+                Message::FfmpegFailed(&result.status, &reason).to_string(),
+            ))
+        }
+        Err(err) => Some(err.into()),
+    };
+    if let Some(err) = failure {
+        let _ = fs::remove_file(&part).await;
+        return Err(err);
+    }
+
+    fs::rename(&part, &output).await?;
+    if output != input {
+        fs::remove_file(input).await?;
+    }
+    let size = fs::metadata(&output).await?.len();
+
+    Ok((output, size))
+}
+
+/// Write the metadata in the format of ffmpeg metadata files
+fn ffmetadata(metadata: &Metadata) -> String {
+    let mut text = String::from(";FFMETADATA1\n");
+
+    let mut tags = vec![
+        (
+            "title",
+            Message::EpisodeTitle(metadata.anime, metadata.episode).to_string(),
+        ),
+        ("show", metadata.anime.to_string()),
+        ("episode_id", metadata.episode.to_string()),
+        ("source", metadata.source.to_string()),
+    ];
+    if metadata.episode.parse::<u32>().is_ok() {
+        tags.push(("episode_sort", metadata.episode.to_string()));
+    }
+    if !metadata.details.voice.is_empty() {
+        tags.push(("voice", metadata.details.voice.join(", ")));
+    }
+    for (key, value) in tags {
+        let _ = writeln!(text, "{key}={}", escape(&value));
+    }
+
+    for (range, title) in chapters(metadata.details, metadata.duration) {
+        let _ = write!(
+            text,
+            "[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            range.start.as_millis(),
+            range.end.as_millis(),
+            escape(&title)
+        );
+    }
+
+    text
+}
+
+/// Split the video into chapters by the opening and the ending
+/// # Returns
+/// Chapters in order, no chapters if the opening and the ending are unknown
+fn chapters(
+    details: &EpisodeDetails,
+    duration: Option<Duration>,
+) -> Vec<(Range<Duration>, String)> {
+    let mut markers = [
+        (&details.opening, Message::ChapterOpening),
+        (&details.ending, Message::ChapterEnding),
+    ]
+    .into_iter()
+    .filter_map(|(range, title)| range.clone().map(|range| (range, title.to_string())))
+    .collect::<Vec<_>>();
+    markers.sort_by_key(|(range, _)| range.start);
+
+    let mut chapters = Vec::new();
+    let mut end = Duration::ZERO;
+    for (range, title) in markers {
+        // Overlapping markers are wrong
+        if range.start < end {
+            continue;
+        }
+        if range.start > end {
+            chapters.push((end..range.start, Message::ChapterEpisode.to_string()));
+        }
+        end = range.end;
+        chapters.push((range, title));
+    }
+
+    if let Some(duration) = duration.filter(|duration| !chapters.is_empty() && *duration > end) {
+        chapters.push((end..duration, Message::ChapterEpisode.to_string()));
+    }
+
+    chapters
+}
+
+/// Escape special characters of values of metadata files
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for char in value.chars() {
+        if matches!(char, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(char);
+    }
+
+    escaped
+}
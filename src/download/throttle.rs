@@ -0,0 +1,39 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::{self, Instant};
+
+/// Limit of the total download speed shared by concurrent downloads
+#[derive(Clone)]
+pub struct Throttle {
+    bytes_per_sec: u64,
+    /// Time the already received bytes are allowed at
+    next_at: Arc<Mutex<Instant>>,
+}
+
+impl Throttle {
+    /// # Arguments
+    /// * `bytes_per_sec` - Maximum speed, it must be positive
+    #[must_use]
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec.max(1),
+            next_at: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Wait until the received bytes fit into the limit
+    pub async fn consume(&self, bytes: usize) {
+        let wait_until = {
+            let mut next_at = self.next_at.lock().expect("Throttle isn't poisoned");
+            let bytes = u64::try_from(bytes).unwrap_or(u64::MAX);
+            let nanos = bytes.saturating_mul(1_000_000_000) / self.bytes_per_sec;
+
+            *next_at = (*next_at).max(Instant::now()) + Duration::from_nanos(nanos);
+            *next_at
+        };
+
+        time::sleep_until(wait_until).await;
+    }
+}
@@ -1,2 +1,5 @@
+pub mod color_mode;
 pub mod language;
+pub mod locale;
+pub mod picker;
 pub mod player;
@@ -0,0 +1,123 @@
+use crate::{errors::ColorModeError, locale::Message};
+
+use std::{
+    env,
+    ffi::OsString,
+    fmt::{self, Display},
+    io::{self, IsTerminal as _},
+};
+use termcolor::ColorChoice;
+
+/// When to use colors in the output
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ColorMode {
+    /// Use colors if the output is a terminal and `NO_COLOR` isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Resolve the mode to a color choice for the standard output
+    #[must_use]
+    pub fn color_choice(self) -> ColorChoice {
+        match self {
+            Self::Always => ColorChoice::Always,
+            Self::Never => ColorChoice::Never,
+            Self::Auto => auto_choice(env::var_os("NO_COLOR"), io::stdout().is_terminal()),
+        }
+    }
+}
+
+/// Decide whether to use colors in the auto mode
+/// # Arguments
+/// * `no_color` - Value of the `NO_COLOR` variable, see https://no-color.org
+/// * `is_terminal` - Whether the output is a terminal
+fn auto_choice(no_color: Option<OsString>, is_terminal: bool) -> ColorChoice {
+    let no_color = no_color.is_some_and(|value| !value.is_empty());
+
+    if no_color || !is_terminal {
+        ColorChoice::Never
+    } else {
+        ColorChoice::Auto
+    }
+}
+
+impl TryFrom<&str> for ColorMode {
+    type Error = ColorModeError;
+
+    fn try_from(mode: &str) -> Result<Self, Self::Error> {
+        match mode.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "always" | "yes" | "on" => Ok(Self::Always),
+            "never" | "no" | "off" => Ok(Self::Never),
+            _ => Err(ColorModeError::UnknownColorMode(
+                Message::UnknownColorMode(mode).to_string(),
+            )),
+        }
+    }
+}
+
+impl Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Always => write!(f, "always"),
+            Self::Never => write!(f, "never"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modes_are_parsed_with_aliases() {
+        assert_eq!(ColorMode::try_from("auto").unwrap(), ColorMode::Auto);
+        assert_eq!(ColorMode::try_from("Always").unwrap(), ColorMode::Always);
+        assert_eq!(ColorMode::try_from("on").unwrap(), ColorMode::Always);
+        assert_eq!(ColorMode::try_from("NO").unwrap(), ColorMode::Never);
+        assert_eq!(ColorMode::try_from("off").unwrap(), ColorMode::Never);
+
+        for mode in [ColorMode::Auto, ColorMode::Always, ColorMode::Never] {
+            assert_eq!(
+                ColorMode::try_from(mode.to_string().as_str()).unwrap(),
+                mode
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_mode_is_rejected() {
+        let err = ColorMode::try_from("rainbow").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            Message::UnknownColorMode("rainbow").to_string()
+        );
+        assert!(ColorMode::try_from("").is_err());
+    }
+
+    #[test]
+    fn explicit_modes_ignore_the_environment() {
+        assert_eq!(ColorMode::Always.color_choice(), ColorChoice::Always);
+        assert_eq!(ColorMode::Never.color_choice(), ColorChoice::Never);
+    }
+
+    #[test]
+    fn colors_are_used_only_in_terminal_without_no_color() {
+        assert_eq!(auto_choice(None, true), ColorChoice::Auto);
+        assert_eq!(auto_choice(None, false), ColorChoice::Never);
+        assert_eq!(
+            auto_choice(Some(OsString::from("1")), true),
+            ColorChoice::Never
+        );
+        assert_eq!(
+            auto_choice(Some(OsString::from("1")), false),
+            ColorChoice::Never
+        );
+        // An empty `NO_COLOR` doesn't disable colors
+        assert_eq!(auto_choice(Some(OsString::new()), true), ColorChoice::Auto);
+    }
+}
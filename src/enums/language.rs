@@ -1,38 +1,98 @@
-use crate::errors::LanguageError;
+use crate::{errors::LanguageError, locale::Message};
 
 use std::fmt::{self, Display};
 
-#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[derive(Debug, Default, Clone, Eq, Hash, PartialEq)]
 pub enum Language {
+    English,
     Russian,
+    Ukrainian,
+    Japanese,
+    Chinese,
+    Korean,
+    Spanish,
+    Portuguese,
+    French,
+    German,
+    Italian,
+    /// Not a language of a source, but the mode to use sources of all languages
+    #[default]
     All,
 }
 
+impl Language {
+    /// ISO 639-1 code of the language
+    /// # Returns
+    /// `None` for [`Language::All`]
+    #[must_use]
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            Self::English => Some("en"),
+            Self::Russian => Some("ru"),
+            Self::Ukrainian => Some("uk"),
+            Self::Japanese => Some("ja"),
+            Self::Chinese => Some("zh"),
+            Self::Korean => Some("ko"),
+            Self::Spanish => Some("es"),
+            Self::Portuguese => Some("pt"),
+            Self::French => Some("fr"),
+            Self::German => Some("de"),
+            Self::Italian => Some("it"),
+            Self::All => None,
+        }
+    }
+
+    /// Check if a source of the language can be used in this language mode
+    /// # Arguments
+    /// * `language` - Language of the source
+    /// # Returns
+    /// `true` if the languages are the same or this is [`Language::All`]
+    #[must_use]
+    pub fn includes(&self, language: &Language) -> bool {
+        self == &Self::All || self == language
+    }
+}
+
 impl TryFrom<&str> for Language {
     type Error = LanguageError;
 
+    /// Parse the language by its English name, ISO 639-1 or ISO 639-2 code
     fn try_from(language: &str) -> Result<Self, Self::Error> {
         match language.to_lowercase().as_str() {
+            "english" | "eng" | "en" => Ok(Self::English),
             "russian" | "rus" | "ru" => Ok(Self::Russian),
-            "all" => Ok(Self::All),
-            _ => Err(LanguageError::UnknownLanguage(format!(
-                "Unknown language `{language}`"
-            ))),
+            "ukrainian" | "ukr" | "uk" | "ua" => Ok(Self::Ukrainian),
+            "japanese" | "jpn" | "ja" | "jp" => Ok(Self::Japanese),
+            "chinese" | "chi" | "zho" | "zh" => Ok(Self::Chinese),
+            "korean" | "kor" | "ko" => Ok(Self::Korean),
+            "spanish" | "spa" | "es" => Ok(Self::Spanish),
+            "portuguese" | "por" | "pt" => Ok(Self::Portuguese),
+            "french" | "fre" | "fra" | "fr" => Ok(Self::French),
+            "german" | "ger" | "deu" | "de" => Ok(Self::German),
+            "italian" | "ita" | "it" => Ok(Self::Italian),
+            "all" | "all languages" | "any" => Ok(Self::All),
+            _ => Err(LanguageError::UnknownLanguage(
+                Message::UnknownLanguage(language).to_string(),
+            )),
         }
     }
 }
 
-impl Default for Language {
-    fn default() -> Self {
-        Self::All
-    }
-}
-
 impl Display for Language {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Language::English => write!(f, "English"),
             Language::Russian => write!(f, "Russian"),
-            Language::All => unreachable!(),
+            Language::Ukrainian => write!(f, "Ukrainian"),
+            Language::Japanese => write!(f, "Japanese"),
+            Language::Chinese => write!(f, "Chinese"),
+            Language::Korean => write!(f, "Korean"),
+            Language::Spanish => write!(f, "Spanish"),
+            Language::Portuguese => write!(f, "Portuguese"),
+            Language::French => write!(f, "French"),
+            Language::German => write!(f, "German"),
+            Language::Italian => write!(f, "Italian"),
+            Language::All => write!(f, "All languages"),
         }
     }
 }
@@ -0,0 +1,58 @@
+use crate::{errors::LocaleError, locale::Message};
+
+use std::{
+    env,
+    fmt::{self, Display},
+};
+
+/// Language of the user interface
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Locale {
+    #[default]
+    English,
+    Russian,
+}
+
+impl Locale {
+    /// Get the locale from `LC_ALL`, `LC_MESSAGES` or `LANG` environment variables
+    /// # Returns
+    /// `None` if the variables aren't set or the locale isn't supported
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::try_from(value.as_str()).ok())
+    }
+}
+
+impl TryFrom<&str> for Locale {
+    type Error = LocaleError;
+
+    /// Parse the locale by its name, language code or POSIX locale, e.g. `ru_RU.UTF-8`
+    fn try_from(locale: &str) -> Result<Self, Self::Error> {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match language.as_str() {
+            "english" | "en" | "c" | "posix" => Ok(Self::English),
+            "russian" | "ru" => Ok(Self::Russian),
+            _ => Err(LocaleError::UnknownLocale(
+                Message::UnknownLocale(locale).to_string(),
+            )),
+        }
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::English => write!(f, "en"),
+            Self::Russian => write!(f, "ru"),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{errors::PickerError, locale::Message};
+
+use std::fmt::{self, Display};
+
+/// External fuzzy picker used to select variants instead of numbered lists
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Picker {
+    Fzf,
+    Skim,
+}
+
+impl Picker {
+    /// Name of the executable
+    #[must_use]
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Fzf => "fzf",
+            Self::Skim => "sk",
+        }
+    }
+}
+
+impl TryFrom<&str> for Picker {
+    type Error = PickerError;
+
+    fn try_from(picker: &str) -> Result<Self, Self::Error> {
+        match picker.to_lowercase().as_str() {
+            "fzf" => Ok(Self::Fzf),
+            "skim" | "sk" => Ok(Self::Skim),
+            _ => Err(PickerError::UnknownPicker(
+                Message::UnknownPicker(picker).to_string(),
+            )),
+        }
+    }
+}
+
+impl Display for Picker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fzf => write!(f, "fzf"),
+            Self::Skim => write!(f, "skim"),
+        }
+    }
+}
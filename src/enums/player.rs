@@ -1,4 +1,4 @@
-use crate::errors::PlayerError;
+use crate::{errors::PlayerError, locale::Message};
 
 use std::fmt::{self, Display};
 
@@ -12,16 +12,10 @@ pub const fn players() -> &'static [Player] {
 }
 
 impl Player {
+    /// Help on installing the player, shown when it can't be launched
     #[must_use]
-    pub fn doc(&self) -> &str {
-        match self {
-            Player::Mpv => {
-                "Are you sure you have MPV installed? \
-                Try running `mpv --version` in your terminal. \
-                If you don't have MPV installed, you can install it from https://mpv.io/installation/ \
-                or use another player."
-            }
-        }
+    pub fn doc(&self) -> String {
+        Message::PlayerHelp(self).to_string()
     }
 }
 
@@ -31,9 +25,9 @@ impl TryFrom<&str> for Player {
     fn try_from(player: &str) -> Result<Self, Self::Error> {
         match player.to_lowercase().as_str() {
             "mpv" => Ok(Self::Mpv),
-            _ => Err(PlayerError::UnknownPlayer(format!(
-                "Unknown player `{player}`"
-            ))),
+            _ => Err(PlayerError::UnknownPlayer(
+                Message::UnknownPlayer(player).to_string(),
+            )),
         }
     }
 }
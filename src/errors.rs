@@ -1,4 +1,9 @@
-use std::fmt::{self, Display};
+use crate::locale::Message;
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
 
 #[derive(Debug)]
 pub enum SourceError {
@@ -17,24 +22,43 @@ impl Display for SourceError {
     }
 }
 
+impl Error for SourceError {}
+
 impl From<reqwest::Error> for SourceError {
     fn from(error: reqwest::Error) -> Self {
-        Self::ApiError(format!("Api error: {error}"))
+        let host = error
+            .url()
+            .and_then(reqwest::Url::host_str)
+            .unwrap_or_default();
+        let status = error.status();
+
+        let message = if error.is_timeout() {
+            Message::Timeout(host)
+        } else if error.is_connect() {
+            Message::ConnectError(host)
+        } else if let Some(status) = &status {
+            Message::HttpStatus(host, status)
+        } else {
+            Message::ApiError(&error)
+        };
+
+        Self::ApiError(message.to_string())
     }
 }
 
 impl From<serde_json::Error> for SourceError {
     fn from(error: serde_json::Error) -> Self {
-        Self::ParseError(format!("Parse error: {error}"))
+        Self::ParseError(Message::ParseError(&error).to_string())
     }
 }
 
 impl From<&str> for SourceError {
     fn from(value: &str) -> Self {
-        Self::UnknownVariant(format!("Unknown variant: {value}"))
+        Self::UnknownVariant(Message::UnknownVariant(&value).to_string())
     }
 }
 
+#[derive(Debug)]
 pub enum LanguageError {
     UnknownLanguage(String),
 }
@@ -47,6 +71,9 @@ impl Display for LanguageError {
     }
 }
 
+impl Error for LanguageError {}
+
+#[derive(Debug)]
 pub enum PlayerError {
     UnknownPlayer(String),
 }
@@ -59,6 +86,28 @@ impl Display for PlayerError {
     }
 }
 
+impl Error for PlayerError {}
+
+#[derive(Debug)]
+pub enum PickerError {
+    UnknownPicker(String),
+    NotInstalled(String),
+    LaunchError(String),
+}
+
+impl Display for PickerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPicker(message)
+            | Self::NotInstalled(message)
+            | Self::LaunchError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for PickerError {}
+
+#[derive(Debug)]
 pub enum StateError {
     UnknownState(String),
 }
@@ -70,3 +119,253 @@ impl Display for StateError {
         }
     }
 }
+
+impl Error for StateError {}
+
+#[derive(Debug)]
+pub enum ColorModeError {
+    UnknownColorMode(String),
+}
+
+impl Display for ColorModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownColorMode(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for ColorModeError {}
+
+#[derive(Debug)]
+pub enum LocaleError {
+    UnknownLocale(String),
+}
+
+impl Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownLocale(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for LocaleError {}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    UnknownTheme(String),
+    InvalidStyle(String),
+}
+
+impl Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTheme(message) | Self::InvalidStyle(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for ThemeError {}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    InvalidArgument(String),
+    ReadError(String),
+    ParseError(String),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidArgument(message)
+            | Self::ReadError(message)
+            | Self::ParseError(message) => {
+                write!(f, "{message}")
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<ColorModeError> for ConfigError {
+    fn from(error: ColorModeError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<LocaleError> for ConfigError {
+    fn from(error: LocaleError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<PickerError> for ConfigError {
+    fn from(error: PickerError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<ThemeError> for ConfigError {
+    fn from(error: ThemeError) -> Self {
+        Self::ParseError(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::ParseError(format!("Config parse error: {error}"))
+    }
+}
+
+#[derive(Debug)]
+pub enum DownloadError {
+    RequestError(String),
+    IoError(String),
+    Unsupported(String),
+    RemuxError(String),
+}
+
+impl Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RequestError(message)
+            | Self::IoError(message)
+            | Self::Unsupported(message)
+            | Self::RemuxError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for DownloadError {}
+
+impl From<SourceError> for DownloadError {
+    fn from(error: SourceError) -> Self {
+        Self::RequestError(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for DownloadError {
+    fn from(error: reqwest::Error) -> Self {
+        SourceError::from(error).into()
+    }
+}
+
+impl From<std::io::Error> for DownloadError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    SourceError(String),
+    IoError(String),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SourceError(message) | Self::IoError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl From<SourceError> for ExportError {
+    fn from(error: SourceError) -> Self {
+        Self::SourceError(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum TrackerError {
+    NotConfigured(String),
+    NotLinked(String),
+    NotFound(String),
+    RequestError(String),
+    ParseError(String),
+    IoError(String),
+}
+
+impl Display for TrackerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConfigured(message)
+            | Self::NotLinked(message)
+            | Self::NotFound(message)
+            | Self::RequestError(message)
+            | Self::ParseError(message)
+            | Self::IoError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for TrackerError {}
+
+impl From<SourceError> for TrackerError {
+    fn from(error: SourceError) -> Self {
+        Self::RequestError(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for TrackerError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::RequestError(SourceError::from(error).to_string())
+    }
+}
+
+impl From<serde_json::Error> for TrackerError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::ParseError(Message::ParseError(&error).to_string())
+    }
+}
+
+impl From<std::io::Error> for TrackerError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum HookError {
+    CommandError(String),
+    WebhookError(String),
+}
+
+impl Display for HookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CommandError(message) | Self::WebhookError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for HookError {}
+
+#[derive(Debug)]
+pub enum ServerError {
+    BindError(String),
+    InvalidUrl(String),
+    ServeError(String),
+}
+
+impl Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BindError(message) | Self::InvalidUrl(message) | Self::ServeError(message) => {
+                write!(f, "{message}")
+            }
+        }
+    }
+}
+
+impl Error for ServerError {}
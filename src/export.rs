@@ -0,0 +1,225 @@
+pub mod nfo;
+
+use crate::{
+    download::file_name,
+    errors::{ExportError, SourceError},
+    locale::Message,
+    sources::{base::Source, details::EpisodeDetails, search::find_anime},
+    storage::{downloads::Downloads, watchlist},
+};
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Settings of the `export-library` command
+#[derive(Clone, Default)]
+pub struct ExportConfig {
+    /// Directory of the library, `None` if it isn't set and there is no videos directory
+    pub dir: Option<PathBuf>,
+    /// Quality of streams, `None` for the highest quality
+    pub quality: Option<String>,
+}
+
+/// Counts of anime and episodes processed by [`run`]
+#[derive(Default)]
+pub struct Report {
+    pub titles: usize,
+    pub episodes: usize,
+    pub failed: usize,
+}
+
+/// Export anime of the watchlist as a library of Kodi or Jellyfin. \
+/// Each anime gets a directory with `tvshow.nfo`, each episode gets a `.strm` file with the URL of its stream
+/// or the path of its downloaded file and an NFO file, e.g. `Madoka/Madoka S01E01.strm`. \
+/// Progress is printed
+/// # Arguments
+/// * `quality` - Quality of streams, `None` for the highest quality
+pub async fn run<S: Source>(
+    sources: &[S],
+    watchlist: &[watchlist::Entry],
+    downloads: &Downloads,
+    dir: &Path,
+    quality: Option<&str>,
+) -> Report {
+    let mut report = Report::default();
+
+    for entry in watchlist {
+        match export_anime(sources, entry, downloads, dir, quality, &mut report).await {
+            Ok(()) => report.titles += 1,
+            Err(err) => {
+                eprintln!("{}", Message::CantExport(&entry.anime, &err));
+                report.failed += 1;
+            }
+        }
+    }
+
+    report
+}
+
+async fn export_anime<S: Source>(
+    sources: &[S],
+    entry: &watchlist::Entry,
+    downloads: &Downloads,
+    dir: &Path,
+    quality: Option<&str>,
+    report: &mut Report,
+) -> Result<(), ExportError> {
+    let mut source = sources
+        .iter()
+        .find(|source| **source == entry.source)
+        .cloned()
+        .ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownSource(&entry.source).to_string())
+        })?;
+
+    find_anime(&mut source, &entry.anime).await?;
+    let details = source.anime_details()?;
+    let anime = source.anime_info()?.to_string();
+
+    let title = file_name(&details.title);
+    let dir = dir.join(&title);
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join("tvshow.nfo"),
+        nfo::tvshow(&details, &source.to_string()),
+    )?;
+    println!("{}", Message::Exporting(&details.title, &dir.display()));
+
+    for (index, episode) in source.episodes()?.iter().enumerate() {
+        // Media servers recognize episodes by numbers
+        let number = episode
+            .parse::<u32>()
+            .unwrap_or_else(|_| u32::try_from(index + 1).unwrap_or(u32::MAX));
+
+        let (target, episode_details) =
+            match target(&mut source, &anime, episode, downloads, quality).await {
+                Ok(target) => target,
+                // Other episodes may be available
+                Err(err) => {
+                    eprintln!("{}", Message::CantExportEpisode(&anime, episode, &err));
+                    report.failed += 1;
+                    continue;
+                }
+            };
+
+        let stem = format!("{title} S01E{number:02}");
+        fs::write(dir.join(format!("{stem}.strm")), format!("{target}\n"))?;
+        fs::write(
+            dir.join(format!("{stem}.nfo")),
+            nfo::episode(
+                &details.title,
+                &Message::EpisodeNumber(episode).to_string(),
+                number,
+                &episode_details,
+            ),
+        )?;
+        report.episodes += 1;
+    }
+
+    Ok(())
+}
+
+/// Get the target of the `.strm` file of the episode
+/// # Returns
+/// The path of the downloaded file or the URL of the stream and details of the episode
+async fn target<S: Source>(
+    source: &mut S,
+    anime: &str,
+    episode: &str,
+    downloads: &Downloads,
+    quality: Option<&str>,
+) -> Result<(String, EpisodeDetails), SourceError> {
+    source
+        .select_episode_as_current(episode.to_string())
+        .await?;
+    let details = source.episode_details();
+
+    if let Some(path) = downloads.find_file(&source.to_string(), anime, episode) {
+        return Ok((path.to_string_lossy().into_owned(), details));
+    }
+
+    source.select_quality_as_current(quality.unwrap_or("max").to_string())?;
+    let url = source.url_for_stream().await?;
+
+    Ok((url, details))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{downloads::Entry, watchlist::Watchlist},
+        testing::{anilibria, anilibria_anime, temp_dir, MockServer},
+    };
+
+    use serde_json::json;
+
+    const TITLE: &str = "Девочка-волшебница Мадока★Магика | Mahou Shoujo Madoka★Magica";
+
+    #[tokio::test]
+    async fn anime_of_several_names_is_exported() {
+        // The API finds nothing by both names
+        let server = MockServer::start(|request| {
+            if request.path.contains("%7C") {
+                return (200, String::from("[]"));
+            }
+            let mut anime = anilibria_anime(
+                9000,
+                "Девочка-волшебница Мадока★Магика",
+                "Mahou Shoujo Madoka★Magica",
+                2,
+            );
+            anime["player"]["playlist"]["2"]["hls"]["hd"] =
+                json!("https://cdn.test/madoka/2/720/index.m3u8");
+            (200, json!([anime]).to_string())
+        });
+        let sources = [anilibria(&server)];
+
+        let dir = temp_dir("export");
+        let video = dir.join("01.ts");
+        fs::write(&video, "video").unwrap();
+        let mut downloads = Downloads::new(None);
+        downloads
+            .add(Entry {
+                source: String::from("Anilibria"),
+                anime: TITLE.to_string(),
+                episode: String::from("1"),
+                quality: String::from("hd"),
+                path: video.clone(),
+                size: 5,
+                downloaded_at: 0,
+            })
+            .unwrap();
+        let mut watchlist = Watchlist::new(None);
+        watchlist.add("Anilibria", TITLE).unwrap();
+
+        let library = dir.join("library");
+        let report = run(
+            &sources,
+            watchlist.entries(),
+            &downloads,
+            &library,
+            Some("hd"),
+        )
+        .await;
+
+        assert_eq!((report.titles, report.episodes, report.failed), (1, 2, 0));
+        let show = library.join("Девочка-волшебница Мадока★Магика");
+        let tvshow = fs::read_to_string(show.join("tvshow.nfo")).unwrap();
+        assert!(tvshow.contains("Mahou Shoujo Madoka★Magica"), "{tvshow}");
+        // The downloaded episode is played from its file
+        assert_eq!(
+            fs::read_to_string(show.join("Девочка-волшебница Мадока★Магика S01E01.strm")).unwrap(),
+            format!("{}\n", video.display())
+        );
+        assert_eq!(
+            fs::read_to_string(show.join("Девочка-волшебница Мадока★Магика S01E02.strm")).unwrap(),
+            "https://cdn.test/madoka/2/720/index.m3u8\n"
+        );
+        assert!(show
+            .join("Девочка-волшебница Мадока★Магика S01E02.nfo")
+            .exists());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::sources::details::{AnimeDetails, EpisodeDetails};
+
+use std::fmt::Write as _;
+
+const HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+
+/// Write `tvshow.nfo` of the anime
+/// # Arguments
+/// * `source` - Name of the source, it's shown as the studio
+#[must_use]
+pub fn tvshow(details: &AnimeDetails, source: &str) -> String {
+    let mut nfo = format!("{HEADER}<tvshow>\n");
+
+    tag(&mut nfo, "title", &details.title);
+    if let Some(original_title) = &details.original_title {
+        tag(&mut nfo, "originaltitle", original_title);
+    }
+    if let Some(description) = &details.description {
+        tag(&mut nfo, "plot", description);
+    }
+    if let Some(year) = details.year {
+        tag(&mut nfo, "year", &year.to_string());
+    }
+    for genre in &details.genres {
+        tag(&mut nfo, "genre", genre);
+    }
+    if let Some(poster) = &details.poster {
+        let _ = writeln!(
+            nfo,
+            "    <thumb aspect=\"poster\">{}</thumb>",
+            escape(poster)
+        );
+    }
+    tag(&mut nfo, "studio", source);
+
+    nfo.push_str("</tvshow>\n");
+    nfo
+}
+
+/// Write the NFO file of the episode
+/// # Arguments
+/// * `title` - Title of the episode if the source doesn't know its name
+/// * `number` - Number of the episode in the first season
+#[must_use]
+pub fn episode(anime: &str, title: &str, number: u32, details: &EpisodeDetails) -> String {
+    let mut nfo = format!("{HEADER}<episodedetails>\n");
+
+    tag(&mut nfo, "title", details.title.as_deref().unwrap_or(title));
+    tag(&mut nfo, "showtitle", anime);
+    tag(&mut nfo, "season", "1");
+    tag(&mut nfo, "episode", &number.to_string());
+    for name in &details.voice {
+        let _ = writeln!(
+            nfo,
+            "    <actor>\n        <name>{}</name>\n        <role>Voice</role>\n    </actor>",
+            escape(name)
+        );
+    }
+
+    nfo.push_str("</episodedetails>\n");
+    nfo
+}
+
+fn tag(nfo: &mut String, name: &str, value: &str) {
+    let _ = writeln!(nfo, "    <{name}>{}</{name}>", escape(value));
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,51 @@
+//! Search anime in sources, select episodes and qualities and play streams. \
+//! The `ani_cli_rs` binary is a front-end over this library,
+//! other tools can use the sources and players directly:
+//! ```no_run
+//! use ani_cli_rs::{players::mpv, Anilibria, Http, Source, SourceError};
+//!
+//! async fn play(query: &str) -> Result<(), SourceError> {
+//!     let mut source = Anilibria::new(Http::default());
+//!     source.search_anime_list(query).await?;
+//!     source.select_anime_as_current(String::from("1")).await?;
+//!     source.select_episode_as_current(String::from("first")).await?;
+//!     source.select_quality_as_current(String::from("max"))?;
+//!
+//!     let url = source.url_for_stream().await?;
+//!     if mpv::is_installed() {
+//!         mpv::launch(&url).expect("mpv is launched");
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//! The front-end of the binary is [`run`],
+//! the items re-exported here are the stable API
+
+mod app;
+pub(crate) mod config;
+pub(crate) mod dialog;
+pub(crate) mod download;
+pub(crate) mod enums;
+pub(crate) mod errors;
+pub(crate) mod export;
+pub(crate) mod locale;
+pub mod players;
+pub(crate) mod server;
+pub(crate) mod sources;
+pub(crate) mod storage;
+pub(crate) mod sync;
+pub(crate) mod tracker;
+pub(crate) mod watch;
+
+#[cfg(test)]
+mod testing;
+
+pub use app::run;
+pub use enums::{language::Language, player::Player};
+pub use errors::{ConfigError, LanguageError, PlayerError, SourceError};
+pub use sources::{
+    any::AnySource,
+    base::Source,
+    http::{Http, HttpConfig},
+    ru::anilibria::Anilibria,
+};
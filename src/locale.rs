@@ -0,0 +1,25 @@
+mod en;
+mod message;
+mod ru;
+
+pub use message::Message;
+
+use crate::enums::locale::Locale;
+
+use std::sync::OnceLock;
+
+static CURRENT: OnceLock<Locale> = OnceLock::new();
+
+/// Set the locale of the user interface. \
+/// The locale can be set only once, next calls are ignored
+pub fn set(locale: Locale) {
+    let _ = CURRENT.set(locale);
+}
+
+/// Locale of the user interface
+/// # Returns
+/// The locale set by [`set`] or the default locale
+#[must_use]
+pub fn current() -> Locale {
+    CURRENT.get().copied().unwrap_or_default()
+}
@@ -0,0 +1,314 @@
+use super::Message;
+
+use crate::enums::player::Player;
+
+/// English texts of the messages
+pub fn text(message: &Message) -> String {
+    match message {
+        Message::Bye => String::from("Bye, peach!"),
+
+        Message::AvailableLanguages => String::from("Available languages"),
+        Message::BackOnEmptyInput => String::from("enter empty input to back previous state"),
+        Message::SelectLanguage => String::from("Select a language"),
+        Message::LanguageSources(language, count) => format!("{language} ({count} sources)"),
+        Message::NoSourcesOfLanguage(language) => {
+            format!("There are no sources of language `{language}`")
+        }
+        Message::UnknownLanguage(language) => format!("Unknown language `{language}`"),
+        Message::UnknownLanguageSeqNum(seq_num) => {
+            format!("Unknown language sequence number `{seq_num}`")
+        }
+
+        Message::AvailableSources => String::from("Available sources"),
+        Message::AllSources => String::from("All sources"),
+        Message::SelectSource => String::from("Select a source"),
+        Message::UnknownSource(source) => format!("Unknown source `{source}`"),
+        Message::UnknownSourceSeqNum(seq_num) => {
+            format!("Unknown source sequence number `{seq_num}`")
+        }
+
+        Message::EnterAnimeName => String::from("Enter anime name"),
+        Message::Searching => String::from("Searching... (press Ctrl-C to cancel)"),
+        Message::SearchCancelled => String::from("Search is cancelled"),
+        Message::AnimeList => String::from("Anime list"),
+        Message::EmptyAnimeList(query) => format!("Anime list by query `{query}` is empty"),
+        Message::SelectAnime => String::from("Select anime"),
+        Message::SelectedAnime(anime) => format!("Selected anime `{anime}`"),
+        Message::UnknownAnimeName(name) => format!("Unknown anime name `{name}`"),
+        Message::UnknownAnimeSeqNum(seq_num) => {
+            format!("Unknown anime sequence number `{seq_num}`")
+        }
+
+        Message::Episodes(episodes) => format!("Episodes: {episodes}"),
+        Message::SelectEpisode => String::from("Select an episode"),
+        Message::SelectedEpisode(episode) => format!("Selected episode `{episode}`"),
+        Message::UnknownEpisodeNumber(number) => format!("Unknown episode number `{number}`"),
+        Message::UnknownEpisodePattern(pattern) => {
+            format!("Unknown episode pattern `{pattern}`. Possible patterns: first|f, last|l")
+        }
+
+        Message::Qualities => String::from("Qualities"),
+        Message::SelectQuality => String::from("Select a quality"),
+        Message::UnknownQuality(quality) => format!("Unknown quality `{quality}`"),
+        Message::QualityFallback(quality, next, err) => {
+            format!("Quality `{quality}` is unavailable ({err}), trying `{next}`")
+        }
+        Message::EmptyPlaylist(url) => format!("Playlist `{url}` has no streams"),
+        Message::CheckingStream => String::from("Checking the stream..."),
+        Message::DescribingStreams => String::from("Loading details of the streams..."),
+
+        Message::AvailablePlayers => String::from("Available players"),
+        Message::SelectPlayer => String::from("Select a player"),
+        Message::PlayerHelp(Player::Mpv) => String::from(
+            "Are you sure you have MPV installed? \
+            Try running `mpv --version` in your terminal. \
+            If you don't have MPV installed, you can install it from https://mpv.io/installation/ \
+            or use another player.",
+        ),
+        Message::UnknownPlayer(player) => format!("Unknown player `{player}`"),
+        Message::UnknownPlayerSeqNum(seq_num) => {
+            format!("Unknown player sequence number `{seq_num}`")
+        }
+        Message::LaunchingPlayer => String::from("Launch the process! Wait opening..."),
+        Message::PlayerFinished => String::from("Process finished!"),
+        Message::CantSaveHistory(err) => format!("Can't save history: {err}"),
+        Message::RelayUrl(url) => format!("The stream is available at {url}"),
+        Message::RelayHint(minutes) => format!(
+            "Open the URL in a player on this or another device, press Ctrl-C to stop. \
+             The relay is stopped after {minutes} min without requests"
+        ),
+        Message::RelayStopped => String::from("The relay is stopped"),
+        Message::CantBind(addr, err) => format!("Can't listen on {addr}: {err}"),
+        Message::PluginError(plugin, err) => format!("Plugin `{plugin}`: {err}"),
+        Message::PluginExited => String::from("the process exited"),
+        Message::PluginLanguage => {
+            String::from("`language` must be a language name or its ISO code, e.g. `en`")
+        }
+        Message::PluginInvalidField(field) => {
+            format!("`{field}` of the response is missing or invalid")
+        }
+        Message::CantLoadPlugin(path, err) => format!("Can't load plugin `{path}`: {err}"),
+        Message::CantReadPlugins(path, err) => {
+            format!("Can't read plugins directory `{path}`: {err}")
+        }
+        Message::SourceInvalidField(source, path) => {
+            format!("Source `{source}`: nothing is found by `{path}` in the response")
+        }
+        Message::NoMediaFound(url) => format!("No video is found on `{url}`"),
+        Message::WatchStarted(minutes) => format!(
+            "The watchlist is checked for new episodes every {minutes} min, press Ctrl-C to stop"
+        ),
+        Message::NewEpisodes(anime, episodes) => format!("New episodes of {anime}: {episodes}"),
+        Message::CantCheckAnime(anime, err) => format!("Can't check `{anime}`: {err}"),
+        Message::CantLoadWatchlist(err) => format!("Can't load watchlist: {err}"),
+        Message::CantSaveSeenEpisodes(err) => format!("Can't save seen episodes: {err}"),
+        Message::HookFailed(hook, err) => format!("Notification hook `{hook}` failed: {err}"),
+        Message::HookExited(status) => format!("the command exited with {status}"),
+        Message::NoSyncRules => {
+            String::from("There are no rules in `sync.rules` of the config file")
+        }
+        Message::InvalidBandwidth(kilobytes) => {
+            format!("Expected a positive number of kilobytes per second, got `{kilobytes}`")
+        }
+        Message::Downloading(anime, episode, quality) => {
+            format!("Downloading {anime}, episode {episode} in {quality}")
+        }
+        Message::Downloaded(anime, episode, size) => {
+            format!("Downloaded {anime}, episode {episode}: {size} MB")
+        }
+        Message::CantDownload(anime, episode, err) => {
+            format!("Can't download {anime}, episode {episode}: {err}")
+        }
+        Message::CantSyncRule(anime, err) => format!("Can't sync `{anime}`: {err}"),
+        Message::RemovedDownload(path) => format!("Removed {path}"),
+        Message::CantSaveDownloads(err) => format!("Can't save downloads: {err}"),
+        Message::SyncFinished(downloaded, failed, removed) => {
+            format!("Downloaded episodes: {downloaded}, failed: {failed}, removed: {removed}")
+        }
+        Message::EncryptedStream => String::from("encrypted streams can't be downloaded"),
+
+        Message::NoDownloads => String::from("There are no downloaded episodes"),
+        Message::Downloads => String::from("Downloaded anime"),
+        Message::DownloadedTitle(count, size) => format!("{count} episodes, {size} MB"),
+        Message::DownloadedEpisode(episode, quality, size) => {
+            format!("Episode {episode} ({quality}), {size} MB")
+        }
+        Message::SelectAction => String::from("Select an action"),
+        Message::PlayDownload => String::from("Play"),
+        Message::DeleteDownload => String::from("Delete"),
+        Message::VerifyDownload => String::from("Verify the file"),
+        Message::CantDeleteDownload(anime, episode, err) => {
+            format!("Can't delete {anime}, episode {episode}: {err}")
+        }
+        Message::DownloadOk(path) => format!("The file {path} is fine"),
+        Message::DownloadMissing(path) => format!("The file {path} is missing"),
+        Message::DownloadSizeMismatch(path, expected, size) => format!(
+            "The file {path} is damaged: {size} bytes instead of {expected}, download it again"
+        ),
+        Message::PlayingLocalCopy(path) => format!("Playing the downloaded file {path}"),
+        Message::UnknownSeqNum(seq_num) => format!("Unknown sequence number `{seq_num}`"),
+
+        Message::FfmpegNotInstalled => String::from(
+            "ffmpeg isn't installed, episodes are kept as downloaded. \
+            You can install it from https://ffmpeg.org/download.html",
+        ),
+        Message::FfmpegFailed(status, reason) => format!("ffmpeg exited with {status}: {reason}"),
+        Message::CantRemux(anime, episode, err) => {
+            format!("Can't remux {anime}, episode {episode}, it's kept as downloaded: {err}")
+        }
+        Message::EpisodeTitle(anime, episode) => format!("{anime}, episode {episode}"),
+        Message::ChapterOpening => String::from("Opening"),
+        Message::ChapterEnding => String::from("Ending"),
+        Message::ChapterEpisode => String::from("Episode"),
+
+        Message::EmptyWatchlist => String::from("The watchlist is empty"),
+        Message::NoExportDir => String::from(
+            "There is no videos directory, set `export.dir` in the config file or pass --output",
+        ),
+        Message::Exporting(anime, dir) => format!("Exporting {anime} to {dir}"),
+        Message::CantExport(anime, err) => format!("Can't export `{anime}`: {err}"),
+        Message::CantExportEpisode(anime, episode, err) => {
+            format!("Can't export {anime}, episode {episode}: {err}")
+        }
+        Message::EpisodeNumber(episode) => format!("Episode {episode}"),
+        Message::ExportFinished(titles, episodes, failed) => {
+            format!("Exported anime: {titles}, episodes: {episodes}, failed: {failed}")
+        }
+
+        Message::TrackerNotConfigured(service) => format!(
+            "Register an application on the site and set `{service}.client_id` and `{service}.client_secret` in the config file"
+        ),
+        Message::TrackerNotLinked(service) => {
+            format!("The account isn't linked, run `ani_cli_rs {service} login`")
+        }
+        Message::AuthorizeUrl(url) => {
+            format!("Open the page, allow access and copy the authorization code:\n{url}")
+        }
+        Message::EnterAuthorizationCode => String::from("Authorization code"),
+        Message::AccountLinked(service, nickname) => {
+            format!("The {service} account `{nickname}` is linked")
+        }
+        Message::AccountUnlinked(service) => format!("The {service} account is unlinked"),
+        Message::CantLinkAccount(err) => format!("Can't link the account: {err}"),
+        Message::NotFoundOnTracker(anime, service) => {
+            format!("`{anime}` isn't found on {service}")
+        }
+        Message::TrackerProgress(service, anime, episodes) => {
+            format!("{service}: {anime} is watched up to episode {episodes}")
+        }
+        Message::CantUpdateProgress(service, err) => {
+            format!("Can't update progress on {service}: {err}")
+        }
+        Message::NotFoundInSources(names) => format!("`{names}` isn't found in the sources"),
+        Message::Imported(anime, source) => format!("Added {anime} of {source} to the watchlist"),
+        Message::ImportFinished(added, known, not_found) => format!(
+            "Added anime: {added}, already in the watchlist: {known}, not found: {not_found}"
+        ),
+
+        Message::FavoritesUnsupported(source) => format!("{source} has no favorites"),
+        Message::NoFavorites => String::from(
+            "There are no favorites, log in to a source first, e.g. `ani_cli_rs anilibria login`",
+        ),
+        Message::NotLoggedIn(service) => {
+            format!("You aren't logged in, run `ani_cli_rs {service} login`")
+        }
+        Message::EnterLogin => String::from("Email or login"),
+        Message::EnterPassword => String::from("Password"),
+        Message::LoggedIn(source) => format!("Logged in to {source}"),
+        Message::LoggedOut(source) => format!("Logged out of {source}"),
+        Message::CantLogIn(err) => format!("Can't log in: {err}"),
+        Message::Favorites => String::from("Favorites"),
+        Message::EmptyFavorites => String::from("There are no favorites yet"),
+        Message::AddFavorite => String::from("Add anime"),
+        Message::RemoveFavorite => String::from("Remove from favorites"),
+        Message::FavoriteAdded(anime) => format!("{anime} is added to favorites"),
+        Message::FavoriteRemoved(anime) => format!("{anime} is removed from favorites"),
+        Message::CantEditFavorites(anime, err) => {
+            format!("Can't edit favorites with `{anime}`: {err}")
+        }
+        Message::ApiUrl(url) => format!("The API is available at {url}"),
+        Message::ApiHint => String::from("Press Ctrl-C to stop the server"),
+        Message::MissingParameter(name) => format!("Parameter `{name}` is required"),
+        Message::JsonRequired => {
+            String::from("Requests that change data must have `Content-Type: application/json`")
+        }
+
+        Message::CacheCleared(removed) => format!("Removed {removed} cached responses"),
+        Message::CantClearCache(err) => format!("Can't clear the cache: {err}"),
+        Message::CantCreateHttpClient(err) => format!("Can't create the HTTP client: {err}"),
+        Message::CantLoadAccounts(err) => format!("Can't load accounts: {err}"),
+        Message::CantLoadHistory(err) => format!("Can't load history: {err}"),
+        Message::CantLoadDownloads(err) => format!("Can't load downloads: {err}"),
+        Message::CantLoadSeenEpisodes(err) => format!("Can't load seen episodes: {err}"),
+
+        Message::Details => String::from("Details"),
+        Message::EpisodeList => String::from("Episodes"),
+        Message::SearchingQuery(query) => format!("Searching `{query}`..."),
+        Message::LoadingFavorites => String::from("Loading favorites..."),
+        Message::PlayingEpisode(episode, player) => {
+            format!("Playing episode {episode} in {player}")
+        }
+        Message::KeysEditing => {
+            String::from("Enter search · Ctrl-F favorites · Esc stop typing · Ctrl-C quit")
+        }
+        Message::KeysAnimeList => {
+            String::from("↑↓/jk move · Enter select · / search · f favorites · Esc back · q quit")
+        }
+        Message::KeysEpisodes => String::from(
+            "←↑↓→/hjkl move · Enter select · g/G first/last · ✓ watched · Esc back · q quit",
+        ),
+        Message::KeysList => String::from("↑↓/jk move · Enter select · Esc back · q quit"),
+
+        Message::WhatNext => String::from("What do you want to do next?"),
+        Message::SelectState => String::from("Select a state"),
+        Message::StateName(state) => state.to_string(),
+        Message::UnknownState(state) => format!("Unknown state `{state}`"),
+        Message::UnknownStateSeqNum(seq_num) => {
+            format!("Unknown state sequence number `{seq_num}`")
+        }
+
+        Message::ConfigNotFound(path) => format!("Config file `{path}` doesn't exist"),
+        Message::InvalidTtl(endpoint) => {
+            format!("TTL of `{endpoint}` must be a number of seconds")
+        }
+        Message::InvalidInterval => {
+            String::from("`--interval` must be a positive number of seconds")
+        }
+        Message::UnknownColorMode(mode) => {
+            format!("Unknown color mode `{mode}`. Possible modes: auto, always, never")
+        }
+        Message::UnknownLocale(locale) => {
+            format!("Unknown locale `{locale}`. Possible locales: en, ru")
+        }
+        Message::UnknownTheme(theme, themes) => {
+            format!("Unknown theme `{theme}`. Possible themes: {themes}")
+        }
+        Message::UnknownStyle(style) => format!(
+            "Unknown style `{style}`. Possible styles: input, warning, error, info, variant, headline"
+        ),
+        Message::StyleNotString(style) => format!("Style `{style}` must be a string"),
+        Message::InvalidStyle(style, err) => format!("Invalid style `{style}`: {err}"),
+
+        Message::UnknownPicker(picker) => {
+            format!("Unknown picker `{picker}`. Possible pickers: fzf, skim")
+        }
+        Message::PickerNotInstalled(picker, command) => format!(
+            "Picker `{picker}` isn't installed. Try running `{command} --version` in your terminal"
+        ),
+        Message::CantLaunchPicker(picker, err) => format!("Can't launch picker `{picker}`: {err}"),
+        Message::PickerFailed(picker, status) => format!("Picker `{picker}` finished with {status}"),
+        Message::UnexpectedPickerOutput(picker, output) => {
+            format!("Unexpected output of picker `{picker}`: {output}")
+        }
+        Message::PickerFallback(err) => format!("{err}. Using the built-in prompt"),
+
+        Message::ApiError(err) => format!("Api error: {err}"),
+        Message::Timeout(host) => format!("`{host}` didn't respond in time, try again later"),
+        Message::ConnectError(host) => format!(
+            "Can't connect to `{host}`, check the internet connection and the proxy settings"
+        ),
+        Message::HttpStatus(host, status) => format!("`{host}` responded with `{status}`"),
+        Message::ParseError(err) => format!("Parse error: {err}"),
+        Message::UnknownVariant(variant) => format!("Unknown variant: {variant}"),
+    }
+}
@@ -0,0 +1,289 @@
+use super::{current, en, ru};
+
+use crate::{
+    dialog::common::state::State,
+    enums::{language::Language, locale::Locale, player::Player},
+};
+
+use std::fmt::{self, Display};
+
+/// Message of the user interface. \
+/// It's displayed in the current locale, see [`super::set`]
+pub enum Message<'a> {
+    Bye,
+
+    AvailableLanguages,
+    BackOnEmptyInput,
+    SelectLanguage,
+    /// Language with the count of its sources
+    LanguageSources(&'a Language, usize),
+    NoSourcesOfLanguage(&'a Language),
+    UnknownLanguage(&'a str),
+    UnknownLanguageSeqNum(usize),
+
+    AvailableSources,
+    AllSources,
+    SelectSource,
+    UnknownSource(&'a str),
+    UnknownSourceSeqNum(usize),
+
+    EnterAnimeName,
+    Searching,
+    SearchCancelled,
+    AnimeList,
+    EmptyAnimeList(&'a str),
+    SelectAnime,
+    SelectedAnime(&'a dyn Display),
+    UnknownAnimeName(&'a str),
+    UnknownAnimeSeqNum(&'a dyn Display),
+
+    Episodes(&'a dyn Display),
+    SelectEpisode,
+    SelectedEpisode(&'a dyn Display),
+    UnknownEpisodeNumber(&'a dyn Display),
+    UnknownEpisodePattern(&'a str),
+
+    Qualities,
+    SelectQuality,
+    UnknownQuality(&'a str),
+    /// The stream of the quality is unavailable, the next quality is tried
+    QualityFallback(&'a str, &'a str, &'a dyn Display),
+    EmptyPlaylist(&'a str),
+    CheckingStream,
+    DescribingStreams,
+
+    AvailablePlayers,
+    SelectPlayer,
+    PlayerHelp(&'a Player),
+    UnknownPlayer(&'a str),
+    UnknownPlayerSeqNum(usize),
+    LaunchingPlayer,
+    PlayerFinished,
+    CantSaveHistory(&'a dyn Display),
+
+    /// The stream is relayed at the URL
+    RelayUrl(&'a str),
+    /// How to use the relay, the argument is the idle timeout in minutes
+    RelayHint(&'a dyn Display),
+    RelayStopped,
+    /// Can't listen on the address
+    CantBind(&'a dyn Display, &'a dyn Display),
+
+    /// The plugin failed with the error
+    PluginError(&'a str, &'a dyn Display),
+    PluginExited,
+    /// The plugin declared an unknown language
+    PluginLanguage,
+    /// The field of the plugin response is missing or invalid
+    PluginInvalidField(&'a str),
+    /// The plugin by the path can't be loaded
+    CantLoadPlugin(&'a dyn Display, &'a dyn Display),
+    /// The plugins directory can't be read
+    CantReadPlugins(&'a dyn Display, &'a dyn Display),
+
+    /// Nothing is found by the path of the source defined in the config file
+    SourceInvalidField(&'a str, &'a dyn Display),
+    /// No video is found on the player page by the URL
+    NoMediaFound(&'a str),
+
+    /// The watchlist is checked with the interval in minutes
+    WatchStarted(&'a dyn Display),
+    /// New episodes of the anime
+    NewEpisodes(&'a str, &'a str),
+    CantCheckAnime(&'a str, &'a dyn Display),
+    CantLoadWatchlist(&'a dyn Display),
+    CantSaveSeenEpisodes(&'a dyn Display),
+    /// The notification hook failed with the error
+    HookFailed(&'a dyn Display, &'a dyn Display),
+    /// The command of the hook exited with the status
+    HookExited(&'a dyn Display),
+
+    NoSyncRules,
+    /// The value of `--bandwidth` isn't a positive number or is too large
+    InvalidBandwidth(&'a str),
+    /// The episode of the anime is downloaded in the quality
+    Downloading(&'a str, &'a str, &'a str),
+    /// The episode of the anime is downloaded, the size is in megabytes
+    Downloaded(&'a str, &'a str, &'a dyn Display),
+    CantDownload(&'a str, &'a str, &'a dyn Display),
+    /// The anime of the rule can't be found
+    CantSyncRule(&'a str, &'a dyn Display),
+    /// The file of an episode that isn't kept is removed
+    RemovedDownload(&'a dyn Display),
+    CantSaveDownloads(&'a dyn Display),
+    /// Counts of downloaded, failed and removed episodes
+    SyncFinished(usize, usize, usize),
+    EncryptedStream,
+
+    NoDownloads,
+    Downloads,
+    /// Count of downloaded episodes of the anime and their size in megabytes
+    DownloadedTitle(usize, &'a dyn Display),
+    /// Episode, quality and size in megabytes
+    DownloadedEpisode(&'a str, &'a str, &'a dyn Display),
+    SelectAction,
+    PlayDownload,
+    DeleteDownload,
+    VerifyDownload,
+    CantDeleteDownload(&'a str, &'a str, &'a dyn Display),
+    DownloadOk(&'a dyn Display),
+    DownloadMissing(&'a dyn Display),
+    /// Path, the downloaded size and the size of the file in bytes
+    DownloadSizeMismatch(&'a dyn Display, &'a dyn Display, &'a dyn Display),
+    PlayingLocalCopy(&'a dyn Display),
+    UnknownSeqNum(&'a str),
+
+    FfmpegNotInstalled,
+    /// Exit status of ffmpeg and the last line of its errors
+    FfmpegFailed(&'a dyn Display, &'a str),
+    CantRemux(&'a str, &'a str, &'a dyn Display),
+    /// Title of a remuxed file by the anime and the episode
+    EpisodeTitle(&'a str, &'a str),
+    ChapterOpening,
+    ChapterEnding,
+    ChapterEpisode,
+
+    EmptyWatchlist,
+    NoExportDir,
+    /// The anime is exported to the directory
+    Exporting(&'a str, &'a dyn Display),
+    CantExport(&'a str, &'a dyn Display),
+    CantExportEpisode(&'a str, &'a str, &'a dyn Display),
+    /// Title of an episode without a name
+    EpisodeNumber(&'a str),
+    /// Counts of exported anime and episodes and failures
+    ExportFinished(usize, usize, usize),
+
+    /// Credentials of the application of the service aren't set
+    TrackerNotConfigured(&'a str),
+    /// The account of the service isn't linked
+    TrackerNotLinked(&'a str),
+    /// Page where the user gets the authorization code
+    AuthorizeUrl(&'a str),
+    EnterAuthorizationCode,
+    /// The account of the service is linked with the nickname
+    AccountLinked(&'a str, &'a str),
+    AccountUnlinked(&'a str),
+    CantLinkAccount(&'a dyn Display),
+    /// The anime isn't found on the tracker
+    NotFoundOnTracker(&'a str, &'a str),
+    /// Count of watched episodes of the anime is updated on the tracker
+    TrackerProgress(&'a str, &'a str, u32),
+    CantUpdateProgress(&'a str, &'a dyn Display),
+    /// The anime with the names isn't found in the sources
+    NotFoundInSources(&'a dyn Display),
+    /// The anime of the source is added to the watchlist
+    Imported(&'a str, &'a str),
+    /// Counts of added, already known and not found anime
+    ImportFinished(usize, usize, usize),
+
+    /// The source has no accounts
+    FavoritesUnsupported(&'a str),
+    /// No source has favorites of a logged in account
+    NoFavorites,
+    /// The user isn't logged in to the service
+    NotLoggedIn(&'a str),
+    EnterLogin,
+    EnterPassword,
+    LoggedIn(&'a str),
+    LoggedOut(&'a str),
+    CantLogIn(&'a dyn Display),
+    Favorites,
+    EmptyFavorites,
+    AddFavorite,
+    RemoveFavorite,
+    FavoriteAdded(&'a str),
+    FavoriteRemoved(&'a str),
+    CantEditFavorites(&'a str, &'a dyn Display),
+
+    /// The API is served at the URL
+    ApiUrl(&'a str),
+    ApiHint,
+    /// The parameter of the API request is required
+    MissingParameter(&'a str),
+    /// Requests of the API that change data must send JSON
+    JsonRequired,
+
+    /// Count of removed cached responses
+    CacheCleared(usize),
+    CantClearCache(&'a dyn Display),
+    CantCreateHttpClient(&'a dyn Display),
+    CantLoadAccounts(&'a dyn Display),
+    CantLoadHistory(&'a dyn Display),
+    CantLoadDownloads(&'a dyn Display),
+    CantLoadSeenEpisodes(&'a dyn Display),
+
+    /// Title of the panel of details in the full-screen interface
+    Details,
+    /// Title of the panel of episodes in the full-screen interface
+    EpisodeList,
+    /// The query is searched in the full-screen interface
+    SearchingQuery(&'a str),
+    /// Favorites are loaded instead of search results in the full-screen interface
+    LoadingFavorites,
+    /// The episode is played in the player
+    PlayingEpisode(&'a dyn Display, &'a Player),
+    /// Keys of the full-screen interface while the query is typed
+    KeysEditing,
+    KeysAnimeList,
+    KeysEpisodes,
+    KeysList,
+
+    WhatNext,
+    SelectState,
+    StateName(&'a State),
+    UnknownState(&'a str),
+    UnknownStateSeqNum(usize),
+
+    /// The config file by the path doesn't exist
+    ConfigNotFound(&'a dyn Display),
+    /// The cache TTL of the endpoint isn't a number of seconds
+    InvalidTtl(&'a str),
+    InvalidInterval,
+    UnknownColorMode(&'a str),
+    UnknownLocale(&'a str),
+    /// The theme is unknown, the second argument lists the built-in themes
+    UnknownTheme(&'a str, &'a str),
+    UnknownStyle(&'a str),
+    /// The style of the config file isn't a string
+    StyleNotString(&'a str),
+    /// The style can't be parsed, e.g. its color is unknown
+    InvalidStyle(&'a str, &'a dyn Display),
+
+    UnknownPicker(&'a str),
+    /// The picker isn't installed, the second argument is its command
+    PickerNotInstalled(&'a dyn Display, &'a str),
+    CantLaunchPicker(&'a dyn Display, &'a dyn Display),
+    /// The picker exited with the status
+    PickerFailed(&'a dyn Display, &'a dyn Display),
+    UnexpectedPickerOutput(&'a dyn Display, &'a str),
+    /// The external picker failed with the error
+    PickerFallback(&'a dyn Display),
+
+    ApiError(&'a dyn Display),
+    /// The host didn't respond in time
+    Timeout(&'a str),
+    /// Can't connect to the host
+    ConnectError(&'a str),
+    /// The host responded with an error status
+    HttpStatus(&'a str, &'a dyn Display),
+    ParseError(&'a dyn Display),
+    UnknownVariant(&'a dyn Display),
+}
+
+impl Message<'_> {
+    /// Get the text of the message in the locale
+    #[must_use]
+    pub fn text(&self, locale: Locale) -> String {
+        match locale {
+            Locale::English => en::text(self),
+            Locale::Russian => ru::text(self),
+        }
+    }
+}
+
+impl Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text(current()))
+    }
+}
@@ -0,0 +1,361 @@
+use super::Message;
+
+use crate::{
+    dialog::common::state::State,
+    enums::{language::Language, player::Player},
+};
+
+/// Russian texts of the messages
+pub fn text(message: &Message) -> String {
+    match message {
+        Message::Bye => String::from("Пока, персик!"),
+
+        Message::AvailableLanguages => String::from("Доступные языки"),
+        Message::BackOnEmptyInput => String::from("введите пустую строку, чтобы вернуться назад"),
+        Message::SelectLanguage => String::from("Выберите язык"),
+        Message::LanguageSources(language, count) => {
+            format!("{} (источников: {count})", language_name(language))
+        }
+        Message::NoSourcesOfLanguage(language) => {
+            format!("Нет источников на языке `{}`", language_name(language))
+        }
+        Message::UnknownLanguage(language) => format!("Неизвестный язык `{language}`"),
+        Message::UnknownLanguageSeqNum(seq_num) => {
+            format!("Неизвестный порядковый номер языка `{seq_num}`")
+        }
+
+        Message::AvailableSources => String::from("Доступные источники"),
+        Message::AllSources => String::from("Все источники"),
+        Message::SelectSource => String::from("Выберите источник"),
+        Message::UnknownSource(source) => format!("Неизвестный источник `{source}`"),
+        Message::UnknownSourceSeqNum(seq_num) => {
+            format!("Неизвестный порядковый номер источника `{seq_num}`")
+        }
+
+        Message::EnterAnimeName => String::from("Введите название аниме"),
+        Message::Searching => String::from("Поиск... (нажмите Ctrl-C для отмены)"),
+        Message::SearchCancelled => String::from("Поиск отменён"),
+        Message::AnimeList => String::from("Список аниме"),
+        Message::EmptyAnimeList(query) => format!("По запросу `{query}` ничего не найдено"),
+        Message::SelectAnime => String::from("Выберите аниме"),
+        Message::SelectedAnime(anime) => format!("Выбрано аниме `{anime}`"),
+        Message::UnknownAnimeName(name) => format!("Неизвестное название аниме `{name}`"),
+        Message::UnknownAnimeSeqNum(seq_num) => {
+            format!("Неизвестный порядковый номер аниме `{seq_num}`")
+        }
+
+        Message::Episodes(episodes) => format!("Серии: {episodes}"),
+        Message::SelectEpisode => String::from("Выберите серию"),
+        Message::SelectedEpisode(episode) => format!("Выбрана серия `{episode}`"),
+        Message::UnknownEpisodeNumber(number) => format!("Неизвестный номер серии `{number}`"),
+        Message::UnknownEpisodePattern(pattern) => {
+            format!("Неизвестный шаблон серии `{pattern}`. Возможные шаблоны: first|f, last|l")
+        }
+
+        Message::Qualities => String::from("Качество"),
+        Message::SelectQuality => String::from("Выберите качество"),
+        Message::UnknownQuality(quality) => format!("Неизвестное качество `{quality}`"),
+        Message::QualityFallback(quality, next, err) => {
+            format!("Качество `{quality}` недоступно ({err}), пробуем `{next}`")
+        }
+        Message::EmptyPlaylist(url) => format!("В плейлисте `{url}` нет потоков"),
+        Message::CheckingStream => String::from("Проверяем поток..."),
+        Message::DescribingStreams => String::from("Загружаем сведения о потоках..."),
+
+        Message::AvailablePlayers => String::from("Доступные плееры"),
+        Message::SelectPlayer => String::from("Выберите плеер"),
+        Message::PlayerHelp(Player::Mpv) => String::from(
+            "Вы уверены, что MPV установлен? \
+            Попробуйте выполнить `mpv --version` в терминале. \
+            Если MPV не установлен, его можно установить с https://mpv.io/installation/ \
+            или выбрать другой плеер.",
+        ),
+        Message::UnknownPlayer(player) => format!("Неизвестный плеер `{player}`"),
+        Message::UnknownPlayerSeqNum(seq_num) => {
+            format!("Неизвестный порядковый номер плеера `{seq_num}`")
+        }
+        Message::LaunchingPlayer => String::from("Запускаем плеер! Ожидайте открытия..."),
+        Message::PlayerFinished => String::from("Плеер закрыт!"),
+        Message::CantSaveHistory(err) => format!("Не удалось сохранить историю: {err}"),
+        Message::RelayUrl(url) => format!("Поток доступен по адресу {url}"),
+        Message::RelayHint(minutes) => format!(
+            "Откройте адрес в плеере на этом или другом устройстве, нажмите Ctrl-C для остановки. \
+             Ретранслятор остановится через {minutes} мин без запросов"
+        ),
+        Message::RelayStopped => String::from("Ретранслятор остановлен"),
+        Message::CantBind(addr, err) => format!("Не удалось занять адрес {addr}: {err}"),
+        Message::PluginError(plugin, err) => format!("Плагин `{plugin}`: {err}"),
+        Message::PluginExited => String::from("процесс завершился"),
+        Message::PluginLanguage => {
+            String::from("`language` должен быть названием языка или его ISO-кодом, например `en`")
+        }
+        Message::PluginInvalidField(field) => {
+            format!("поле `{field}` ответа отсутствует или некорректно")
+        }
+        Message::CantLoadPlugin(path, err) => format!("Не удалось загрузить плагин `{path}`: {err}"),
+        Message::CantReadPlugins(path, err) => {
+            format!("Не удалось прочитать папку плагинов `{path}`: {err}")
+        }
+        Message::SourceInvalidField(source, path) => {
+            format!("Источник `{source}`: в ответе ничего не найдено по пути `{path}`")
+        }
+        Message::NoMediaFound(url) => format!("На странице `{url}` не найдено видео"),
+        Message::WatchStarted(minutes) => format!(
+            "Список просмотра проверяется на новые серии каждые {minutes} мин, \
+             нажмите Ctrl-C для остановки"
+        ),
+        Message::NewEpisodes(anime, episodes) => format!("Новые серии {anime}: {episodes}"),
+        Message::CantCheckAnime(anime, err) => format!("Не удалось проверить `{anime}`: {err}"),
+        Message::CantLoadWatchlist(err) => format!("Не удалось загрузить список просмотра: {err}"),
+        Message::CantSaveSeenEpisodes(err) => {
+            format!("Не удалось сохранить просмотренные серии: {err}")
+        }
+        Message::HookFailed(hook, err) => format!("Хук уведомлений `{hook}` завершился ошибкой: {err}"),
+        Message::HookExited(status) => format!("команда завершилась с {status}"),
+        Message::NoSyncRules => String::from("В `sync.rules` файла настроек нет правил"),
+        Message::InvalidBandwidth(kilobytes) => {
+            format!("Ожидалось положительное число килобайт в секунду, получено `{kilobytes}`")
+        }
+        Message::Downloading(anime, episode, quality) => {
+            format!("Загрузка {anime}, серия {episode} в {quality}")
+        }
+        Message::Downloaded(anime, episode, size) => {
+            format!("Загружено {anime}, серия {episode}: {size} МБ")
+        }
+        Message::CantDownload(anime, episode, err) => {
+            format!("Не удалось загрузить {anime}, серия {episode}: {err}")
+        }
+        Message::CantSyncRule(anime, err) => format!("Не удалось синхронизировать `{anime}`: {err}"),
+        Message::RemovedDownload(path) => format!("Удалено {path}"),
+        Message::CantSaveDownloads(err) => format!("Не удалось сохранить загрузки: {err}"),
+        Message::SyncFinished(downloaded, failed, removed) => format!(
+            "Загружено серий: {downloaded}, с ошибкой: {failed}, удалено: {removed}"
+        ),
+        Message::EncryptedStream => String::from("зашифрованные потоки нельзя загрузить"),
+
+        Message::NoDownloads => String::from("Загруженных серий нет"),
+        Message::Downloads => String::from("Загруженные аниме"),
+        Message::DownloadedTitle(count, size) => format!("серий: {count}, {size} МБ"),
+        Message::DownloadedEpisode(episode, quality, size) => {
+            format!("Серия {episode} ({quality}), {size} МБ")
+        }
+        Message::SelectAction => String::from("Выберите действие"),
+        Message::PlayDownload => String::from("Смотреть"),
+        Message::DeleteDownload => String::from("Удалить"),
+        Message::VerifyDownload => String::from("Проверить файл"),
+        Message::CantDeleteDownload(anime, episode, err) => {
+            format!("Не удалось удалить {anime}, серия {episode}: {err}")
+        }
+        Message::DownloadOk(path) => format!("Файл {path} в порядке"),
+        Message::DownloadMissing(path) => format!("Файл {path} отсутствует"),
+        Message::DownloadSizeMismatch(path, expected, size) => format!(
+            "Файл {path} повреждён: {size} байт вместо {expected}, загрузите его снова"
+        ),
+        Message::PlayingLocalCopy(path) => format!("Воспроизводится загруженный файл {path}"),
+        Message::UnknownSeqNum(seq_num) => format!("Неизвестный порядковый номер `{seq_num}`"),
+
+        Message::FfmpegNotInstalled => String::from(
+            "ffmpeg не установлен, серии сохраняются как есть. \
+            Установить его можно с https://ffmpeg.org/download.html",
+        ),
+        Message::FfmpegFailed(status, reason) => {
+            format!("ffmpeg завершился с {status}: {reason}")
+        }
+        Message::CantRemux(anime, episode, err) => format!(
+            "Не удалось перепаковать {anime}, серия {episode}, она сохранена как есть: {err}"
+        ),
+        Message::EpisodeTitle(anime, episode) => format!("{anime}, серия {episode}"),
+        Message::ChapterOpening => String::from("Опенинг"),
+        Message::ChapterEnding => String::from("Эндинг"),
+        Message::ChapterEpisode => String::from("Серия"),
+
+        Message::EmptyWatchlist => String::from("Список просмотра пуст"),
+        Message::NoExportDir => String::from(
+            "Папки видео нет, укажите `export.dir` в файле настроек или передайте --output",
+        ),
+        Message::Exporting(anime, dir) => format!("Экспорт {anime} в {dir}"),
+        Message::CantExport(anime, err) => format!("Не удалось экспортировать `{anime}`: {err}"),
+        Message::CantExportEpisode(anime, episode, err) => {
+            format!("Не удалось экспортировать {anime}, серия {episode}: {err}")
+        }
+        Message::EpisodeNumber(episode) => format!("Серия {episode}"),
+        Message::ExportFinished(titles, episodes, failed) => {
+            format!("Экспортировано аниме: {titles}, серий: {episodes}, с ошибкой: {failed}")
+        }
+
+        Message::TrackerNotConfigured(service) => format!(
+            "Зарегистрируйте приложение на сайте и укажите `{service}.client_id` и `{service}.client_secret` в файле настроек"
+        ),
+        Message::TrackerNotLinked(service) => {
+            format!("Аккаунт не привязан, выполните `ani_cli_rs {service} login`")
+        }
+        Message::AuthorizeUrl(url) => {
+            format!("Откройте страницу, разрешите доступ и скопируйте код авторизации:\n{url}")
+        }
+        Message::EnterAuthorizationCode => String::from("Код авторизации"),
+        Message::AccountLinked(service, nickname) => {
+            format!("Аккаунт {service} `{nickname}` привязан")
+        }
+        Message::AccountUnlinked(service) => format!("Аккаунт {service} отвязан"),
+        Message::CantLinkAccount(err) => format!("Не удалось привязать аккаунт: {err}"),
+        Message::NotFoundOnTracker(anime, service) => {
+            format!("`{anime}` не найдено на {service}")
+        }
+        Message::TrackerProgress(service, anime, episodes) => {
+            format!("{service}: {anime} просмотрено до серии {episodes}")
+        }
+        Message::CantUpdateProgress(service, err) => {
+            format!("Не удалось обновить прогресс на {service}: {err}")
+        }
+        Message::NotFoundInSources(names) => format!("`{names}` не найдено в источниках"),
+        Message::Imported(anime, source) => {
+            format!("{anime} из {source} добавлено в список просмотра")
+        }
+        Message::ImportFinished(added, known, not_found) => format!(
+            "Добавлено аниме: {added}, уже в списке: {known}, не найдено: {not_found}"
+        ),
+
+        Message::FavoritesUnsupported(source) => format!("У {source} нет избранного"),
+        Message::NoFavorites => String::from(
+            "Избранного нет, сначала войдите в аккаунт источника, например `ani_cli_rs anilibria login`",
+        ),
+        Message::NotLoggedIn(service) => {
+            format!("Вы не вошли в аккаунт, выполните `ani_cli_rs {service} login`")
+        }
+        Message::EnterLogin => String::from("Почта или логин"),
+        Message::EnterPassword => String::from("Пароль"),
+        Message::LoggedIn(source) => format!("Выполнен вход в {source}"),
+        Message::LoggedOut(source) => format!("Выполнен выход из {source}"),
+        Message::CantLogIn(err) => format!("Не удалось войти: {err}"),
+        Message::Favorites => String::from("Избранное"),
+        Message::EmptyFavorites => String::from("В избранном пока ничего нет"),
+        Message::AddFavorite => String::from("Добавить аниме"),
+        Message::RemoveFavorite => String::from("Удалить из избранного"),
+        Message::FavoriteAdded(anime) => format!("{anime} добавлено в избранное"),
+        Message::FavoriteRemoved(anime) => format!("{anime} удалено из избранного"),
+        Message::CantEditFavorites(anime, err) => {
+            format!("Не удалось изменить избранное с `{anime}`: {err}")
+        }
+        Message::ApiUrl(url) => format!("API доступен по адресу {url}"),
+        Message::ApiHint => String::from("Нажмите Ctrl-C для остановки сервера"),
+        Message::MissingParameter(name) => format!("Параметр `{name}` обязателен"),
+        Message::JsonRequired => String::from(
+            "Запросы, изменяющие данные, должны иметь заголовок `Content-Type: application/json`",
+        ),
+
+        Message::CacheCleared(removed) => format!("Удалено сохранённых ответов: {removed}"),
+        Message::CantClearCache(err) => format!("Не удалось очистить кэш: {err}"),
+        Message::CantCreateHttpClient(err) => format!("Не удалось создать HTTP-клиент: {err}"),
+        Message::CantLoadAccounts(err) => format!("Не удалось загрузить аккаунты: {err}"),
+        Message::CantLoadHistory(err) => format!("Не удалось загрузить историю: {err}"),
+        Message::CantLoadDownloads(err) => format!("Не удалось загрузить список загрузок: {err}"),
+        Message::CantLoadSeenEpisodes(err) => {
+            format!("Не удалось загрузить просмотренные серии: {err}")
+        }
+
+        Message::Details => String::from("Подробности"),
+        Message::EpisodeList => String::from("Серии"),
+        Message::SearchingQuery(query) => format!("Поиск `{query}`..."),
+        Message::LoadingFavorites => String::from("Загрузка избранного..."),
+        Message::PlayingEpisode(episode, player) => {
+            format!("Серия {episode} воспроизводится в {player}")
+        }
+        Message::KeysEditing => {
+            String::from("Enter поиск · Ctrl-F избранное · Esc закончить ввод · Ctrl-C выход")
+        }
+        Message::KeysAnimeList => {
+            String::from("↑↓/jk перемещение · Enter выбор · / поиск · f избранное · Esc назад · q выход")
+        }
+        Message::KeysEpisodes => String::from(
+            "←↑↓→/hjkl перемещение · Enter выбор · g/G первая/последняя · ✓ просмотрено · Esc назад · q выход",
+        ),
+        Message::KeysList => {
+            String::from("↑↓/jk перемещение · Enter выбор · Esc назад · q выход")
+        }
+
+        Message::WhatNext => String::from("Что делать дальше?"),
+        Message::SelectState => String::from("Выберите действие"),
+        Message::StateName(state) => state_name(state).to_string(),
+        Message::UnknownState(state) => format!("Неизвестное действие `{state}`"),
+        Message::UnknownStateSeqNum(seq_num) => {
+            format!("Неизвестный порядковый номер действия `{seq_num}`")
+        }
+
+        Message::ConfigNotFound(path) => format!("Файл настроек `{path}` не существует"),
+        Message::InvalidTtl(endpoint) => {
+            format!("TTL `{endpoint}` должен быть числом секунд")
+        }
+        Message::InvalidInterval => {
+            String::from("`--interval` должен быть положительным числом секунд")
+        }
+        Message::UnknownColorMode(mode) => {
+            format!("Неизвестный режим цвета `{mode}`. Возможные режимы: auto, always, never")
+        }
+        Message::UnknownLocale(locale) => {
+            format!("Неизвестная локаль `{locale}`. Возможные локали: en, ru")
+        }
+        Message::UnknownTheme(theme, themes) => {
+            format!("Неизвестная тема `{theme}`. Возможные темы: {themes}")
+        }
+        Message::UnknownStyle(style) => format!(
+            "Неизвестный стиль `{style}`. Возможные стили: input, warning, error, info, variant, headline"
+        ),
+        Message::StyleNotString(style) => format!("Стиль `{style}` должен быть строкой"),
+        Message::InvalidStyle(style, err) => format!("Неверный стиль `{style}`: {err}"),
+
+        Message::UnknownPicker(picker) => {
+            format!("Неизвестный пикер `{picker}`. Возможные пикеры: fzf, skim")
+        }
+        Message::PickerNotInstalled(picker, command) => format!(
+            "Пикер `{picker}` не установлен. Попробуйте выполнить `{command} --version` в терминале"
+        ),
+        Message::CantLaunchPicker(picker, err) => {
+            format!("Не удалось запустить пикер `{picker}`: {err}")
+        }
+        Message::PickerFailed(picker, status) => {
+            format!("Пикер `{picker}` завершился с {status}")
+        }
+        Message::UnexpectedPickerOutput(picker, output) => {
+            format!("Неожиданный вывод пикера `{picker}`: {output}")
+        }
+        Message::PickerFallback(err) => format!("{err}. Используется встроенный выбор"),
+
+        Message::ApiError(err) => format!("Ошибка API: {err}"),
+        Message::Timeout(host) => format!("`{host}` не ответил вовремя, попробуйте позже"),
+        Message::ConnectError(host) => format!(
+            "Не удалось подключиться к `{host}`, проверьте подключение к интернету и настройки прокси"
+        ),
+        Message::HttpStatus(host, status) => format!("`{host}` ответил `{status}`"),
+        Message::ParseError(err) => format!("Ошибка разбора ответа: {err}"),
+        Message::UnknownVariant(variant) => format!("Неизвестный вариант: {variant}"),
+    }
+}
+
+fn language_name(language: &Language) -> &'static str {
+    match language {
+        Language::English => "Английский",
+        Language::Russian => "Русский",
+        Language::Ukrainian => "Украинский",
+        Language::Japanese => "Японский",
+        Language::Chinese => "Китайский",
+        Language::Korean => "Корейский",
+        Language::Spanish => "Испанский",
+        Language::Portuguese => "Португальский",
+        Language::French => "Французский",
+        Language::German => "Немецкий",
+        Language::Italian => "Итальянский",
+        Language::All => "Все языки",
+    }
+}
+
+fn state_name(state: &State) -> &'static str {
+    match state {
+        State::SelectLanguage => "Выбрать язык",
+        State::SelectSource => "Выбрать источник",
+        State::SelectAnime => "Выбрать аниме",
+        State::SelectEpisode => "Выбрать серию",
+        State::SelectQuality => "Выбрать качество",
+        State::SelectPlayer => "Выбрать плеер",
+        State::LaunchPlayer => "Запустить плеер",
+    }
+}
@@ -1,13 +1,6 @@
-mod dialog;
-mod enums;
-mod errors;
-mod players;
-mod sources;
+use std::{env, process::ExitCode};
 
-use enums::language::Language;
-
-fn main() {
-    let sources = [sources::ru::anilibria::Anilibria::default()];
-
-    dialog::cli::run(&sources);
+#[tokio::main]
+async fn main() -> ExitCode {
+    ani_cli_rs::run(env::args().skip(1)).await
 }
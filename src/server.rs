@@ -0,0 +1,39 @@
+pub mod api;
+pub mod relay;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+
+/// Get the address of this machine in the local network. \
+/// No packets are sent, the route to a public address is only looked up
+fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80)).ok()?;
+
+    socket
+        .local_addr()
+        .ok()
+        .map(|addr| addr.ip())
+        .filter(|ip| !ip.is_loopback())
+}
+
+/// Get URLs of a path of the server to be opened on this machine and on other devices
+/// # Arguments
+/// * `addr` - Address the server listens on
+/// * `path` - Path with the leading slash
+/// # Returns
+/// A loopback URL and a LAN URL if the server listens on all interfaces,
+/// otherwise the URL of the address
+#[must_use]
+pub fn urls(addr: SocketAddr, path: &str) -> Vec<String> {
+    if !addr.ip().is_unspecified() {
+        return vec![format!("http://{addr}{path}")];
+    }
+
+    let port = addr.port();
+    let mut urls = vec![format!("http://127.0.0.1:{port}{path}")];
+    if let Some(ip) = lan_ip() {
+        urls.push(format!("http://{}{path}", SocketAddr::new(ip, port)));
+    }
+
+    urls
+}
@@ -0,0 +1,1045 @@
+use crate::{
+    dialog::common::{
+        stream::{checked_url_for_stream, describe_qualities},
+        watched::is_episode_watched,
+    },
+    enums::language::Language,
+    errors::{ServerError, SourceError},
+    locale::Message,
+    sources::{
+        base::Source,
+        hls::StreamInfo,
+        search::{search_all, search_source, Query},
+        stream::StreamCheck,
+    },
+    storage::{history::History, watchlist::Watchlist},
+};
+
+use hyper::{
+    body,
+    header::{HeaderValue, ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_TYPE},
+    rt::Executor,
+    server::conn::AddrIncoming,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::{
+    cell::RefCell, collections::HashMap, convert::Infallible, future::Future, io, net::SocketAddr,
+    rc::Rc,
+};
+use tokio::task::LocalSet;
+
+/// Path prefix of the endpoints
+pub const API_PATH: &str = "/api";
+
+/// Local JSON REST API over the sources, so other front-ends can use the same source logic. \
+/// Requests are stateless: each request works with a copy of the source,
+/// the anime is selected by the search query and its sequence number or title,
+/// the episode and the quality are selected by their names
+pub struct ApiServer<S>
+where
+    S: Source,
+{
+    sources: Vec<S>,
+    stream_check: StreamCheck,
+    history: RefCell<History>,
+    watchlist: RefCell<Watchlist>,
+}
+
+impl<S> ApiServer<S>
+where
+    S: Source + 'static,
+{
+    #[must_use]
+    pub fn new(
+        sources: Vec<S>,
+        stream_check: StreamCheck,
+        history: History,
+        watchlist: Watchlist,
+    ) -> Self {
+        Self {
+            sources,
+            stream_check,
+            history: RefCell::new(history),
+            watchlist: RefCell::new(watchlist),
+        }
+    }
+
+    /// Bind the server to the address, port `0` to choose a free port
+    pub fn bind(self, addr: SocketAddr) -> Result<Serving<S>, ServerError> {
+        let incoming = AddrIncoming::bind(&addr)
+            .map_err(|err| ServerError::BindError(Message::CantBind(&addr, &err).to_string()))?;
+
+        Ok(Serving {
+            incoming,
+            api: Rc::new(self),
+        })
+    }
+
+    /// Web pages of other origins can read responses of `GET` requests only. \
+    /// Requests that change data must send JSON, so browsers don't send them from other origins
+    /// without a preflight request, which isn't allowed
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let is_get = request.method() == Method::GET;
+        let mut response = self.respond(request).await;
+        if is_get {
+            response
+                .headers_mut()
+                .insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+        }
+        response
+    }
+
+    async fn respond(&self, request: Request<Body>) -> Response<Body> {
+        let (parts, body) = request.into_parts();
+
+        let mut params = query_params(&parts.uri);
+        if parts.method == Method::POST || parts.method == Method::DELETE {
+            let is_json = parts
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .is_some_and(|content_type| content_type.starts_with("application/json"));
+            if !is_json {
+                return Failure::new(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    Message::JsonRequired.to_string(),
+                )
+                .into_response();
+            }
+
+            match body_params(body).await {
+                Ok(body_params) => params.extend(body_params),
+                Err(failure) => return failure.into_response(),
+            }
+        }
+        let params = Params(params);
+
+        let path = parts.uri.path().trim_end_matches('/');
+        let Some(endpoint) = path.strip_prefix(API_PATH) else {
+            return Failure::not_found().into_response();
+        };
+
+        let result = match (parts.method, endpoint) {
+            (Method::GET, "/sources") => Ok(self.sources()),
+            (Method::GET, "/search") => self.search(&params).await,
+            (Method::GET, "/favorites") => self.favorites(&params).await,
+            (Method::GET, "/anime") => self.anime(&params).await,
+            (Method::GET, "/episodes") => self.episodes(&params).await,
+            (Method::GET, "/qualities") => self.qualities(&params).await,
+            (Method::GET, "/stream") => self.stream(&params).await,
+            (Method::GET, "/history") => Ok(self.history()),
+            (Method::POST, "/history") => self.add_to_history(&params),
+            (Method::GET, "/watchlist") => Ok(self.watchlist()),
+            (Method::POST, "/watchlist") => self.add_to_watchlist(&params),
+            (Method::DELETE, "/watchlist") => self.remove_from_watchlist(&params),
+            (
+                _,
+                "/sources" | "/search" | "/favorites" | "/anime" | "/episodes" | "/qualities"
+                | "/stream" | "/history" | "/watchlist",
+            ) => Err(Failure::new(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Method not allowed",
+            )),
+            _ => Err(Failure::not_found()),
+        };
+
+        match result {
+            Ok(value) => json_response(StatusCode::OK, &value),
+            Err(failure) => failure.into_response(),
+        }
+    }
+
+    /// `GET /api/sources`
+    fn sources(&self) -> Value {
+        self.sources
+            .iter()
+            .map(|source| {
+                json!({
+                    "name": source.to_string(),
+                    "language": source.language().to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// `GET /api/search?query=...[&source=...][&language=...]`
+    async fn search(&self, params: &Params) -> Result<Value, Failure> {
+        self.list(params, Query::Name(params.required("query")?))
+            .await
+    }
+
+    /// `GET /api/favorites[?source=...][&language=...]`: favorites of the logged in accounts
+    async fn favorites(&self, params: &Params) -> Result<Value, Failure> {
+        self.list(params, Query::Favorites).await
+    }
+
+    /// Search anime in the sources of the parameters or list their favorites
+    async fn list(&self, params: &Params, query: Query<'_>) -> Result<Value, Failure> {
+        let language = match params.optional("language") {
+            Some(language) => Language::try_from(language)
+                .map_err(|err| Failure::new(StatusCode::BAD_REQUEST, err.to_string()))?,
+            None => Language::All,
+        };
+
+        let mut sources = match params.optional("source") {
+            Some(_) => vec![self.source(params)?],
+            None => self
+                .sources
+                .iter()
+                .filter(|source| language.includes(source.language()))
+                .cloned()
+                .collect(),
+        };
+
+        let mut errors = Vec::new();
+        // Whether any source has loaded its favorites
+        let mut has_favorites = false;
+        let hits = search_all(
+            &mut sources,
+            query,
+            std::future::pending(),
+            |source, result| match result {
+                Ok(_) => has_favorites = true,
+                Err(err) => {
+                    errors.push(json!({ "source": source.to_string(), "error": err.to_string() }));
+                }
+            },
+        )
+        .await
+        .into_hits();
+
+        if query == Query::Favorites && !has_favorites && errors.is_empty() {
+            return Err(Failure::no_favorites());
+        }
+
+        let results = hits
+            .iter()
+            .map(|hit| {
+                json!({
+                    "source": sources[hit.source_index].to_string(),
+                    // Sequence numbers can be passed as `anime` with the same query
+                    "anime": hit.anime_index + 1,
+                    "title": hit.title,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(json!({ "results": results, "errors": errors }))
+    }
+
+    /// `GET /api/anime?source=...&query=...&anime=...`
+    async fn anime(&self, params: &Params) -> Result<Value, Failure> {
+        let mut source = self.select(params, Step::Anime).await?;
+
+        let title = source.anime_info()?.to_string();
+        let episodes_info = source.episodes_info()?.to_string();
+        let in_watchlist = self
+            .watchlist
+            .borrow()
+            .contains(&source.to_string(), &title);
+
+        Ok(json!({
+            "source": source.to_string(),
+            "title": title,
+            "episodes_info": episodes_info.trim(),
+            "episodes": source.episodes()?,
+            "in_watchlist": in_watchlist,
+        }))
+    }
+
+    /// `GET /api/episodes?source=...&query=...&anime=...`
+    async fn episodes(&self, params: &Params) -> Result<Value, Failure> {
+        let source = self.select(params, Step::Anime).await?;
+
+        let history = self.history.borrow();
+        let episodes = source
+            .episodes()?
+            .into_iter()
+            .map(|episode| {
+                json!({
+                    "watched": is_episode_watched(&history, &source, &episode),
+                    "episode": episode,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Value::Array(episodes))
+    }
+
+    /// `GET /api/qualities?source=...&query=...&anime=...&episode=...[&describe=true]`. \
+    /// Streams are described only if `describe` is passed, because their playlists are requested
+    async fn qualities(&self, params: &Params) -> Result<Value, Failure> {
+        let mut source = self.select(params, Step::Episode).await?;
+
+        let qualities = if params.optional("describe") == Some("true") {
+            describe_qualities(&mut source, &self.stream_check).await
+        } else {
+            source
+                .qualities()?
+                .into_iter()
+                .map(|quality| (quality, None))
+                .collect()
+        };
+
+        Ok(qualities
+            .into_iter()
+            .map(|(quality, info)| {
+                json!({
+                    "quality": quality,
+                    "info": info.as_ref().map(stream_info),
+                })
+            })
+            .collect())
+    }
+
+    /// `GET /api/stream?source=...&query=...&anime=...&episode=...&quality=...`. \
+    /// The stream is checked like before launching a player, so lower qualities may be returned
+    async fn stream(&self, params: &Params) -> Result<Value, Failure> {
+        let mut source = self.select(params, Step::Quality).await?;
+
+        let mut warnings = Vec::new();
+        let url = checked_url_for_stream(&mut source, &self.stream_check, |quality, next, err| {
+            warnings.push(Message::QualityFallback(quality, next, err).to_string());
+        })
+        .await?;
+
+        Ok(json!({
+            "url": url,
+            "quality": source.quality_info()?.to_string(),
+            "warnings": warnings,
+        }))
+    }
+
+    /// `GET /api/history`
+    fn history(&self) -> Value {
+        self.history
+            .borrow()
+            .entries()
+            .iter()
+            .map(|entry| {
+                json!({
+                    "source": entry.source,
+                    "anime": entry.anime,
+                    "episode": entry.episode,
+                    "watched_at": entry.watched_at,
+                })
+            })
+            .collect()
+    }
+
+    /// `POST /api/history` with `source`, `anime` title and `episode`
+    fn add_to_history(&self, params: &Params) -> Result<Value, Failure> {
+        let source = self.source(params)?.to_string();
+        let anime = params.required("anime")?;
+        let episode = params.required("episode")?;
+
+        self.history
+            .borrow_mut()
+            .add(&source, anime, episode)
+            .map_err(Failure::storage)?;
+
+        Ok(json!({ "source": source, "anime": anime, "episode": episode }))
+    }
+
+    /// `GET /api/watchlist`
+    fn watchlist(&self) -> Value {
+        self.watchlist
+            .borrow()
+            .entries()
+            .iter()
+            .map(|entry| {
+                json!({
+                    "source": entry.source,
+                    "anime": entry.anime,
+                    "added_at": entry.added_at,
+                })
+            })
+            .collect()
+    }
+
+    /// `POST /api/watchlist` with `source` and `anime` title
+    fn add_to_watchlist(&self, params: &Params) -> Result<Value, Failure> {
+        let source = self.source(params)?.to_string();
+        let anime = params.required("anime")?;
+
+        let added = self
+            .watchlist
+            .borrow_mut()
+            .add(&source, anime)
+            .map_err(Failure::storage)?;
+
+        Ok(json!({ "added": added }))
+    }
+
+    /// `DELETE /api/watchlist` with `source` and `anime` title
+    fn remove_from_watchlist(&self, params: &Params) -> Result<Value, Failure> {
+        let source = self.source(params)?.to_string();
+        let anime = params.required("anime")?;
+
+        let removed = self
+            .watchlist
+            .borrow_mut()
+            .remove(&source, anime)
+            .map_err(Failure::storage)?;
+
+        Ok(json!({ "removed": removed }))
+    }
+
+    /// Get a copy of the source by its name, the first source is used if the name isn't passed
+    fn source(&self, params: &Params) -> Result<S, Failure> {
+        let source = match params.optional("source") {
+            Some(name) => self
+                .sources
+                .iter()
+                .find(|source| **source == name.to_string()),
+            None => self.sources.first(),
+        };
+
+        source.cloned().ok_or_else(|| {
+            Failure::new(
+                StatusCode::NOT_FOUND,
+                Message::UnknownSource(params.optional("source").unwrap_or_default()).to_string(),
+            )
+        })
+    }
+
+    /// Get a copy of the source with the anime, the episode and the quality selected
+    /// up to the step
+    async fn select(&self, params: &Params, step: Step) -> Result<S, Failure> {
+        let mut source = self.source(params)?;
+
+        if !search_source(&mut source, params.query()?).await? {
+            return Err(Failure::no_favorites());
+        }
+        source
+            .select_anime_as_current(params.required("anime")?.to_string())
+            .await?;
+
+        if step >= Step::Episode {
+            source
+                .select_episode_as_current(params.required("episode")?.to_string())
+                .await?;
+        }
+        if step >= Step::Quality {
+            source.select_quality_as_current(params.required("quality")?.to_string())?;
+        }
+
+        Ok(source)
+    }
+}
+
+/// API bound to an address, connections are accepted when it's run
+pub struct Serving<S>
+where
+    S: Source,
+{
+    incoming: AddrIncoming,
+    api: Rc<ApiServer<S>>,
+}
+
+impl<S> Serving<S>
+where
+    S: Source + 'static,
+{
+    /// URLs of the API, see [`super::urls`]
+    #[must_use]
+    pub fn urls(&self) -> Vec<String> {
+        super::urls(self.incoming.local_addr(), API_PATH)
+    }
+
+    /// Serve requests until `stop` is completed. \
+    /// Sources aren't thread-safe, so requests are handled on the current thread
+    pub async fn run<F>(self, stop: F) -> Result<(), ServerError>
+    where
+        F: Future<Output = ()>,
+    {
+        let api = self.api;
+        let make_service = make_service_fn(move |_| {
+            let api = Rc::clone(&api);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let api = Rc::clone(&api);
+                    async move { Ok::<_, Infallible>(api.handle(request).await) }
+                }))
+            }
+        });
+
+        let server = Server::builder(self.incoming)
+            .executor(LocalExecutor)
+            .serve(make_service)
+            .with_graceful_shutdown(stop);
+
+        LocalSet::new()
+            .run_until(server)
+            .await
+            .map_err(|err| ServerError::ServeError(err.to_string()))
+    }
+}
+
+/// Executor of connections on the current thread
+#[derive(Clone, Copy)]
+struct LocalExecutor;
+
+impl<F> Executor<F> for LocalExecutor
+where
+    F: Future + 'static,
+{
+    fn execute(&self, future: F) {
+        tokio::task::spawn_local(future);
+    }
+}
+
+/// Step of the dialog the source is prepared for
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+enum Step {
+    Anime,
+    Episode,
+    Quality,
+}
+
+/// Parameters of the query string and the JSON body of the request
+struct Params(HashMap<String, String>);
+
+impl Params {
+    fn optional(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Anime are searched by `query` or selected from favorites if `favorites=true` is passed instead
+    fn query(&self) -> Result<Query<'_>, Failure> {
+        match self.optional("favorites") {
+            Some("true") => Ok(Query::Favorites),
+            _ => self.required("query").map(Query::Name),
+        }
+    }
+
+    fn required(&self, name: &str) -> Result<&str, Failure> {
+        self.optional(name).ok_or_else(|| {
+            Failure::new(
+                StatusCode::BAD_REQUEST,
+                Message::MissingParameter(name).to_string(),
+            )
+        })
+    }
+}
+
+fn query_params(uri: &hyper::Uri) -> HashMap<String, String> {
+    let Some(query) = uri.query() else {
+        return HashMap::new();
+    };
+
+    Url::parse(&format!("http://localhost/?{query}"))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+/// Parse a JSON object of the body, values which aren't strings are converted to strings
+async fn body_params(body: Body) -> Result<HashMap<String, String>, Failure> {
+    let body = body::to_bytes(body)
+        .await
+        .map_err(|err| Failure::new(StatusCode::BAD_REQUEST, err.to_string()))?;
+    if body.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    match serde_json::from_slice(&body) {
+        Ok(Value::Object(object)) => Ok(object
+            .into_iter()
+            .map(|(name, value)| match value {
+                Value::String(value) => (name, value),
+                value => (name, value.to_string()),
+            })
+            .collect()),
+        Ok(_) => Err(Failure::new(
+            StatusCode::BAD_REQUEST,
+            Message::ParseError(&"body must be a JSON object").to_string(),
+        )),
+        Err(err) => Err(Failure::new(
+            StatusCode::BAD_REQUEST,
+            Message::ParseError(&err).to_string(),
+        )),
+    }
+}
+
+fn stream_info(info: &StreamInfo) -> Value {
+    json!({
+        "resolution": info.resolution.map(|(width, height)| format!("{width}x{height}")),
+        "bandwidth": info.bandwidth,
+        "codecs": info.codecs,
+        "duration": info.duration.map(|duration| duration.as_secs()),
+        "size": info.size,
+    })
+}
+
+/// Error response of the API
+struct Failure {
+    status: StatusCode,
+    message: String,
+}
+
+impl Failure {
+    fn new<M>(status: StatusCode, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self::new(StatusCode::NOT_FOUND, "Not found")
+    }
+
+    /// The source has no logged in account, so its favorites can't be listed
+    fn no_favorites() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, Message::NoFavorites.to_string())
+    }
+
+    fn storage(err: io::Error) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+    }
+
+    fn into_response(self) -> Response<Body> {
+        json_response(self.status, &json!({ "error": self.message }))
+    }
+}
+
+impl From<SourceError> for Failure {
+    /// Unknown anime, episodes and qualities are the client errors,
+    /// other errors are the errors of the source API
+    fn from(err: SourceError) -> Self {
+        let status = match err {
+            SourceError::UnknownVariant(_) => StatusCode::NOT_FOUND,
+            SourceError::ApiError(_) | SourceError::ParseError(_) => StatusCode::BAD_GATEWAY,
+        };
+
+        Self::new(status, err.to_string())
+    }
+}
+
+fn json_response(status: StatusCode, value: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(value.to_string()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{http, MockServer, MockSource};
+    use hyper::HeaderMap;
+
+    /// Run the test while the API over the sources is served on a free port
+    /// # Arguments
+    /// * `test` - Gets the URL of the API
+    async fn serve<F, T>(sources: Vec<MockSource>, stream_check: StreamCheck, test: F)
+    where
+        F: FnOnce(String) -> T,
+        T: Future<Output = ()>,
+    {
+        let api = ApiServer::new(
+            sources,
+            stream_check,
+            History::new(None),
+            Watchlist::new(None),
+        );
+        let serving = api.bind(([127, 0, 0, 1], 0).into()).unwrap();
+        let url = serving.urls().remove(0);
+
+        tokio::select! {
+            result = serving.run(std::future::pending()) => panic!("Server stopped: {result:?}"),
+            () = test(url) => {}
+        }
+    }
+
+    /// Send a request with the JSON body
+    /// # Returns
+    /// The status and the JSON of the response
+    async fn request(method: Method, url: &str, body: Option<Value>) -> (u16, Value) {
+        let (status, _, json) = request_with_headers(method, url, body).await;
+        (status, json)
+    }
+
+    /// Send a request with the JSON body, declaring its content type unless it's a GET one
+    /// # Returns
+    /// The status, the headers and the JSON of the response
+    async fn request_with_headers(
+        method: Method,
+        url: &str,
+        body: Option<Value>,
+    ) -> (u16, HeaderMap, Value) {
+        let is_get = method == Method::GET;
+        let mut request = reqwest::Client::new().request(method, url);
+        if !is_get {
+            request = request.header(CONTENT_TYPE, "application/json");
+        }
+        if let Some(body) = body {
+            request = request.body(body.to_string());
+        }
+        let response = request.send().await.unwrap();
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+        let text = response.text().await.unwrap();
+
+        (status, headers, serde_json::from_str(&text).unwrap())
+    }
+
+    async fn get(url: &str) -> (u16, Value) {
+        request(Method::GET, url, None).await
+    }
+
+    fn sources() -> Vec<MockSource> {
+        vec![
+            MockSource::new("Mock", "http://stream.test")
+                .with_anime("Mahou Shoujo Madoka Magica", &["1", "2", "3"])
+                .with_anime("Madoka Magica Movie", &["1"]),
+            MockSource::new("Other", "http://other.test").with_anime("Bakemonogatari", &["1"]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn sources_are_listed() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let (status, sources) = get(&format!("{api}/sources")).await;
+
+            assert_eq!(status, 200);
+            let language = Language::Russian.to_string();
+            assert_eq!(
+                sources,
+                json!([
+                    { "name": "Mock", "language": language },
+                    { "name": "Other", "language": language },
+                ])
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn anime_is_searched_and_selected_up_to_stream() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let (status, search) = get(&format!("{api}/search?query=madoka")).await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                search,
+                json!({
+                    "results": [
+                        { "source": "Mock", "anime": 1, "title": "Mahou Shoujo Madoka Magica" },
+                        { "source": "Mock", "anime": 2, "title": "Madoka Magica Movie" },
+                    ],
+                    "errors": [{
+                        "source": "Other",
+                        "error": Message::EmptyAnimeList("madoka").to_string(),
+                    }],
+                })
+            );
+
+            let selected = "source=Mock&query=madoka&anime=1";
+            let (status, anime) = get(&format!("{api}/anime?{selected}")).await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                anime,
+                json!({
+                    "source": "Mock",
+                    "title": "Mahou Shoujo Madoka Magica",
+                    "episodes_info": "1, 2, 3",
+                    "episodes": ["1", "2", "3"],
+                    "in_watchlist": false,
+                })
+            );
+
+            let (status, qualities) = get(&format!("{api}/qualities?{selected}&episode=2")).await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                qualities,
+                json!([
+                    { "quality": "480p", "info": null },
+                    { "quality": "720p", "info": null },
+                ])
+            );
+
+            let (status, stream) =
+                get(&format!("{api}/stream?{selected}&episode=2&quality=720p")).await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                stream,
+                json!({
+                    "url": "http://stream.test/1/2/720p.m3u8",
+                    "quality": "720p",
+                    "warnings": [],
+                })
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn search_is_filtered_by_source_and_language() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let (status, search) = get(&format!("{api}/search?query=a&source=other")).await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                search["results"],
+                json!([{ "source": "Other", "anime": 1, "title": "Bakemonogatari" }])
+            );
+
+            let (status, search) = get(&format!("{api}/search?query=a&language=en")).await;
+            assert_eq!(status, 200);
+            assert_eq!(search, json!({ "results": [], "errors": [] }));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn qualities_are_described_by_their_playlists() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/1/1/720p.m3u8" => (
+                200,
+                String::from(
+                    "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=2000000,RESOLUTION=1280x720\nhd.m3u8\n",
+                ),
+            ),
+            "/1/1/hd.m3u8" => (
+                200,
+                String::from("#EXTM3U\n#EXTINF:10.0,\nseg1.ts\n#EXTINF:5.0,\nseg2.ts\n"),
+            ),
+            _ => (404, String::new()),
+        });
+        let sources = vec![MockSource::new("Mock", server.url()).with_anime("Madoka", &["1"])];
+
+        serve(sources, StreamCheck::new(http()), |api| async move {
+            let (status, qualities) = get(&format!(
+                "{api}/qualities?query=madoka&anime=1&episode=1&describe=true"
+            ))
+            .await;
+
+            assert_eq!(status, 200);
+            assert_eq!(qualities[0], json!({ "quality": "480p", "info": null }));
+            assert_eq!(qualities[1]["quality"], "720p");
+            assert_eq!(qualities[1]["info"]["resolution"], "1280x720");
+            assert_eq!(qualities[1]["info"]["bandwidth"], 2_000_000);
+            assert_eq!(qualities[1]["info"]["duration"], 15);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn history_marks_watched_episodes() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let (status, added) = request(
+                Method::POST,
+                &format!("{api}/history"),
+                Some(json!({ "source": "Mock", "anime": "Mahou Shoujo Madoka Magica", "episode": 2 })),
+            )
+            .await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                added,
+                json!({ "source": "Mock", "anime": "Mahou Shoujo Madoka Magica", "episode": "2" })
+            );
+
+            let (status, history) = get(&format!("{api}/history")).await;
+            assert_eq!(status, 200);
+            assert_eq!(history.as_array().unwrap().len(), 1);
+            assert_eq!(history[0]["anime"], "Mahou Shoujo Madoka Magica");
+            assert_eq!(history[0]["episode"], "2");
+
+            let (status, episodes) =
+                get(&format!("{api}/episodes?source=Mock&query=madoka&anime=1")).await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                episodes,
+                json!([
+                    { "episode": "1", "watched": false },
+                    { "episode": "2", "watched": true },
+                    { "episode": "3", "watched": false },
+                ])
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn watchlist_entries_are_added_and_removed() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let url = format!("{api}/watchlist");
+            let entry = json!({ "source": "Mock", "anime": "Madoka Magica Movie" });
+
+            let (status, added) = request(Method::POST, &url, Some(entry.clone())).await;
+            assert_eq!((status, added), (200, json!({ "added": true })));
+            let (_, added) = request(Method::POST, &url, Some(entry.clone())).await;
+            assert_eq!(added, json!({ "added": false }));
+
+            let (status, watchlist) = get(&url).await;
+            assert_eq!(status, 200);
+            assert_eq!(watchlist.as_array().unwrap().len(), 1);
+            assert_eq!(watchlist[0]["anime"], "Madoka Magica Movie");
+            let (_, anime) = get(&format!("{api}/anime?query=movie&anime=1")).await;
+            assert_eq!(anime["in_watchlist"], true);
+
+            let (status, removed) = request(Method::DELETE, &url, Some(entry)).await;
+            assert_eq!((status, removed), (200, json!({ "removed": true })));
+            let (_, watchlist) = get(&url).await;
+            assert_eq!(watchlist, json!([]));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn invalid_requests_are_bad_requests() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let (status, error) = get(&format!("{api}/search")).await;
+            assert_eq!(status, 400);
+            assert_eq!(
+                error,
+                json!({ "error": Message::MissingParameter("query").to_string() })
+            );
+
+            let (status, error) =
+                get(&format!("{api}/stream?query=madoka&anime=1&episode=1")).await;
+            assert_eq!(status, 400);
+            assert_eq!(
+                error,
+                json!({ "error": Message::MissingParameter("quality").to_string() })
+            );
+
+            let (status, _) = get(&format!("{api}/search?query=madoka&language=klingon")).await;
+            assert_eq!(status, 400);
+
+            let (status, _) = request(
+                Method::POST,
+                &format!("{api}/watchlist"),
+                Some(json!(["Mock", "Madoka Magica Movie"])),
+            )
+            .await;
+            assert_eq!(status, 400);
+
+            let response = reqwest::Client::new()
+                .post(format!("{api}/history"))
+                .header(CONTENT_TYPE, "application/json")
+                .body("{")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status().as_u16(), 400);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn favorites_are_listed_and_selected() {
+        let mut sources = sources();
+        sources[0] = sources[0].clone().with_favorites(&["Madoka Magica Movie"]);
+
+        serve(sources, StreamCheck::disabled(), |api| async move {
+            let (status, favorites) = get(&format!("{api}/favorites")).await;
+            assert_eq!(status, 200);
+            assert_eq!(
+                favorites,
+                json!({
+                    "results": [{ "source": "Mock", "anime": 1, "title": "Madoka Magica Movie" }],
+                    "errors": [],
+                })
+            );
+
+            let (status, anime) = get(&format!("{api}/anime?favorites=true&anime=1")).await;
+            assert_eq!(status, 200);
+            assert_eq!(anime["title"], "Madoka Magica Movie");
+
+            let (status, error) = get(&format!("{api}/favorites?source=other")).await;
+            assert_eq!(status, 401);
+            assert_eq!(error, json!({ "error": Message::NoFavorites.to_string() }));
+            let (status, _) = get(&format!(
+                "{api}/episodes?source=other&favorites=true&anime=1"
+            ))
+            .await;
+            assert_eq!(status, 401);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn unknown_resources_are_not_found() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let (status, error) =
+                get(&format!("{api}/anime?source=unknown&query=madoka&anime=1")).await;
+            assert_eq!(status, 404);
+            assert_eq!(
+                error,
+                json!({ "error": Message::UnknownSource("unknown").to_string() })
+            );
+
+            let (status, error) = get(&format!("{api}/anime?query=madoka&anime=3")).await;
+            assert_eq!(status, 404);
+            assert_eq!(
+                error,
+                json!({ "error": Message::UnknownAnimeSeqNum(&3).to_string() })
+            );
+
+            let selected = "query=madoka&anime=1";
+            let (status, _) = get(&format!("{api}/qualities?{selected}&episode=4")).await;
+            assert_eq!(status, 404);
+            let (status, _) = get(&format!("{api}/stream?{selected}&episode=1&quality=4k")).await;
+            assert_eq!(status, 404);
+
+            let (status, error) = get(&format!("{api}/unknown")).await;
+            assert_eq!((status, error), (404, json!({ "error": "Not found" })));
+            let (status, _) = get(api.trim_end_matches(API_PATH)).await;
+            assert_eq!(status, 404);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn only_reads_are_allowed_cross_origin() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            let (status, headers, _) =
+                request_with_headers(Method::GET, &format!("{api}/watchlist"), None).await;
+            assert_eq!(status, 200);
+            assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+
+            let entry = json!({ "source": "Mock", "anime": "Madoka Magica Movie" });
+            let (status, headers, _) =
+                request_with_headers(Method::POST, &format!("{api}/watchlist"), Some(entry)).await;
+            assert_eq!(status, 200);
+            assert!(!headers.contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+            let response = reqwest::Client::new()
+                .post(format!("{api}/history"))
+                .header(CONTENT_TYPE, "text/plain")
+                .body(r#"{ "source": "Mock", "anime": "Madoka Magica Movie", "episode": 1 }"#)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status().as_u16(), 415);
+            assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+            let (_, history) = get(&format!("{api}/history")).await;
+            assert_eq!(history, json!([]));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn unsupported_methods_are_not_allowed() {
+        serve(sources(), StreamCheck::disabled(), |api| async move {
+            for (method, endpoint) in [
+                (Method::POST, "sources"),
+                (Method::PUT, "watchlist"),
+                (Method::DELETE, "history"),
+                (Method::POST, "stream"),
+            ] {
+                let (status, error) = request(method, &format!("{api}/{endpoint}"), None).await;
+                assert_eq!(status, 405, "{endpoint}");
+                assert_eq!(error, json!({ "error": "Method not allowed" }));
+            }
+        })
+        .await;
+    }
+}
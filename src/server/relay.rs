@@ -0,0 +1,465 @@
+use crate::{
+    errors::{ServerError, SourceError},
+    locale::Message,
+    sources::{
+        hls::{self, UriKind},
+        http::Http,
+    },
+};
+
+use futures::{stream, StreamExt};
+use hyper::{
+    header::{ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_LENGTH, CONTENT_TYPE},
+    server::conn::AddrIncoming,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use reqwest::Url;
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    future::Future,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::time::{self, Instant};
+
+/// Path of the relayed playlist of the stream
+pub const STREAM_PATH: &str = "/stream.m3u8";
+
+const PLAYLIST_CONTENT_TYPE: &str = "application/vnd.apple.mpegurl";
+
+/// Settings of the relay
+#[derive(Clone)]
+pub struct RelayConfig {
+    /// Address to listen on, port `0` to choose a free port
+    pub bind: SocketAddr,
+    /// The relay is stopped if there are no requests for this time
+    pub idle_timeout: Duration,
+    /// Whether a QR code of the URL is shown
+    pub qr: bool,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from((Ipv4Addr::UNSPECIFIED, 8787)),
+            idle_timeout: Duration::from_secs(10 * 60),
+            qr: false,
+        }
+    }
+}
+
+/// Local HTTP server that proxies an HLS stream. \
+/// URIs in the playlists are rewritten to the server,
+/// so the stream can be played on other devices in the local network
+/// or by players that can't reach the media host
+#[derive(Clone)]
+pub struct Relay {
+    http: Http,
+    bind: SocketAddr,
+    idle_timeout: Duration,
+    qr: bool,
+}
+
+impl Relay {
+    #[must_use]
+    pub fn new(http: Http, config: &RelayConfig) -> Self {
+        Self {
+            http,
+            bind: config.bind,
+            idle_timeout: config.idle_timeout,
+            qr: config.qr,
+        }
+    }
+
+    /// Whether a QR code of the URL should be shown
+    #[must_use]
+    pub fn qr(&self) -> bool {
+        self.qr
+    }
+
+    #[must_use]
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// Bind the server to relay the stream
+    /// # Arguments
+    /// * `url` - URL of the master or the media playlist of the stream
+    pub fn bind(&self, url: &str) -> Result<Serving, ServerError> {
+        let url = Url::parse(url)
+            .map_err(|err| ServerError::InvalidUrl(Message::ParseError(&err).to_string()))?;
+        let incoming = AddrIncoming::bind(&self.bind).map_err(|err| {
+            ServerError::BindError(Message::CantBind(&self.bind, &err).to_string())
+        })?;
+
+        let mut resources = Resources::default();
+        resources.path(url, UriKind::Playlist);
+
+        Ok(Serving {
+            incoming,
+            state: Arc::new(State {
+                http: self.http.clone(),
+                resources: Mutex::new(resources),
+                last_activity_at: Mutex::new(Instant::now()),
+                active: AtomicUsize::new(0),
+            }),
+            idle_timeout: self.idle_timeout,
+        })
+    }
+}
+
+/// Relay bound to an address, connections are accepted when it's run
+pub struct Serving {
+    incoming: AddrIncoming,
+    state: Arc<State>,
+    idle_timeout: Duration,
+}
+
+impl Serving {
+    /// URLs of the relayed playlist, see [`super::urls`]
+    #[must_use]
+    pub fn urls(&self) -> Vec<String> {
+        super::urls(self.incoming.local_addr(), STREAM_PATH)
+    }
+
+    /// Serve the stream until it's idle for the idle timeout or `stop` is completed
+    pub async fn run<F>(self, stop: F) -> Result<(), ServerError>
+    where
+        F: Future<Output = ()>,
+    {
+        let state = Arc::clone(&self.state);
+        let make_service = make_service_fn(move |_| {
+            let state = Arc::clone(&state);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle(Arc::clone(&state), request)
+                }))
+            }
+        });
+
+        let shutdown = async {
+            tokio::select! {
+                () = stop => {}
+                () = idle(&self.state, self.idle_timeout) => {}
+            }
+        };
+
+        Server::builder(self.incoming)
+            .serve(make_service)
+            .with_graceful_shutdown(shutdown)
+            .await
+            .map_err(|err| ServerError::ServeError(err.to_string()))
+    }
+}
+
+struct State {
+    http: Http,
+    resources: Mutex<Resources>,
+    last_activity_at: Mutex<Instant>,
+    /// Count of responses being sent
+    active: AtomicUsize,
+}
+
+impl State {
+    fn touch(&self) {
+        *self.last_activity_at.lock().unwrap() = Instant::now();
+    }
+}
+
+/// Upstream resources by their local IDs, the playlist of the stream has ID `0`. \
+/// Resources are registered when the playlists referencing them are relayed
+/// and are removed when the playlist doesn't reference them anymore,
+/// e.g. old segments of a live stream, so the map doesn't grow while the stream is played
+#[derive(Default)]
+struct Resources {
+    resources: HashMap<usize, (Url, UriKind)>,
+    ids: HashMap<Url, usize>,
+    next_id: usize,
+    /// IDs of resources referenced by the last relayed version of each playlist
+    referenced: HashMap<usize, HashSet<usize>>,
+}
+
+impl Resources {
+    /// Get the local path of the resource, the resource is registered if it's new
+    fn path(&mut self, url: Url, kind: UriKind) -> String {
+        let id = self.register(url.clone(), kind);
+        Self::local_path(id, &url, kind)
+    }
+
+    /// Rewrite URIs of the playlist to local paths and remove resources
+    /// that were referenced only by the previous version of the playlist
+    /// # Arguments
+    /// * `id` - ID of the playlist
+    /// * `url` - URL of the playlist, relative URIs are resolved against it
+    fn rewrite(&mut self, id: usize, url: &Url, playlist: &str) -> String {
+        let mut referenced = HashSet::new();
+        let playlist = hls::rewrite_uris(playlist, |uri, kind| match url.join(uri) {
+            Ok(url) => {
+                let id = self.register(url.clone(), kind);
+                referenced.insert(id);
+                Self::local_path(id, &url, kind)
+            }
+            Err(_) => uri.to_string(),
+        });
+
+        let previous = self.referenced.insert(id, referenced).unwrap_or_default();
+        for stale in previous {
+            self.release(stale);
+        }
+
+        playlist
+    }
+
+    fn register(&mut self, url: Url, kind: UriKind) -> usize {
+        if let Some(id) = self.ids.get(&url) {
+            return *id;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ids.insert(url.clone(), id);
+        self.resources.insert(id, (url, kind));
+        id
+    }
+
+    /// Remove the resource if no playlist references it,
+    /// resources referenced only by a removed playlist are removed too
+    fn release(&mut self, id: usize) {
+        let referenced = self
+            .referenced
+            .values()
+            .any(|referenced| referenced.contains(&id));
+        if id == 0 || referenced {
+            return;
+        }
+
+        if let Some((url, _)) = self.resources.remove(&id) {
+            self.ids.remove(&url);
+        }
+        for child in self.referenced.remove(&id).unwrap_or_default() {
+            self.release(child);
+        }
+    }
+
+    fn local_path(id: usize, url: &Url, kind: UriKind) -> String {
+        if id == 0 {
+            return STREAM_PATH.to_string();
+        }
+        match kind {
+            UriKind::Playlist => format!("/{id}.m3u8"),
+            // Some players detect the format by the extension
+            UriKind::Media => match url
+                .path_segments()
+                .and_then(Iterator::last)
+                .and_then(|name| name.rsplit_once('.'))
+            {
+                Some((_, extension)) => format!("/{id}.{extension}"),
+                None => format!("/{id}"),
+            },
+        }
+    }
+
+    /// Get the ID, the URL and the kind of the resource by its local path
+    fn get(&self, path: &str) -> Option<(usize, Url, UriKind)> {
+        let id = if path == STREAM_PATH {
+            0
+        } else {
+            let name = path.strip_prefix('/')?;
+            name.split('.').next()?.parse().ok()?
+        };
+
+        self.resources
+            .get(&id)
+            .map(|(url, kind)| (id, url.clone(), *kind))
+    }
+}
+
+/// Guard of a response being sent, the relay isn't idle while it exists
+struct Activity(Arc<State>);
+
+impl Activity {
+    fn start(state: Arc<State>) -> Self {
+        state.active.fetch_add(1, Ordering::SeqCst);
+        Self(state)
+    }
+}
+
+impl Drop for Activity {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::SeqCst);
+        self.0.touch();
+    }
+}
+
+async fn handle(state: Arc<State>, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    state.touch();
+
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return Ok(text_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Method not allowed",
+        ));
+    }
+
+    let resource = state.resources.lock().unwrap().get(request.uri().path());
+    let response = match resource {
+        Some((id, url, UriKind::Playlist)) => relay_playlist(&state, id, &url).await,
+        Some((_, url, UriKind::Media)) => relay_media(&state, &url).await,
+        None => return Ok(text_response(StatusCode::NOT_FOUND, "Not found")),
+    };
+
+    Ok(response.unwrap_or_else(|err| text_response(StatusCode::BAD_GATEWAY, err.to_string())))
+}
+
+async fn relay_playlist(
+    state: &State,
+    id: usize,
+    url: &Url,
+) -> Result<Response<Body>, SourceError> {
+    let playlist = state.http.get_text(url.as_str(), &[]).await?;
+    let playlist = state.resources.lock().unwrap().rewrite(id, url, &playlist);
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, PLAYLIST_CONTENT_TYPE)
+        .header(ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(Body::from(playlist))
+        .unwrap())
+}
+
+/// Stream the resource to the client without buffering it
+async fn relay_media(state: &Arc<State>, url: &Url) -> Result<Response<Body>, SourceError> {
+    let upstream = state.http.get(url.as_str()).await?;
+
+    let mut response = Response::builder()
+        .status(upstream.status())
+        .header(ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+    for name in [CONTENT_TYPE, CONTENT_LENGTH] {
+        if let Some(value) = upstream.headers().get(&name) {
+            response = response.header(name, value);
+        }
+    }
+
+    let activity = Activity::start(Arc::clone(state));
+    let http = state.http.clone();
+    let body = stream::unfold(Some(upstream), move |upstream| {
+        let http = http.clone();
+        async move {
+            let mut upstream = upstream?;
+            match http.chunk(&mut upstream).await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(upstream))),
+                Ok(None) => None,
+                // The body ends after the error
+                Err(err) => Some((Err(err), None)),
+            }
+        }
+    })
+    .map(move |chunk| {
+        activity.0.touch();
+        chunk
+    });
+
+    Ok(response.body(Body::wrap_stream(body)).unwrap())
+}
+
+fn text_response<T>(status: StatusCode, text: T) -> Response<Body>
+where
+    T: Into<Body>,
+{
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(text.into())
+        .unwrap()
+}
+
+/// Wait until there are no requests and no responses are sent for the timeout
+async fn idle(state: &State, timeout: Duration) {
+    loop {
+        let deadline = *state.last_activity_at.lock().unwrap() + timeout;
+
+        if Instant::now() < deadline {
+            time::sleep_until(deadline).await;
+        } else if state.active.load(Ordering::SeqCst) == 0 {
+            return;
+        } else {
+            // A response is stalled, it's checked again later
+            time::sleep(timeout).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live_playlist(segments: std::ops::Range<usize>) -> String {
+        let mut playlist = String::from("#EXTM3U\n#EXT-X-TARGETDURATION:10\n");
+        for segment in segments {
+            playlist.push_str(&format!("#EXTINF:10.0,\nsegment{segment}.ts\n"));
+        }
+        playlist
+    }
+
+    #[test]
+    fn segments_dropped_from_live_playlist_are_evicted() {
+        let mut resources = Resources::default();
+        let master = Url::parse("http://cdn.test/master.m3u8").unwrap();
+        resources.path(master.clone(), UriKind::Playlist);
+
+        let variant = resources.rewrite(
+            0,
+            &master,
+            "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1\nhd/index.m3u8\n",
+        );
+        assert!(variant.contains("/1.m3u8"), "{variant}");
+        let (id, url, _) = resources.get("/1.m3u8").unwrap();
+
+        for window in 0..100 {
+            let playlist = resources.rewrite(id, &url, &live_playlist(window..window + 3));
+            assert_eq!(playlist.matches(".ts").count(), 3, "{playlist}");
+        }
+
+        // The master playlist, the variant playlist and the 3 segments of the last window
+        assert_eq!(resources.resources.len(), 5);
+        assert_eq!(resources.ids.len(), 5);
+        let (_, segment, kind) = resources
+            .get(&format!("/{}.ts", resources.next_id - 1))
+            .unwrap();
+        assert_eq!(segment.as_str(), "http://cdn.test/hd/segment101.ts");
+        assert!(kind == UriKind::Media);
+        assert!(resources.get("/2.ts").is_none());
+    }
+
+    #[test]
+    fn playlists_dropped_from_master_playlist_are_evicted_with_their_segments() {
+        let mut resources = Resources::default();
+        let master = Url::parse("http://cdn.test/master.m3u8").unwrap();
+        resources.path(master.clone(), UriKind::Playlist);
+
+        resources.rewrite(
+            0,
+            &master,
+            "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1\nsd.m3u8\n",
+        );
+        let (id, url, _) = resources.get("/1.m3u8").unwrap();
+        resources.rewrite(id, &url, &live_playlist(0..3));
+        assert_eq!(resources.resources.len(), 5);
+
+        resources.rewrite(
+            0,
+            &master,
+            "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=2\nhd.m3u8\n",
+        );
+
+        assert_eq!(resources.resources.len(), 2);
+        assert!(resources.get("/1.m3u8").is_none());
+        assert!(resources.get(STREAM_PATH).is_some());
+        assert!(resources.get("/5.m3u8").is_some());
+    }
+}
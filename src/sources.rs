@@ -1,2 +1,13 @@
+pub mod any;
 pub mod base;
+pub mod cache;
+pub mod details;
+pub mod hls;
+pub mod http;
+pub mod mirrors;
+pub mod plugin;
+pub mod rest;
 pub mod ru;
+pub mod scrape;
+pub mod search;
+pub mod stream;
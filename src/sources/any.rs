@@ -0,0 +1,136 @@
+use super::{
+    base::Source,
+    details::{AnimeDetails, EpisodeDetails},
+    plugin::Plugin,
+    rest::Rest,
+    ru::anilibria::Anilibria,
+    scrape::HtmlSource,
+};
+
+use crate::{enums::language::Language, errors::SourceError};
+
+use std::fmt::{self, Display};
+
+/// One of the built-in sources or a source loaded at runtime or defined in the config file,
+/// so sources of different types can be used in the same list. \
+/// Information of the wrapped source is converted to strings
+#[derive(Clone)]
+pub enum AnySource<'a> {
+    Anilibria(Anilibria<'a>),
+    Plugin(Plugin),
+    Rest(Rest),
+    Html(HtmlSource),
+}
+
+/// Call the method of the wrapped source
+macro_rules! dispatch {
+    ($self:ident, $source:ident => $call:expr) => {
+        match $self {
+            Self::Anilibria($source) => $call,
+            Self::Plugin($source) => $call,
+            Self::Rest($source) => $call,
+            Self::Html($source) => $call,
+        }
+    };
+}
+
+impl Display for AnySource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        dispatch!(self, source => write!(f, "{source}"))
+    }
+}
+
+impl PartialEq<String> for AnySource<'_> {
+    fn eq(&self, other: &String) -> bool {
+        dispatch!(self, source => source == other)
+    }
+}
+
+impl Source for AnySource<'_> {
+    type SearchAnimeListInfo = String;
+    type AnimeInfo = String;
+    type EpisodesInfo = String;
+    type EpisodeIndo = String;
+    type QualitiesInfo = String;
+    type QualityInfo = String;
+
+    fn language(&self) -> &Language {
+        dispatch!(self, source => source.language())
+    }
+
+    async fn search_anime_list(
+        &mut self,
+        query: &str,
+    ) -> Result<Self::SearchAnimeListInfo, SourceError> {
+        dispatch!(self, source => source.search_anime_list(query).await.map(|info| info.to_string()))
+    }
+
+    async fn select_anime_as_current(&mut self, anime: String) -> Result<(), SourceError> {
+        dispatch!(self, source => source.select_anime_as_current(anime).await)
+    }
+
+    fn anime_list(&self) -> Vec<String> {
+        dispatch!(self, source => source.anime_list())
+    }
+
+    async fn favorite_anime_list(
+        &mut self,
+    ) -> Result<Option<Self::SearchAnimeListInfo>, SourceError> {
+        dispatch!(self, source => source
+            .favorite_anime_list()
+            .await
+            .map(|info| info.map(|info| info.to_string())))
+    }
+
+    async fn set_favorite(&self, favorite: bool) -> Result<(), SourceError> {
+        dispatch!(self, source => source.set_favorite(favorite).await)
+    }
+
+    fn anime_info(&self) -> Result<Self::AnimeInfo, SourceError> {
+        dispatch!(self, source => source.anime_info().map(|info| info.to_string()))
+    }
+
+    fn anime_details(&self) -> Result<AnimeDetails, SourceError> {
+        dispatch!(self, source => source.anime_details())
+    }
+
+    fn episodes_info(&mut self) -> Result<Self::EpisodesInfo, SourceError> {
+        dispatch!(self, source => source.episodes_info().map(|info| info.to_string()))
+    }
+
+    async fn select_episode_as_current(&mut self, episode: String) -> Result<(), SourceError> {
+        dispatch!(self, source => source.select_episode_as_current(episode).await)
+    }
+
+    fn episodes(&self) -> Result<Vec<String>, SourceError> {
+        dispatch!(self, source => source.episodes())
+    }
+
+    fn episode_info(&self) -> Result<Self::EpisodeIndo, SourceError> {
+        dispatch!(self, source => source.episode_info().map(|info| info.to_string()))
+    }
+
+    fn episode_details(&self) -> EpisodeDetails {
+        dispatch!(self, source => source.episode_details())
+    }
+
+    fn qualities_info(&mut self) -> Result<Self::QualitiesInfo, SourceError> {
+        dispatch!(self, source => source.qualities_info().map(|info| info.to_string()))
+    }
+
+    fn qualities(&self) -> Result<Vec<String>, SourceError> {
+        dispatch!(self, source => source.qualities())
+    }
+
+    fn select_quality_as_current(&mut self, quality: String) -> Result<(), SourceError> {
+        dispatch!(self, source => source.select_quality_as_current(quality))
+    }
+
+    fn quality_info(&self) -> Result<Self::QualityInfo, SourceError> {
+        dispatch!(self, source => source.quality_info().map(|info| info.to_string()))
+    }
+
+    async fn url_for_stream(&self) -> Result<String, SourceError> {
+        dispatch!(self, source => source.url_for_stream().await)
+    }
+}
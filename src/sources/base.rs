@@ -1,12 +1,18 @@
 use std::fmt::Display;
 
-use crate::{errors::SourceError, Language};
+use super::details::{AnimeDetails, EpisodeDetails};
+use crate::{errors::SourceError, locale::Message, Language};
 
-/// A base trait for all sources
+/// A base trait for all sources. \
+/// Methods that may need network are asynchronous:
+/// sources load the data of the next step when an anime or an episode is selected,
+/// so the other methods only describe the loaded data
 /// # Sub traits
-/// - [`Display`](std::fmt::Display): Display the name and language of the source, e.g. `Anilibria (Russian)`
-/// - [`PartialEq`](std::cmp::PartialEq): Compare source by name,
-///     e.g. `Anilibria` || `anilibria` || `anilibria.tv` for [Anilibria](crate::sources::ru::anilibria::Anilibria)
+/// - [`Display`]: Display the name and language of the source, e.g. `Anilibria (Russian)`
+/// - [`PartialEq`]: Compare source by name,
+///   e.g. `Anilibria` || `anilibria` || `anilibria.tv` for [Anilibria](crate::sources::ru::anilibria::Anilibria)
+// Futures of sources aren't `Send`, sources share state through `Rc`
+#[allow(async_fn_in_trait)]
 pub trait Source: Clone + Display + PartialEq<String> {
     type SearchAnimeListInfo: Display;
     type AnimeInfo: Display;
@@ -21,7 +27,10 @@ pub trait Source: Clone + Display + PartialEq<String> {
     /// Search anime by name
     /// # Arguments
     /// * `name` - Anime name.
-    fn search_anime_list(&mut self, query: &str) -> Result<Self::SearchAnimeListInfo, SourceError>;
+    async fn search_anime_list(
+        &mut self,
+        query: &str,
+    ) -> Result<Self::SearchAnimeListInfo, SourceError>;
 
     /// Select an anime as current anime. \
     /// This method is used to select an anime from list of anime
@@ -31,11 +40,47 @@ pub trait Source: Clone + Display + PartialEq<String> {
     /// Any detail that can define an anime. \
     /// For example, anime name, sequence number in list and other. \
     /// What is the best way to specify the anime, depends on the source.
-    fn select_anime_as_current(&mut self, _: String) -> Result<(), SourceError>;
+    async fn select_anime_as_current(&mut self, _: String) -> Result<(), SourceError>;
+
+    /// Get found anime of the last search in the same order as in the search result. \
+    /// Sequence number of an anime in the list (starting from 1) can be passed to
+    /// [`select_anime_as_current`](Source::select_anime_as_current),
+    /// it takes precedence over titles containing the number, see [`find_in_anime_list`]
+    fn anime_list(&self) -> Vec<String>;
+
+    /// Load favorite anime of the account of the user as found anime,
+    /// so they can be selected like results of [`search_anime_list`](Source::search_anime_list)
+    /// # Returns
+    /// `Ok(None)` if the source has no accounts or the user isn't logged in
+    async fn favorite_anime_list(
+        &mut self,
+    ) -> Result<Option<Self::SearchAnimeListInfo>, SourceError> {
+        Ok(None)
+    }
+
+    /// Add current anime to favorites of the account of the user or remove it from them
+    /// # Returns
+    /// An error if the source has no accounts or the user isn't logged in
+    async fn set_favorite(&self, favorite: bool) -> Result<(), SourceError> {
+        let _ = favorite;
+
+        Err(SourceError::UnknownVariant(
+            Message::FavoritesUnsupported(&self.to_string()).to_string(),
+        ))
+    }
 
     /// Get information about the anime
     fn anime_info(&self) -> Result<Self::AnimeInfo, SourceError>;
 
+    /// Get details of current anime, e.g. its description and genres. \
+    /// Sources that don't know them return only the title from [`anime_info`](Source::anime_info)
+    fn anime_details(&self) -> Result<AnimeDetails, SourceError> {
+        Ok(AnimeDetails {
+            title: self.anime_info()?.to_string(),
+            ..AnimeDetails::default()
+        })
+    }
+
     /// Get information about episodes of current anime
     fn episodes_info(&mut self) -> Result<Self::EpisodesInfo, SourceError>;
 
@@ -47,14 +92,28 @@ pub trait Source: Clone + Display + PartialEq<String> {
     /// Any detail that can define an episode. \
     /// For example, episode name, sequence number in list and other. \
     /// What is the best way to specify the episode, depends on the source.
-    fn select_episode_as_current(&mut self, _: String) -> Result<(), SourceError>;
+    async fn select_episode_as_current(&mut self, _: String) -> Result<(), SourceError>;
+
+    /// Get episodes of current anime in ascending order. \
+    /// Each episode can be passed to [`select_episode_as_current`](Source::select_episode_as_current)
+    fn episodes(&self) -> Result<Vec<String>, SourceError>;
 
     /// Get information about the episode
     fn episode_info(&self) -> Result<Self::EpisodeIndo, SourceError>;
 
+    /// Get details of current episode, e.g. the voice team and the time of the opening and ending. \
+    /// Sources that don't know them return empty details
+    fn episode_details(&self) -> EpisodeDetails {
+        EpisodeDetails::default()
+    }
+
     /// Get information about qualities of current episode
     fn qualities_info(&mut self) -> Result<Self::QualitiesInfo, SourceError>;
 
+    /// Get qualities of current episode from the lowest to the highest. \
+    /// Each quality can be passed to [`select_quality_as_current`](Source::select_quality_as_current)
+    fn qualities(&self) -> Result<Vec<String>, SourceError>;
+
     /// Select a quality as current quality. \
     /// This method is used to select a quality from qualities
     /// and select it for future use. \
@@ -65,6 +124,82 @@ pub trait Source: Clone + Display + PartialEq<String> {
     /// What is the best way to specify the quality, depends on the source.
     fn select_quality_as_current(&mut self, _: String) -> Result<(), SourceError>;
 
+    /// Get information about the quality. \
+    /// It's displayed the same way as the quality in [`qualities`](Source::qualities)
+    fn quality_info(&self) -> Result<Self::QualityInfo, SourceError>;
+
     /// Get url for steam anime and use it in player
-    fn url_for_stream(&self) -> Result<String, SourceError>;
+    async fn url_for_stream(&self) -> Result<String, SourceError>;
+}
+
+/// Find an anime of the last search by its sequence number or by a part of its title,
+/// see [`Source::select_anime_as_current`]. \
+/// The sequence number takes precedence, so a title with digits, e.g. `... 2nd Season`,
+/// doesn't shadow the anime of the number, numbers out of the list are matched as titles, e.g. `86`
+/// # Arguments
+/// * `matches` - Whether the anime matches the lowercase part of the title
+pub fn find_in_anime_list<'a, T, F>(
+    anime_list: &'a [T],
+    title_or_seq_num: &str,
+    matches: F,
+) -> Result<&'a T, SourceError>
+where
+    F: Fn(&T, &str) -> bool,
+{
+    let seq_num = title_or_seq_num.parse::<usize>().ok();
+    if let Some(anime) = seq_num
+        .and_then(|seq_num| seq_num.checked_sub(1))
+        .and_then(|index| anime_list.get(index))
+    {
+        return Ok(anime);
+    }
+
+    let name = title_or_seq_num.to_lowercase();
+    if let Some(anime) = anime_list.iter().find(|anime| matches(anime, &name)) {
+        return Ok(anime);
+    }
+
+    let message = match seq_num {
+        Some(seq_num) => Message::UnknownAnimeSeqNum(&seq_num).to_string(),
+        None => Message::UnknownAnimeName(title_or_seq_num).to_string(),
+    };
+    Err(SourceError::UnknownVariant(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(anime_list: &'a [&'a str], title_or_seq_num: &str) -> Result<&'a str, SourceError> {
+        find_in_anime_list(anime_list, title_or_seq_num, |title, name| {
+            title.to_lowercase().contains(name)
+        })
+        .copied()
+    }
+
+    #[test]
+    fn sequence_number_takes_precedence_over_titles_with_digits() {
+        let anime_list = ["Gintama 2", "Gintama", "86 Eighty-Six"];
+
+        assert_eq!(find(&anime_list, "2").unwrap(), "Gintama");
+        assert_eq!(find(&anime_list, "1").unwrap(), "Gintama 2");
+        // Numbers out of the list are parts of titles
+        assert_eq!(find(&anime_list, "86").unwrap(), "86 Eighty-Six");
+        assert_eq!(find(&anime_list, "eighty").unwrap(), "86 Eighty-Six");
+    }
+
+    #[test]
+    fn unknown_anime_is_rejected() {
+        let anime_list = ["Gintama"];
+
+        assert_eq!(
+            find(&anime_list, "7").unwrap_err().to_string(),
+            SourceError::UnknownVariant(Message::UnknownAnimeSeqNum(&7).to_string()).to_string()
+        );
+        assert_eq!(
+            find(&anime_list, "madoka").unwrap_err().to_string(),
+            SourceError::UnknownVariant(Message::UnknownAnimeName("madoka").to_string())
+                .to_string()
+        );
+    }
 }
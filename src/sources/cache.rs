@@ -0,0 +1,162 @@
+use crate::storage::{cache_path, read_json, write_json};
+
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fs,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// On-disk cache of API responses keyed by endpoint and parameters. \
+/// Responses are kept after they expire, so they are used when the API is unavailable
+#[derive(Clone, Default)]
+pub struct Cache {
+    /// `None` if the cache is disabled
+    dir: Option<PathBuf>,
+    /// TTLs of endpoints that override TTLs of sources
+    ttls: HashMap<String, Duration>,
+}
+
+impl Cache {
+    /// Create a cache in the directory, `None` to disable the cache
+    #[must_use]
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            ttls: HashMap::new(),
+        }
+    }
+
+    /// Create a cache in the user cache directory
+    #[must_use]
+    pub fn load() -> Self {
+        Self::new(cache_path("http"))
+    }
+
+    /// Override the TTL of the endpoint
+    #[must_use]
+    pub fn with_ttl(mut self, endpoint: &str, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint.to_string(), ttl);
+        self
+    }
+
+    /// Get the response from the cache if it's fresh, otherwise request it and store it
+    /// # Arguments
+    /// * `endpoint` - Name of the endpoint including the source, e.g. `anilibria/searchTitles`
+    /// * `params` - Parameters of the request that change the response
+    /// * `ttl` - How long the response stays fresh, e.g. minutes for updates and days for details of an anime
+    /// * `request` - Request of the response
+    /// # Returns
+    /// The stale response if the request failed and the response was cached before,
+    /// otherwise the error of the request
+    pub async fn fetch<F, Fut, E>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+        ttl: Duration,
+        request: F,
+    ) -> Result<String, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+    {
+        let Some(path) = self.path(endpoint, params) else {
+            return request().await;
+        };
+
+        let ttl = self.ttls.get(endpoint).copied().unwrap_or(ttl);
+        let cached = read_entry(&path);
+        if let Some((stored_at, body)) = &cached {
+            if now().saturating_sub(*stored_at) < ttl.as_secs() {
+                return Ok(body.clone());
+            }
+        }
+
+        match request().await {
+            Ok(body) => {
+                // The cache is an optimization, so failed writes aren't errors
+                let _ = write_json(
+                    &path,
+                    &json!({
+                        "endpoint": endpoint,
+                        "stored_at": now(),
+                        "body": body,
+                    }),
+                );
+                Ok(body)
+            }
+            Err(err) => match cached {
+                Some((_, body)) => Ok(body),
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Remove all cached responses
+    /// # Returns
+    /// Count of the removed responses
+    pub fn clear(&self) -> io::Result<usize> {
+        let Some(dir) = &self.dir else {
+            return Ok(0);
+        };
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn path(&self, endpoint: &str, params: &[(&str, &str)]) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+
+        let mut key = endpoint.to_string();
+        for (name, value) in params {
+            key.push_str(&format!("\n{name}={value}"));
+        }
+
+        Some(dir.join(format!("{:016x}.json", fnv1a(key.as_bytes()))))
+    }
+}
+
+/// Read the time the response was stored at and the response
+fn read_entry(path: &Path) -> Option<(u64, String)> {
+    let entry = read_json(path).ok()??;
+
+    Some((
+        entry["stored_at"].as_u64()?,
+        entry["body"].as_str()?.to_string(),
+    ))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// FNV-1a hash, unlike `DefaultHasher` it's stable between Rust versions,
+/// so cached files are found after an update
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
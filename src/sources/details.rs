@@ -0,0 +1,27 @@
+use std::{ops::Range, time::Duration};
+
+/// Details of an anime for media libraries, e.g. for NFO files of Kodi and Jellyfin
+#[derive(Clone, Default)]
+pub struct AnimeDetails {
+    pub title: String,
+    /// Title in the original language, e.g. romaji
+    pub original_title: Option<String>,
+    pub description: Option<String>,
+    pub genres: Vec<String>,
+    pub year: Option<u16>,
+    /// URL of the poster
+    pub poster: Option<String>,
+}
+
+/// Details of an episode that aren't needed to play it, e.g. for metadata of downloaded files
+#[derive(Clone, Default)]
+pub struct EpisodeDetails {
+    /// Name of the episode, e.g. `Как будто во сне`
+    pub title: Option<String>,
+    /// Names of voice actors, e.g. `["Amikiri", "Hekomi"]`
+    pub voice: Vec<String>,
+    /// Time of the opening, `None` if it's unknown
+    pub opening: Option<Range<Duration>>,
+    /// Time of the ending, `None` if it's unknown
+    pub ending: Option<Range<Duration>>,
+}
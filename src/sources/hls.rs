@@ -0,0 +1,268 @@
+use crate::{errors::SourceError, locale::Message};
+
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+/// Parsed HLS playlist
+pub enum Playlist {
+    /// Playlist of variants of the stream, e.g. in different resolutions
+    Master(Vec<Variant>),
+    Media(MediaPlaylist),
+}
+
+/// Variant of a master playlist
+pub struct Variant {
+    /// URI of the media playlist, it may be relative to the master playlist
+    pub uri: String,
+    /// Peak bitrate in bits per second
+    pub bandwidth: Option<u64>,
+    /// Width and height
+    pub resolution: Option<(u32, u32)>,
+    /// e.g. `avc1.64001f,mp4a.40.2`
+    pub codecs: Option<String>,
+    pub frame_rate: Option<f64>,
+}
+
+pub struct MediaPlaylist {
+    pub target_duration: Option<Duration>,
+    pub segments: Vec<Segment>,
+    /// Whether the playlist has `#EXT-X-ENDLIST`, i.e. it's not a live stream
+    pub ended: bool,
+    /// Whether segments are encrypted by `#EXT-X-KEY`
+    pub encrypted: bool,
+}
+
+pub struct Segment {
+    /// URI of the segment, it may be relative to the playlist
+    pub uri: String,
+    pub duration: Duration,
+}
+
+impl MediaPlaylist {
+    /// Total duration of the segments
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.segments.iter().map(|segment| segment.duration).sum()
+    }
+}
+
+/// Parse an HLS playlist
+/// # Returns
+/// An error if the text isn't an HLS playlist
+pub fn parse(text: &str) -> Result<Playlist, SourceError> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    if lines.next() != Some("#EXTM3U") {
+        return Err(SourceError::ParseError(
+            Message::ParseError(&"`#EXTM3U` header is missing").to_string(),
+        ));
+    }
+
+    let mut variants = Vec::new();
+    let mut media = MediaPlaylist {
+        target_duration: None,
+        segments: Vec::new(),
+        ended: false,
+        encrypted: false,
+    };
+    // Attributes of the next URI
+    let mut variant: Option<Variant> = None;
+    let mut segment_duration: Option<Duration> = None;
+
+    for line in lines {
+        if let Some(attributes) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            variant = Some(parse_variant(attributes));
+        } else if let Some(value) = line.strip_prefix("#EXTINF:") {
+            let duration = value.split(',').next().unwrap_or_default();
+            segment_duration = Some(parse_duration(duration));
+        } else if let Some(value) = line.strip_prefix("#EXT-X-TARGETDURATION:") {
+            media.target_duration = Some(parse_duration(value));
+        } else if line == "#EXT-X-ENDLIST" {
+            media.ended = true;
+        } else if let Some(attributes) = line.strip_prefix("#EXT-X-KEY:") {
+            media.encrypted |= parse_attributes(attributes)
+                .into_iter()
+                .any(|(name, value)| name == "METHOD" && value != "NONE");
+        } else if line.starts_with('#') {
+            // Other tags and comments aren't needed
+        } else if let Some(mut variant) = variant.take() {
+            variant.uri = line.to_string();
+            variants.push(variant);
+        } else {
+            media.segments.push(Segment {
+                uri: line.to_string(),
+                duration: segment_duration.take().unwrap_or_default(),
+            });
+        }
+    }
+
+    if variants.is_empty() {
+        Ok(Playlist::Media(media))
+    } else {
+        Ok(Playlist::Master(variants))
+    }
+}
+
+/// Kind of a resource referenced by a playlist
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum UriKind {
+    /// Media playlist of a variant or a rendition
+    Playlist,
+    /// Segment, encryption key or initialization section
+    Media,
+}
+
+/// Replace URIs of the playlist, e.g. to proxy the stream through another server. \
+/// URIs in attributes of tags like `#EXT-X-KEY` and `#EXT-X-MEDIA` are replaced too
+/// # Arguments
+/// * `rewrite` - Function of the original URI and its kind that returns the new URI
+pub fn rewrite_uris<F>(text: &str, mut rewrite: F) -> String
+where
+    F: FnMut(&str, UriKind) -> String,
+{
+    let mut rewritten = String::with_capacity(text.len());
+    let mut variant_uri_is_next = false;
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if line.starts_with('#') {
+            variant_uri_is_next |= line.starts_with("#EXT-X-STREAM-INF:");
+
+            let kind = if line.starts_with("#EXT-X-MEDIA:")
+                || line.starts_with("#EXT-X-I-FRAME-STREAM-INF:")
+            {
+                UriKind::Playlist
+            } else {
+                UriKind::Media
+            };
+            match split_uri_attribute(line) {
+                Some((prefix, uri, suffix)) => {
+                    rewritten.push_str(prefix);
+                    rewritten.push_str(&rewrite(uri, kind));
+                    rewritten.push_str(suffix);
+                }
+                None => rewritten.push_str(line),
+            }
+        } else if variant_uri_is_next {
+            variant_uri_is_next = false;
+            rewritten.push_str(&rewrite(line, UriKind::Playlist));
+        } else {
+            rewritten.push_str(&rewrite(line, UriKind::Media));
+        }
+        rewritten.push('\n');
+    }
+
+    rewritten
+}
+
+/// Split a tag by its quoted `URI` attribute
+/// # Returns
+/// The tag before the URI, the URI and the tag after the URI
+fn split_uri_attribute(tag: &str) -> Option<(&str, &str, &str)> {
+    let start = tag.find("URI=\"")? + "URI=\"".len();
+    let end = start + tag[start..].find('"')?;
+
+    Some((&tag[..start], &tag[start..end], &tag[end..]))
+}
+
+fn parse_variant(attributes: &str) -> Variant {
+    let mut variant = Variant {
+        uri: String::new(),
+        bandwidth: None,
+        resolution: None,
+        codecs: None,
+        frame_rate: None,
+    };
+
+    for (name, value) in parse_attributes(attributes) {
+        match name {
+            "BANDWIDTH" => variant.bandwidth = value.parse().ok(),
+            "RESOLUTION" => {
+                variant.resolution = value
+                    .split_once(['x', 'X'])
+                    .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+            }
+            "CODECS" => variant.codecs = Some(value.to_string()),
+            "FRAME-RATE" => variant.frame_rate = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    variant
+}
+
+/// Parse an attribute list, e.g. `BANDWIDTH=1280000,CODECS="avc1.4d401f,mp4a.40.2"`. \
+/// Quotes of quoted values are removed
+fn parse_attributes(attributes: &str) -> Vec<(&str, &str)> {
+    let mut parsed = Vec::new();
+    let mut rest = attributes;
+
+    while let Some((name, value_and_rest)) = rest.split_once('=') {
+        let (value, next) = if let Some(quoted) = value_and_rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            let next = quoted.get(end + 1..).unwrap_or_default();
+            (&quoted[..end], next)
+        } else {
+            let end = value_and_rest.find(',').unwrap_or(value_and_rest.len());
+            (&value_and_rest[..end], &value_and_rest[end..])
+        };
+
+        parsed.push((name.trim(), value));
+        rest = next.trim_start_matches(',');
+    }
+
+    parsed
+}
+
+fn parse_duration(value: &str) -> Duration {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .unwrap_or_default()
+}
+
+/// Summary of a stream shown in the quality menu
+#[derive(Default)]
+pub struct StreamInfo {
+    pub resolution: Option<(u32, u32)>,
+    /// Bitrate in bits per second
+    pub bandwidth: Option<u64>,
+    pub codecs: Option<String>,
+    pub duration: Option<Duration>,
+    /// Size in bytes, estimated by the bitrate and the duration
+    pub size: Option<u64>,
+}
+
+impl Display for StreamInfo {
+    /// e.g. `1280x720 · 2.1 Mbps · avc1.64001f · 23:40 · ~365 MB`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+
+        if let Some((width, height)) = self.resolution {
+            parts.push(format!("{width}x{height}"));
+        }
+        if let Some(bandwidth) = self.bandwidth {
+            #[allow(clippy::cast_precision_loss)]
+            parts.push(format!("{:.1} Mbps", bandwidth as f64 / 1_000_000.0));
+        }
+        if let Some(codecs) = &self.codecs {
+            parts.push(codecs.clone());
+        }
+        if let Some(duration) = self.duration {
+            let secs = duration.as_secs();
+            parts.push(if secs >= 3600 {
+                format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+            } else {
+                format!("{}:{:02}", secs / 60, secs % 60)
+            });
+        }
+        if let Some(size) = self.size {
+            parts.push(format!("~{} MB", size / 1_000_000));
+        }
+
+        write!(f, "{}", parts.join(" · "))
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{errors::SourceError, locale::Message};
+
+use hyper::body::Bytes;
+use reqwest::{
+    header::{CONTENT_RANGE, CONTENT_TYPE, RANGE},
+    Client, Method, Proxy, RequestBuilder, Response, StatusCode,
+};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::{self, Instant};
+
+/// Settings of the HTTP client shared by sources
+#[derive(Clone)]
+pub struct HttpConfig {
+    /// Time to wait for a connection to be established
+    pub connect_timeout: Duration,
+    /// Time to wait for the whole response after the request is sent,
+    /// streamed responses wait this time for the headers and for each part of the body instead
+    pub read_timeout: Duration,
+    /// How many times a failed idempotent request is repeated
+    pub retries: u32,
+    /// Delay before the first retry, it's doubled for each next retry
+    pub backoff: Duration,
+    /// Minimal interval between requests to the same host, `None` to disable the rate limiting
+    pub rate_limit: Option<Duration>,
+    pub user_agent: String,
+    /// URL of HTTP, HTTPS or SOCKS5 proxy, e.g. `socks5://127.0.0.1:9050`. \
+    /// Proxies of `HTTP_PROXY` and `HTTPS_PROXY` environment variables are used if it isn't set
+    pub proxy: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            rate_limit: Some(Duration::from_millis(200)),
+            user_agent: format!(
+                "{}/{} (+{})",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION"),
+                "https://github.com/Desiders/ani_cli_rs"
+            ),
+            proxy: None,
+        }
+    }
+}
+
+/// HTTP client with timeouts, retries and per-host rate limiting. \
+/// Clones share the connection pool and the rate limits
+#[derive(Clone)]
+pub struct Http {
+    client: Client,
+    read_timeout: Duration,
+    retries: u32,
+    backoff: Duration,
+    rate_limit: Option<Duration>,
+    /// Time when the next request to the host is allowed
+    next_request_at: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl Http {
+    /// Build the client
+    /// # Returns
+    /// An error if the proxy URL is invalid or the TLS backend can't be initialized
+    pub fn new(config: &HttpConfig) -> Result<Self, reqwest::Error> {
+        // The read timeout is set for each request, so streamed responses aren't cut off
+        let mut builder = Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .user_agent(&config.user_agent);
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+
+        Ok(Self {
+            client: builder.build()?,
+            read_timeout: config.read_timeout,
+            retries: config.retries,
+            backoff: config.backoff,
+            rate_limit: config.rate_limit,
+            next_request_at: Arc::default(),
+        })
+    }
+
+    /// Send a GET request and read the response body. \
+    /// The request is repeated with exponential backoff on timeouts, connection errors,
+    /// `429 Too Many Requests` and server errors
+    pub async fn get_text(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<String, reqwest::Error> {
+        self.with_retries(url, || async {
+            self.client
+                .get(url)
+                .query(query)
+                .timeout(self.read_timeout)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await
+        })
+        .await
+    }
+
+    /// Send a GET request without reading the response body,
+    /// so big resources like video segments can be streamed. \
+    /// Only the headers are waited for the read timeout,
+    /// read the body with [`Http::chunk`] to stop waiting for a stalled stream. \
+    /// The request is repeated like in [`Http::get_text`]
+    pub async fn get(&self, url: &str) -> Result<Response, SourceError> {
+        let result = self
+            .with_retries(url, || async {
+                match time::timeout(self.read_timeout, self.client.get(url).send()).await {
+                    Ok(response) => response
+                        .and_then(Response::error_for_status)
+                        .map_err(GetError::Request),
+                    Err(_) => Err(GetError::Timeout),
+                }
+            })
+            .await;
+
+        result.map_err(|err| match err {
+            GetError::Request(err) => err.into(),
+            GetError::Timeout => SourceError::ApiError(Message::Timeout(&host(url)).to_string()),
+        })
+    }
+
+    /// Send a POST request with the JSON body, e.g. to a webhook. \
+    /// The request isn't repeated because it may be not idempotent
+    pub async fn post_json(&self, url: &str, body: &Value) -> Result<Response, reqwest::Error> {
+        self.wait_for_turn(url).await;
+
+        self.client
+            .post(url)
+            .timeout(self.read_timeout)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await?
+            .error_for_status()
+    }
+
+    /// Create a request with the shared client, e.g. to add headers of an API, and send it with [`Http::send`]
+    pub fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    /// Send the request created by [`Http::request`] respecting the rate limit. \
+    /// The request isn't repeated because it may be not idempotent
+    /// # Returns
+    /// An error if the request fails or the response has an error status
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let request = request.timeout(self.read_timeout).build()?;
+        self.wait_for_turn(request.url().as_str()).await;
+
+        self.client.execute(request).await?.error_for_status()
+    }
+
+    /// Check the resource is available by requesting its first byte,
+    /// so big files like video segments aren't downloaded. \
+    /// The request is repeated like in [`Http::get_text`]
+    /// # Returns
+    /// Size of the resource in bytes if the server reports it
+    pub async fn probe(&self, url: &str) -> Result<Option<u64>, reqwest::Error> {
+        self.with_retries(url, || async {
+            let response = self
+                .client
+                .get(url)
+                .timeout(self.read_timeout)
+                .header(RANGE, "bytes=0-0")
+                .send()
+                .await?
+                .error_for_status()?;
+
+            // e.g. `Content-Range: bytes 0-0/1048576`, servers without ranges send the whole resource
+            let size = match response.headers().get(CONTENT_RANGE) {
+                Some(range) => range
+                    .to_str()
+                    .ok()
+                    .and_then(|range| range.rsplit_once('/'))
+                    .and_then(|(_, size)| size.parse().ok()),
+                None => response.content_length(),
+            };
+
+            Ok(size)
+        })
+        .await
+    }
+
+    /// Read the next chunk of the body of a response of [`Http::get`]
+    /// # Returns
+    /// * `Ok(None)` - If the body is read to the end
+    /// * `Err` - With [`io::ErrorKind::TimedOut`] if no data comes for the read timeout
+    pub async fn chunk(&self, response: &mut Response) -> io::Result<Option<Bytes>> {
+        match time::timeout(self.read_timeout, response.chunk()).await {
+            Ok(chunk) => chunk.map_err(io::Error::other),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                Message::Timeout(response.url().host_str().unwrap_or_default()).to_string(),
+            )),
+        }
+    }
+
+    async fn with_retries<F, Fut, T, E>(&self, url: &str, mut request: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Transient,
+    {
+        let mut attempt = 0;
+
+        loop {
+            self.wait_for_turn(url).await;
+
+            match request().await {
+                Err(err) if attempt < self.retries && err.is_transient() => {
+                    time::sleep(self.backoff * 2_u32.saturating_pow(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Wait until a request to the host of the URL is allowed by the rate limit
+    async fn wait_for_turn(&self, url: &str) {
+        let Some(interval) = self.rate_limit else {
+            return;
+        };
+        let host = host(url);
+
+        let turn = {
+            let mut next_request_at = self.next_request_at.lock().unwrap();
+            let now = Instant::now();
+            let turn = next_request_at.get(&host).map_or(now, |at| (*at).max(now));
+            next_request_at.insert(host, turn + interval);
+            turn
+        };
+
+        time::sleep_until(turn).await;
+    }
+}
+
+impl Default for Http {
+    fn default() -> Self {
+        Self::new(&HttpConfig::default()).expect("Default HTTP client can't be built")
+    }
+}
+
+/// Host of the URL, empty if the URL is invalid
+fn host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+/// Error of a request that may succeed if it's repeated
+trait Transient {
+    fn is_transient(&self) -> bool;
+}
+
+impl Transient for reqwest::Error {
+    fn is_transient(&self) -> bool {
+        self.is_timeout()
+            || self.is_connect()
+            || self.status().is_some_and(|status| {
+                status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            })
+    }
+}
+
+/// Error of [`Http::get`]
+enum GetError {
+    Request(reqwest::Error),
+    /// The headers didn't come for the read timeout
+    Timeout,
+}
+
+impl Transient for GetError {
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Request(err) => err.is_transient(),
+            Self::Timeout => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn stalled_headers_time_out_and_are_retried() {
+        // The server accepts connections, but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/video.ts", listener.local_addr().unwrap());
+        let accepted = Arc::new(Mutex::new(Vec::new()));
+        let server = tokio::spawn({
+            let accepted = Arc::clone(&accepted);
+            async move {
+                while let Ok((connection, _)) = listener.accept().await {
+                    accepted.lock().unwrap().push(connection);
+                }
+            }
+        });
+
+        let http = Http::new(&HttpConfig {
+            read_timeout: Duration::from_millis(100),
+            retries: 2,
+            backoff: Duration::from_millis(10),
+            rate_limit: None,
+            ..HttpConfig::default()
+        })
+        .unwrap();
+
+        let err = http.get(&url).await.unwrap_err();
+        assert_eq!(err.to_string(), Message::Timeout("127.0.0.1").to_string());
+        // The first attempt and 2 retries
+        assert_eq!(accepted.lock().unwrap().len(), 3);
+
+        server.abort();
+    }
+}
@@ -0,0 +1,197 @@
+use crate::storage::{data_path, read_json, write_json};
+
+use serde_json::{Map, Value};
+use std::{
+    future::Future,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Ordered list of interchangeable hosts, e.g. API mirrors of a source. \
+/// The last working host is tried first and is remembered between runs.
+/// Clones share the last working host
+#[derive(Clone)]
+pub struct Mirrors {
+    /// Key of the last working host in the state file, e.g. `anilibria/api`
+    name: String,
+    hosts: Vec<String>,
+    /// `None` to keep the last working host in memory
+    path: Option<PathBuf>,
+    working: Arc<Mutex<Option<String>>>,
+    /// Whether the last working host has worked in this run and not only in a previous one
+    checked: Arc<AtomicBool>,
+}
+
+impl Mirrors {
+    /// Create mirrors without the remembered host
+    /// # Arguments
+    /// * `name` - Key of the last working host in the state file
+    /// * `hosts` - Hosts in order of preference
+    /// * `path` - Path of the state file, `None` to keep the last working host in memory
+    #[must_use]
+    pub fn new(name: &str, hosts: Vec<String>, path: Option<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            hosts,
+            path,
+            working: Arc::default(),
+            checked: Arc::default(),
+        }
+    }
+
+    /// Create mirrors with the last working host remembered in the user data directory
+    #[must_use]
+    pub fn load(name: &str, hosts: Vec<String>) -> Self {
+        let mirrors = Self::new(name, hosts, data_path("mirrors.json"));
+
+        // The state is an optimization, so unreadable state is ignored
+        if let Some(path) = &mirrors.path {
+            if let Ok(Some(state)) = read_json(path) {
+                *mirrors.working.lock().unwrap() = state[name].as_str().map(ToString::to_string);
+            }
+        }
+
+        mirrors
+    }
+
+    /// Hosts in order they should be tried
+    /// # Arguments
+    /// * `preferred` - Host to try right after the last working host,
+    ///   e.g. the host returned by the API with the media path
+    #[must_use]
+    pub fn candidates(&self, preferred: Option<&str>) -> Vec<String> {
+        let working = self.working.lock().unwrap().clone();
+
+        let mut candidates: Vec<String> = Vec::new();
+        for host in working
+            .into_iter()
+            .filter(|host| self.hosts.contains(host) || Some(host.as_str()) == preferred)
+            .chain(preferred.map(ToString::to_string))
+            .chain(self.hosts.iter().cloned())
+        {
+            if !candidates.contains(&host) {
+                candidates.push(host);
+            }
+        }
+
+        candidates
+    }
+
+    /// Host that has worked in this run, so it can be used without trying it again
+    /// # Returns
+    /// `None` if no host has worked yet, even if a host is remembered by a previous run
+    #[must_use]
+    pub fn checked(&self) -> Option<String> {
+        if self.checked.load(Ordering::Relaxed) {
+            self.working.lock().unwrap().clone()
+        } else {
+            None
+        }
+    }
+
+    /// Call `request` with each host until it succeeds and remember the host
+    /// # Arguments
+    /// * `preferred` - See [`Mirrors::candidates`]
+    /// # Returns
+    /// The result of the first successful request or the error of the last one
+    /// # Panics
+    /// If there are no hosts
+    pub async fn try_each<F, Fut, T, E>(
+        &self,
+        preferred: Option<&str>,
+        mut request: F,
+    ) -> Result<T, E>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut last_err = None;
+
+        for host in self.candidates(preferred) {
+            match request(host.clone()).await {
+                Ok(value) => {
+                    self.remember(host);
+                    return Ok(value);
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.expect("No hosts to try"))
+    }
+
+    fn remember(&self, host: String) {
+        self.checked.store(true, Ordering::Relaxed);
+        {
+            let mut working = self.working.lock().unwrap();
+            if working.as_ref() == Some(&host) {
+                return;
+            }
+            *working = Some(host.clone());
+        }
+
+        let Some(path) = &self.path else {
+            return;
+        };
+        let mut state = match read_json(path) {
+            Ok(Some(Value::Object(state))) => state,
+            _ => Map::new(),
+        };
+        state.insert(self.name.clone(), Value::String(host));
+
+        // The state is an optimization, so failed writes aren't errors
+        let _ = write_json(path, &Value::Object(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosts() -> Vec<String> {
+        vec![String::from("a"), String::from("b"), String::from("c")]
+    }
+
+    #[tokio::test]
+    async fn failed_hosts_are_skipped_and_working_host_is_tried_first() {
+        let mirrors = Mirrors::new("test", hosts(), None);
+        assert_eq!(mirrors.checked(), None);
+
+        let tried = Mutex::new(Vec::new());
+        let result = mirrors
+            .try_each(None, |host| {
+                tried.lock().unwrap().push(host.clone());
+                async move {
+                    if host == "b" {
+                        Ok(host)
+                    } else {
+                        Err(host)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result, Ok(String::from("b")));
+        assert_eq!(*tried.lock().unwrap(), ["a", "b"]);
+        assert_eq!(mirrors.checked().as_deref(), Some("b"));
+        assert_eq!(mirrors.candidates(Some("c")), ["b", "c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn host_of_previous_run_is_not_checked() {
+        let mirrors = Mirrors::new("test", hosts(), None);
+        *mirrors.working.lock().unwrap() = Some(String::from("c"));
+
+        assert_eq!(mirrors.checked(), None);
+        assert_eq!(mirrors.candidates(None), ["c", "a", "b"]);
+
+        let result = mirrors
+            .try_each(None, |host| async move { Err::<(), _>(host) })
+            .await;
+        assert_eq!(result, Err(String::from("b")));
+        assert_eq!(mirrors.checked(), None);
+    }
+}
@@ -0,0 +1,56 @@
+mod process;
+mod source;
+
+pub use source::Plugin;
+
+use crate::storage::data_path;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Time to wait for a response of a plugin
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Get the directory of plugins in the user data directory,
+/// e.g. `~/.local/share/ani_cli_rs/plugins` on Linux
+#[must_use]
+pub fn default_dir() -> Option<PathBuf> {
+    data_path("plugins")
+}
+
+/// Find executables in the directory in order of their names
+/// # Returns
+/// An empty list if the directory doesn't exist
+pub fn find(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if is_executable(&entry.metadata()?) {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    metadata.is_file()
+}
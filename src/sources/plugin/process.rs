@@ -0,0 +1,202 @@
+use crate::{errors::SourceError, locale::Message};
+
+use serde_json::Value;
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    time,
+};
+
+/// Running plugin executable. \
+/// Requests and responses are JSON objects, one per line
+pub struct Process {
+    name: String,
+    path: PathBuf,
+    timeout: Duration,
+    // The process is killed when it's dropped
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    /// Whether the last request isn't answered, e.g. it timed out or was cancelled,
+    /// so a late response would be read as the response of the next request
+    unanswered: bool,
+}
+
+impl Process {
+    /// Start the executable, its stderr is discarded to not break the interface
+    pub fn spawn(path: &Path, timeout: Duration) -> Result<Self, SourceError> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (child, stdin, stdout) = start(path, &name)?;
+
+        Ok(Self {
+            name,
+            path: path.to_path_buf(),
+            timeout,
+            _child: child,
+            stdin,
+            stdout,
+            unanswered: false,
+        })
+    }
+
+    /// Name used in errors, the file name until the plugin declares its name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the name used in errors, e.g. the name declared by the plugin
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Send the request and wait for the response
+    /// # Returns
+    /// An error if the plugin doesn't respond in time, exits, sends invalid JSON
+    /// or responds with `{"error": "..."}`
+    pub async fn request(&mut self, request: &Value) -> Result<Value, SourceError> {
+        // Plugins keep no state, so the process is restarted instead of waiting for the late response
+        if self.unanswered {
+            let (child, stdin, stdout) = start(&self.path, &self.name)?;
+            self._child = child;
+            self.stdin = stdin;
+            self.stdout = stdout;
+        }
+
+        // Stays set if the request is cancelled
+        self.unanswered = true;
+        let result = time::timeout(self.timeout, self.exchange(request)).await;
+        match result {
+            Ok(result) => {
+                self.unanswered = false;
+                result
+            }
+            Err(_) => Err(SourceError::ApiError(
+                Message::Timeout(&self.name).to_string(),
+            )),
+        }
+    }
+
+    async fn exchange(&mut self, request: &Value) -> Result<Value, SourceError> {
+        let mut line = request.to_string();
+        line.push('\n');
+
+        let mut response = String::new();
+        let read = async {
+            self.stdin.write_all(line.as_bytes()).await?;
+            self.stdin.flush().await?;
+            self.stdout.read_line(&mut response).await
+        };
+        match read.await {
+            Ok(0) => {
+                return Err(SourceError::ApiError(
+                    Message::PluginError(&self.name, &Message::PluginExited).to_string(),
+                ))
+            }
+            Ok(_) => {}
+            Err(err) => {
+                return Err(SourceError::ApiError(
+                    Message::PluginError(&self.name, &err).to_string(),
+                ))
+            }
+        }
+
+        let response: Value = serde_json::from_str(&response).map_err(|err| {
+            SourceError::ParseError(Message::PluginError(&self.name, &err).to_string())
+        })?;
+
+        match response["error"].as_str() {
+            // Unknown anime, episodes and qualities are reported like by built-in sources
+            Some(error) if response["kind"] == "unknown" => {
+                Err(SourceError::UnknownVariant(error.to_string()))
+            }
+            Some(error) => Err(SourceError::ApiError(
+                Message::PluginError(&self.name, &error).to_string(),
+            )),
+            None => Ok(response),
+        }
+    }
+}
+
+/// Start the executable with piped stdin and stdout
+fn start(
+    path: &Path,
+    name: &str,
+) -> Result<(Child, ChildStdin, BufReader<ChildStdout>), SourceError> {
+    let mut child = Command::new(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| SourceError::ApiError(Message::PluginError(name, &err).to_string()))?;
+
+    let stdin = child.stdin.take().expect("stdin is piped");
+    let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+    Ok((child, stdin, stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    use serde_json::json;
+    use std::{fs, os::unix::fs::PermissionsExt as _};
+
+    /// Plugin that answers each request with `{"request": ...}`, requests with `slow` are answered late
+    fn slow_plugin(name: &str) -> PathBuf {
+        let path = temp_dir(name).join("slow_plugin");
+        fs::write(
+            &path,
+            "#!/bin/sh\n\
+             while read -r line; do\n\
+             case \"$line\" in *slow*) sleep 0.8 ;; esac\n\
+             echo \"{\\\"request\\\": $line}\"\n\
+             done\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn late_response_isnt_read_as_the_next_one() {
+        let path = slow_plugin("plugin_timeout");
+        let mut process = Process::spawn(&path, Duration::from_millis(500)).unwrap();
+
+        let err = process.request(&json!({ "method": "slow" })).await;
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            Message::Timeout("slow_plugin").to_string()
+        );
+
+        // The late response arrives while the next request is waited for
+        let response = process.request(&json!({ "method": "fast" })).await.unwrap();
+        assert_eq!(response, json!({ "request": { "method": "fast" } }));
+    }
+
+    #[tokio::test]
+    async fn cancelled_request_doesnt_break_the_next_one() {
+        let path = slow_plugin("plugin_cancel");
+        let mut process = Process::spawn(&path, Duration::from_secs(5)).unwrap();
+
+        let cancelled = time::timeout(
+            Duration::from_millis(100),
+            process.request(&json!({ "method": "slow" })),
+        )
+        .await;
+        assert!(cancelled.is_err());
+
+        let response = process.request(&json!({ "method": "fast" })).await.unwrap();
+        assert_eq!(response, json!({ "request": { "method": "fast" } }));
+    }
+}
@@ -0,0 +1,312 @@
+use super::process::Process;
+
+use crate::{
+    enums::language::Language,
+    errors::SourceError,
+    locale::Message,
+    sources::base::{find_in_anime_list, Source},
+};
+
+use serde_json::{json, Value};
+use std::{
+    fmt::{self, Display},
+    path::Path,
+    rc::Rc,
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+/// Anime found by the plugin
+#[derive(Clone)]
+struct Anime {
+    /// Identifier passed back to the plugin, the title if the plugin doesn't send it
+    id: String,
+    title: String,
+}
+
+/// Source implemented by an external executable, see `PLUGINS.md` for the protocol. \
+/// The plugin is stateless: the selected anime, episode and quality are kept here
+/// and passed in each request, so clones of the source share the process
+#[derive(Clone)]
+pub struct Plugin {
+    name: String,
+    language: Language,
+    process: Rc<Mutex<Process>>,
+
+    current_anime_list: Vec<Anime>,
+    current_anime: Option<Anime>,
+    current_episodes: Vec<String>,
+    /// Description of the episodes sent by the plugin
+    current_episodes_info: Option<String>,
+    current_episode: Option<String>,
+    current_qualities: Vec<String>,
+    current_quality: Option<String>,
+}
+
+impl Plugin {
+    /// Start the executable and ask it for its name and language
+    /// # Arguments
+    /// * `timeout` - Time to wait for each response of the plugin
+    pub async fn spawn(path: &Path, timeout: Duration) -> Result<Self, SourceError> {
+        let mut process = Process::spawn(path, timeout)?;
+
+        let response = process.request(&json!({ "method": "describe" })).await?;
+        let name = string(process.name(), &response, "name")?;
+        let language = match Language::try_from(string(&name, &response, "language")?.as_str()) {
+            Ok(Language::All) | Err(_) => {
+                return Err(SourceError::ParseError(
+                    Message::PluginError(&name, &Message::PluginLanguage).to_string(),
+                ))
+            }
+            Ok(language) => language,
+        };
+        process.set_name(&name);
+
+        Ok(Self {
+            name,
+            language,
+            process: Rc::new(Mutex::new(process)),
+            current_anime_list: Vec::new(),
+            current_anime: None,
+            current_episodes: Vec::new(),
+            current_episodes_info: None,
+            current_episode: None,
+            current_qualities: Vec::new(),
+            current_quality: None,
+        })
+    }
+
+    async fn request(&self, request: Value) -> Result<Value, SourceError> {
+        self.process.lock().await.request(&request).await
+    }
+
+    fn anime(&self) -> &Anime {
+        self.current_anime.as_ref().expect("No anime selected")
+    }
+
+    fn episode(&self) -> &str {
+        self.current_episode.as_ref().expect("No episode selected")
+    }
+}
+
+impl Display for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl PartialEq<String> for Plugin {
+    fn eq(&self, other: &String) -> bool {
+        self.name.to_lowercase() == other.to_lowercase()
+    }
+}
+
+impl Source for Plugin {
+    type SearchAnimeListInfo = String;
+    type AnimeInfo = String;
+    type EpisodesInfo = String;
+    type EpisodeIndo = String;
+    type QualitiesInfo = String;
+    type QualityInfo = String;
+
+    fn language(&self) -> &Language {
+        &self.language
+    }
+
+    async fn search_anime_list(
+        &mut self,
+        query: &str,
+    ) -> Result<Self::SearchAnimeListInfo, SourceError> {
+        let response = self
+            .request(json!({ "method": "search", "query": query }))
+            .await?;
+
+        self.current_anime_list = array(&self.name, &response, "anime")?
+            .iter()
+            .map(|anime| {
+                let title = anime["title"].as_str().or(anime.as_str())?.to_string();
+                let id = anime["id"]
+                    .as_str()
+                    .map_or_else(|| title.clone(), ToString::to_string);
+
+                Some(Anime { id, title })
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid_response(&self.name, "anime"))?;
+
+        Ok(self
+            .current_anime_list
+            .iter()
+            .enumerate()
+            .map(|(index, anime)| format!("\t{seq_num}. {}\n", anime.title, seq_num = index + 1))
+            .collect())
+    }
+
+    async fn select_anime_as_current(
+        &mut self,
+        title_or_seq_num: String,
+    ) -> Result<(), SourceError> {
+        let anime = find_in_anime_list(
+            &self.current_anime_list,
+            &title_or_seq_num,
+            |anime, name| anime.title.to_lowercase().contains(name),
+        )?
+        .clone();
+
+        let response = self
+            .request(json!({ "method": "episodes", "anime": anime.id }))
+            .await?;
+
+        self.current_episodes = strings(&response, "episodes")
+            .ok_or_else(|| invalid_response(&self.name, "episodes"))?;
+        self.current_episodes_info = response["info"].as_str().map(ToString::to_string);
+        self.current_anime = Some(anime);
+        self.current_episode = None;
+        self.current_quality = None;
+
+        Ok(())
+    }
+
+    fn anime_list(&self) -> Vec<String> {
+        self.current_anime_list
+            .iter()
+            .map(|anime| anime.title.clone())
+            .collect()
+    }
+
+    fn anime_info(&self) -> Result<Self::AnimeInfo, SourceError> {
+        Ok(self.anime().title.clone())
+    }
+
+    fn episodes_info(&mut self) -> Result<Self::EpisodesInfo, SourceError> {
+        Ok(self
+            .current_episodes_info
+            .clone()
+            .unwrap_or_else(|| format!("\n\t{}\n", self.current_episodes.join(", "))))
+    }
+
+    async fn select_episode_as_current(&mut self, episode: String) -> Result<(), SourceError> {
+        let episode = match episode.to_lowercase().as_str() {
+            "first" | "f" => self.current_episodes.first(),
+            "last" | "l" => self.current_episodes.last(),
+            name => self
+                .current_episodes
+                .iter()
+                .find(|known| known.to_lowercase() == name),
+        }
+        .cloned()
+        .ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownEpisodePattern(&episode).to_string())
+        })?;
+
+        let response = self
+            .request(json!({
+                "method": "qualities",
+                "anime": self.anime().id,
+                "episode": episode,
+            }))
+            .await?;
+
+        self.current_qualities = strings(&response, "qualities")
+            .ok_or_else(|| invalid_response(&self.name, "qualities"))?;
+        self.current_episode = Some(episode);
+        self.current_quality = None;
+
+        Ok(())
+    }
+
+    fn episodes(&self) -> Result<Vec<String>, SourceError> {
+        Ok(self.current_episodes.clone())
+    }
+
+    fn episode_info(&self) -> Result<Self::EpisodeIndo, SourceError> {
+        Ok(self.episode().to_string())
+    }
+
+    fn qualities_info(&mut self) -> Result<Self::QualitiesInfo, SourceError> {
+        Ok(self
+            .current_qualities
+            .iter()
+            .enumerate()
+            .map(|(index, quality)| format!("\t{seq_num} | {quality}\n", seq_num = index + 1))
+            .collect())
+    }
+
+    fn qualities(&self) -> Result<Vec<String>, SourceError> {
+        Ok(self.current_qualities.clone())
+    }
+
+    fn select_quality_as_current(&mut self, quality: String) -> Result<(), SourceError> {
+        let name = quality.to_lowercase();
+
+        let known = match name.as_str() {
+            "min" => self.current_qualities.first(),
+            "max" => self.current_qualities.last(),
+            _ => self
+                .current_qualities
+                .iter()
+                .find(|known| known.to_lowercase() == name)
+                .or_else(|| {
+                    name.parse::<usize>()
+                        .ok()
+                        .and_then(|seq_num| seq_num.checked_sub(1))
+                        .and_then(|index| self.current_qualities.get(index))
+                }),
+        };
+
+        self.current_quality = Some(known.cloned().ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownQuality(&quality).to_string())
+        })?);
+
+        Ok(())
+    }
+
+    fn quality_info(&self) -> Result<Self::QualityInfo, SourceError> {
+        Ok(self.current_quality.clone().expect("No quality selected"))
+    }
+
+    async fn url_for_stream(&self) -> Result<String, SourceError> {
+        let response = self
+            .request(json!({
+                "method": "stream",
+                "anime": self.anime().id,
+                "episode": self.episode(),
+                "quality": self.current_quality.as_ref().expect("No quality selected"),
+            }))
+            .await?;
+
+        string(&self.name, &response, "url")
+    }
+}
+
+fn string(plugin: &str, response: &Value, key: &str) -> Result<String, SourceError> {
+    response[key]
+        .as_str()
+        .map(ToString::to_string)
+        .ok_or_else(|| invalid_response(plugin, key))
+}
+
+fn array<'a>(plugin: &str, response: &'a Value, key: &str) -> Result<&'a Vec<Value>, SourceError> {
+    response[key]
+        .as_array()
+        .ok_or_else(|| invalid_response(plugin, key))
+}
+
+/// Get an array of strings, numbers are converted to strings, e.g. episodes `[1, 2]`
+fn strings(response: &Value, key: &str) -> Option<Vec<String>> {
+    response[key]
+        .as_array()?
+        .iter()
+        .map(|value| match value {
+            Value::String(value) => Some(value.clone()),
+            Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn invalid_response(plugin: &str, key: &str) -> SourceError {
+    SourceError::ParseError(
+        Message::PluginError(plugin, &Message::PluginInvalidField(key)).to_string(),
+    )
+}
@@ -0,0 +1,7 @@
+mod path;
+mod source;
+mod spec;
+
+pub use path::JsonPath;
+pub use source::Rest;
+pub use spec::{EpisodesSpec, RequestSpec, RestSpec, SearchSpec, StreamsSpec};
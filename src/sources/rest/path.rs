@@ -0,0 +1,220 @@
+use serde_json::Value;
+use std::fmt::{self, Display};
+
+/// Path to values of a JSON document, a subset of JSONPath. \
+/// Supported steps: `$` for the root, `.key`, `['key']`, `[0]` and `.*` or `[*]` for all items,
+/// e.g. `$.list[*].names.ru`. \
+/// The path is displayed as it's written in the config file
+#[derive(Clone, Debug)]
+pub struct JsonPath {
+    text: String,
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug)]
+enum Step {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+impl JsonPath {
+    /// # Returns
+    /// A description of the error if the path is invalid
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let Some(mut rest) = path.trim().strip_prefix('$') else {
+            return Err(format!("Path `{path}` must start with `$`"));
+        };
+
+        let mut steps = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after_dot) = rest.strip_prefix('.') {
+                let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+                let key = &after_dot[..end];
+                steps.push(match key {
+                    "" => return Err(format!("Path `{path}` has an empty key")),
+                    "*" => Step::Wildcard,
+                    key => Step::Key(key.to_string()),
+                });
+                rest = &after_dot[end..];
+            } else if let Some(after_bracket) = rest.strip_prefix('[') {
+                let Some(end) = after_bracket.find(']') else {
+                    return Err(format!("Path `{path}` has an unclosed `[`"));
+                };
+                let selector = after_bracket[..end].trim();
+                steps.push(if selector == "*" {
+                    Step::Wildcard
+                } else if let Ok(index) = selector.parse() {
+                    Step::Index(index)
+                } else {
+                    let key = selector
+                        .strip_prefix('\'')
+                        .and_then(|key| key.strip_suffix('\''))
+                        .or_else(|| {
+                            selector
+                                .strip_prefix('"')
+                                .and_then(|key| key.strip_suffix('"'))
+                        })
+                        .ok_or_else(|| {
+                            format!("Path `{path}` has an invalid selector `{selector}`")
+                        })?;
+                    Step::Key(key.to_string())
+                });
+                rest = &after_bracket[end + 1..];
+            } else {
+                return Err(format!("Path `{path}` has an unexpected `{rest}`"));
+            }
+        }
+
+        Ok(Self {
+            text: path.to_string(),
+            steps,
+        })
+    }
+
+    /// Get all values by the path, missing keys and indexes are skipped
+    #[must_use]
+    pub fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        let mut values = vec![value];
+
+        for step in &self.steps {
+            values = values
+                .into_iter()
+                .flat_map(|value| -> Vec<&Value> {
+                    match (step, value) {
+                        (Step::Key(key), Value::Object(object)) => {
+                            object.get(key).into_iter().collect()
+                        }
+                        (Step::Index(index), Value::Array(array)) => {
+                            array.get(*index).into_iter().collect()
+                        }
+                        (Step::Wildcard, Value::Array(array)) => array.iter().collect(),
+                        (Step::Wildcard, Value::Object(object)) => object.values().collect(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+
+        values
+    }
+
+    /// Get items by the path. \
+    /// If the path selects a single array, its items are returned,
+    /// so `$.list` and `$.list[*]` are the same
+    #[must_use]
+    pub fn items<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        match self.select(value).as_slice() {
+            [Value::Array(items)] => items.iter().collect(),
+            values => values.to_vec(),
+        }
+    }
+
+    /// Get the first string by the path, numbers are converted to strings
+    /// # Returns
+    /// `None` if there is no string or number by the path or the string is empty
+    #[must_use]
+    pub fn string(&self, value: &Value) -> Option<String> {
+        self.select(value)
+            .into_iter()
+            .find_map(|value| match value {
+                Value::String(value) if !value.is_empty() => Some(value.clone()),
+                Value::Number(value) => Some(value.to_string()),
+                _ => None,
+            })
+    }
+}
+
+impl Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn select(path: &str, value: &Value) -> Vec<Value> {
+        JsonPath::parse(path)
+            .unwrap()
+            .select(value)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn nested_keys_and_indexes_are_selected() {
+        let value = json!({
+            "list": [
+                { "names": { "ru": "Мадока", "en": "Madoka" }, "id": 1 },
+                { "names": { "ru": "Ева" }, "id": 2 },
+            ],
+            "odd key": "value",
+        });
+
+        assert_eq!(select("$", &value), vec![value.clone()]);
+        assert_eq!(select("$.list[0].names.en", &value), [json!("Madoka")]);
+        assert_eq!(select("$['list'][1][\"names\"].ru", &value), [json!("Ева")]);
+        assert_eq!(select("$['odd key']", &value), [json!("value")]);
+        // Missing keys and indexes select nothing
+        assert!(select("$.list[2].names", &value).is_empty());
+        assert!(select("$.list[1].names.en", &value).is_empty());
+        assert!(select("$.list.names", &value).is_empty());
+    }
+
+    #[test]
+    fn wildcards_select_all_items() {
+        let value = json!({
+            "list": [
+                { "names": { "ru": "Мадока", "en": "Madoka" } },
+                { "names": { "ru": "Ева" } },
+            ],
+        });
+
+        assert_eq!(
+            select("$.list[*].names.ru", &value),
+            [json!("Мадока"), json!("Ева")]
+        );
+        assert_eq!(select("$.list.*.names.en", &value), [json!("Madoka")]);
+        assert_eq!(select("$.list[0].names.*", &value).len(), 2);
+
+        let path = JsonPath::parse("$.list").unwrap();
+        assert_eq!(path.items(&value).len(), 2);
+        let path = JsonPath::parse("$.list[*].names").unwrap();
+        assert_eq!(path.items(&value).len(), 2);
+    }
+
+    #[test]
+    fn strings_are_taken_from_strings_and_numbers() {
+        let value = json!({ "id": 42, "empty": "", "title": "Madoka", "list": [null, "1"] });
+        let string = |path: &str| JsonPath::parse(path).unwrap().string(&value);
+
+        assert_eq!(string("$.id").as_deref(), Some("42"));
+        assert_eq!(string("$.title").as_deref(), Some("Madoka"));
+        assert_eq!(string("$.list[*]").as_deref(), Some("1"));
+        assert_eq!(string("$.empty"), None);
+        assert_eq!(string("$.missing"), None);
+    }
+
+    #[test]
+    fn invalid_paths_are_rejected() {
+        for path in [
+            "list",
+            "$.",
+            "$.list..id",
+            "$.list[0",
+            "$.list[key]",
+            "$.list['key\"]",
+            "$list",
+        ] {
+            assert!(JsonPath::parse(path).is_err(), "`{path}` is accepted");
+        }
+
+        let path = JsonPath::parse(" $.list[*] ").unwrap();
+        assert_eq!(path.to_string(), " $.list[*] ");
+    }
+}
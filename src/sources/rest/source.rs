@@ -0,0 +1,386 @@
+use super::{
+    path::JsonPath,
+    spec::{RequestSpec, RestSpec},
+};
+
+use crate::{
+    enums::language::Language,
+    errors::SourceError,
+    locale::Message,
+    sources::{
+        base::{find_in_anime_list, Source},
+        cache::Cache,
+        http::Http,
+    },
+};
+
+use reqwest::Url;
+use serde_json::Value;
+use std::{
+    fmt::{self, Display},
+    rc::Rc,
+    time::Duration,
+};
+
+/// Search results change only when new anime are added
+const SEARCH_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone)]
+struct Anime {
+    /// Identifier passed to the endpoint of episodes, the title if the API doesn't send it
+    id: String,
+    title: String,
+}
+
+#[derive(Clone)]
+struct Episode {
+    number: String,
+    /// Item of the episode in the response, streams are taken from it
+    /// if the source doesn't define a request of streams
+    item: Value,
+}
+
+#[derive(Clone)]
+struct Stream {
+    quality: String,
+    url: String,
+}
+
+/// Source defined in the config file, see [`RestSpec`]. \
+/// Clones of the source share the definition
+#[derive(Clone)]
+pub struct Rest {
+    spec: Rc<RestSpec>,
+    http: Http,
+    cache: Cache,
+
+    current_anime_list: Vec<Anime>,
+    current_anime: Option<Anime>,
+    current_episodes: Vec<Episode>,
+    /// URL of the request of episodes, relative stream URLs are resolved against it
+    current_episodes_url: Option<String>,
+    current_episode: Option<Episode>,
+    current_streams: Vec<Stream>,
+    current_stream: Option<Stream>,
+}
+
+impl Rest {
+    #[must_use]
+    pub fn new(spec: RestSpec, http: Http) -> Self {
+        Self {
+            spec: Rc::new(spec),
+            http,
+            cache: Cache::default(),
+            current_anime_list: Vec::new(),
+            current_anime: None,
+            current_episodes: Vec::new(),
+            current_episodes_url: None,
+            current_episode: None,
+            current_streams: Vec::new(),
+            current_stream: None,
+        }
+    }
+
+    /// Set the cache of API responses, the cache is disabled by default
+    #[must_use]
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Send the request and parse the response
+    /// # Arguments
+    /// * `cache_ttl` - TTL of the response, `None` if the response isn't cached
+    /// # Returns
+    /// The response and the URL it was requested from
+    async fn request(
+        &self,
+        request: &RequestSpec,
+        values: &[(&str, &str)],
+        cache_ttl: Option<(&str, Duration)>,
+    ) -> Result<(Value, String), SourceError> {
+        let (url, params) = request.build(values);
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        let body = match cache_ttl {
+            Some((endpoint, ttl)) => {
+                let endpoint = format!("{}/{endpoint}", self.spec.name.to_lowercase());
+                let key: Vec<(&str, &str)> = [("url", url.as_str())]
+                    .into_iter()
+                    .chain(params.iter().copied())
+                    .collect();
+
+                self.cache
+                    .fetch(&endpoint, &key, ttl, || self.http.get_text(&url, &params))
+                    .await?
+            }
+            None => self.http.get_text(&url, &params).await?,
+        };
+
+        Ok((serde_json::from_str(&body)?, url))
+    }
+
+    fn string(&self, item: &Value, path: &JsonPath) -> Result<String, SourceError> {
+        path.string(item).ok_or_else(|| {
+            SourceError::ParseError(Message::SourceInvalidField(&self.spec.name, &path).to_string())
+        })
+    }
+
+    fn anime(&self) -> &Anime {
+        self.current_anime.as_ref().expect("No anime selected")
+    }
+
+    fn episode(&self) -> &Episode {
+        self.current_episode.as_ref().expect("No episode selected")
+    }
+}
+
+impl Display for Rest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec.name)
+    }
+}
+
+impl PartialEq<String> for Rest {
+    fn eq(&self, other: &String) -> bool {
+        self.spec.name.to_lowercase() == other.to_lowercase()
+    }
+}
+
+impl Source for Rest {
+    type SearchAnimeListInfo = String;
+    type AnimeInfo = String;
+    type EpisodesInfo = String;
+    type EpisodeIndo = String;
+    type QualitiesInfo = String;
+    type QualityInfo = String;
+
+    fn language(&self) -> &Language {
+        &self.spec.language
+    }
+
+    async fn search_anime_list(
+        &mut self,
+        query: &str,
+    ) -> Result<Self::SearchAnimeListInfo, SourceError> {
+        let search = &self.spec.search;
+        let (response, _) = self
+            .request(
+                &search.request,
+                &[("query", query)],
+                Some(("search", SEARCH_TTL)),
+            )
+            .await?;
+
+        self.current_anime_list = search
+            .items
+            .items(&response)
+            .into_iter()
+            .map(|item| {
+                let title = self.string(item, &search.title)?;
+                let id = match &search.id {
+                    Some(id) => self.string(item, id)?,
+                    None => title.clone(),
+                };
+
+                Ok(Anime { id, title })
+            })
+            .collect::<Result<_, SourceError>>()?;
+
+        Ok(self
+            .current_anime_list
+            .iter()
+            .enumerate()
+            .map(|(index, anime)| format!("\t{seq_num}. {}\n", anime.title, seq_num = index + 1))
+            .collect())
+    }
+
+    async fn select_anime_as_current(
+        &mut self,
+        title_or_seq_num: String,
+    ) -> Result<(), SourceError> {
+        let anime = find_in_anime_list(
+            &self.current_anime_list,
+            &title_or_seq_num,
+            |anime, name| anime.title.to_lowercase().contains(name),
+        )?
+        .clone();
+
+        let episodes = &self.spec.episodes;
+        let (response, url) = self
+            .request(&episodes.request, &[("anime", &anime.id)], None)
+            .await?;
+
+        self.current_episodes = episodes
+            .items
+            .items(&response)
+            .into_iter()
+            .map(|item| {
+                Ok(Episode {
+                    number: self.string(item, &episodes.number)?,
+                    item: item.clone(),
+                })
+            })
+            .collect::<Result<_, SourceError>>()?;
+        self.current_episodes_url = Some(url);
+        self.current_anime = Some(anime);
+        self.current_episode = None;
+        self.current_stream = None;
+
+        Ok(())
+    }
+
+    fn anime_list(&self) -> Vec<String> {
+        self.current_anime_list
+            .iter()
+            .map(|anime| anime.title.clone())
+            .collect()
+    }
+
+    fn anime_info(&self) -> Result<Self::AnimeInfo, SourceError> {
+        Ok(self.anime().title.clone())
+    }
+
+    fn episodes_info(&mut self) -> Result<Self::EpisodesInfo, SourceError> {
+        Ok(format!("\n\t{}\n", self.episodes()?.join(", ")))
+    }
+
+    async fn select_episode_as_current(&mut self, episode: String) -> Result<(), SourceError> {
+        let episode = match episode.to_lowercase().as_str() {
+            "first" | "f" => self.current_episodes.first(),
+            "last" | "l" => self.current_episodes.last(),
+            number => self
+                .current_episodes
+                .iter()
+                .find(|known| known.number.to_lowercase() == number),
+        }
+        .cloned()
+        .ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownEpisodePattern(&episode).to_string())
+        })?;
+
+        let streams = &self.spec.streams;
+        let (response, url) = match &streams.request {
+            Some(request) => {
+                self.request(
+                    request,
+                    &[("anime", &self.anime().id), ("episode", &episode.number)],
+                    None,
+                )
+                .await?
+            }
+            None => (
+                episode.item.clone(),
+                self.current_episodes_url.clone().unwrap_or_default(),
+            ),
+        };
+        let base_url = streams.base_url.clone().or_else(|| Url::parse(&url).ok());
+
+        let mut current_streams = Vec::new();
+        for item in streams.items.items(&response) {
+            // Missing qualities are usually `null`
+            let Some(stream_url) = streams.stream_url.string(item) else {
+                continue;
+            };
+
+            current_streams.push(Stream {
+                quality: self.string(item, &streams.quality)?,
+                url: match base_url
+                    .as_ref()
+                    .and_then(|base| base.join(&stream_url).ok())
+                {
+                    Some(url) => url.to_string(),
+                    None => stream_url,
+                },
+            });
+        }
+        if current_streams.is_empty() {
+            return Err(SourceError::ParseError(
+                Message::SourceInvalidField(&self.spec.name, &streams.stream_url).to_string(),
+            ));
+        }
+
+        self.current_streams = current_streams;
+        self.current_episode = Some(episode);
+        self.current_stream = None;
+
+        Ok(())
+    }
+
+    fn episodes(&self) -> Result<Vec<String>, SourceError> {
+        Ok(self
+            .current_episodes
+            .iter()
+            .map(|episode| episode.number.clone())
+            .collect())
+    }
+
+    fn episode_info(&self) -> Result<Self::EpisodeIndo, SourceError> {
+        Ok(self.episode().number.clone())
+    }
+
+    fn qualities_info(&mut self) -> Result<Self::QualitiesInfo, SourceError> {
+        Ok(self
+            .current_streams
+            .iter()
+            .enumerate()
+            .map(|(index, stream)| {
+                format!("\t{seq_num} | {}\n", stream.quality, seq_num = index + 1)
+            })
+            .collect())
+    }
+
+    fn qualities(&self) -> Result<Vec<String>, SourceError> {
+        Ok(self
+            .current_streams
+            .iter()
+            .map(|stream| stream.quality.clone())
+            .collect())
+    }
+
+    fn select_quality_as_current(&mut self, quality: String) -> Result<(), SourceError> {
+        let name = quality.to_lowercase();
+
+        let known = match name.as_str() {
+            "min" => self.current_streams.first(),
+            "max" => self.current_streams.last(),
+            _ => self
+                .current_streams
+                .iter()
+                .find(|known| known.quality.to_lowercase() == name)
+                .or_else(|| {
+                    name.parse::<usize>()
+                        .ok()
+                        .and_then(|seq_num| seq_num.checked_sub(1))
+                        .and_then(|index| self.current_streams.get(index))
+                }),
+        };
+
+        self.current_stream = Some(known.cloned().ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownQuality(&quality).to_string())
+        })?);
+
+        Ok(())
+    }
+
+    fn quality_info(&self) -> Result<Self::QualityInfo, SourceError> {
+        Ok(self
+            .current_stream
+            .as_ref()
+            .expect("No quality selected")
+            .quality
+            .clone())
+    }
+
+    async fn url_for_stream(&self) -> Result<String, SourceError> {
+        Ok(self
+            .current_stream
+            .as_ref()
+            .expect("No quality selected")
+            .url
+            .clone())
+    }
+}
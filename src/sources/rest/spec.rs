@@ -0,0 +1,147 @@
+use super::path::JsonPath;
+
+use crate::enums::language::Language;
+
+use reqwest::Url;
+
+/// Definition of a source backed by a JSON API, read from the `sources` key of the config file
+#[derive(Clone)]
+pub struct RestSpec {
+    pub name: String,
+    pub language: Language,
+    pub search: SearchSpec,
+    pub episodes: EpisodesSpec,
+    pub streams: StreamsSpec,
+}
+
+/// Request to an endpoint. \
+/// The URL and values of the parameters may contain placeholders
+/// `{query}`, `{anime}` and `{episode}` replaced by the search query,
+/// the identifier of the selected anime and the number of the selected episode
+#[derive(Clone)]
+pub struct RequestSpec {
+    pub url: String,
+    /// Query parameters, encoded by the HTTP client
+    pub params: Vec<(String, String)>,
+}
+
+/// How anime are found
+#[derive(Clone)]
+pub struct SearchSpec {
+    pub request: RequestSpec,
+    /// Path to found anime in the response
+    pub items: JsonPath,
+    /// Path to the identifier of an anime in an item, the title is used if it's missing
+    pub id: Option<JsonPath>,
+    /// Path to the title of an anime in an item
+    pub title: JsonPath,
+}
+
+/// How episodes of the selected anime are listed
+#[derive(Clone)]
+pub struct EpisodesSpec {
+    pub request: RequestSpec,
+    /// Path to episodes in the response
+    pub items: JsonPath,
+    /// Path to the number of an episode in an item
+    pub number: JsonPath,
+}
+
+/// How streams of the selected episode are listed
+#[derive(Clone)]
+pub struct StreamsSpec {
+    /// `None` if streams are taken from the item of the episode
+    pub request: Option<RequestSpec>,
+    /// Path to streams in the response or in the item of the episode
+    pub items: JsonPath,
+    /// Path to the name of the quality of a stream in an item
+    pub quality: JsonPath,
+    /// Path to the URL of a stream in an item
+    pub stream_url: JsonPath,
+    /// URL relative stream URLs are resolved against,
+    /// the URL of the request of streams or episodes if it's missing
+    pub base_url: Option<Url>,
+}
+
+impl RequestSpec {
+    /// Replace placeholders in the URL and the parameters
+    /// # Arguments
+    /// * `values` - Values of placeholders, e.g. `[("anime", "42")]`
+    /// # Returns
+    /// The URL with percent-encoded values and the parameters
+    #[must_use]
+    pub fn build(&self, values: &[(&str, &str)]) -> (String, Vec<(String, String)>) {
+        let mut url = self.url.clone();
+        let mut params = self.params.clone();
+        for (name, value) in values {
+            let placeholder = format!("{{{name}}}");
+            url = url.replace(&placeholder, &encode(value));
+            for (_, param) in &mut params {
+                *param = param.replace(&placeholder, value);
+            }
+        }
+
+        (url, params)
+    }
+}
+
+/// Percent-encode everything but unreserved characters, so a value is a single path segment
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_replaced() {
+        let request = RequestSpec {
+            url: String::from("https://example.com/anime/{anime}/episodes/{episode}"),
+            params: vec![
+                (String::from("q"), String::from("{query}")),
+                (String::from("page"), String::from("1")),
+            ],
+        };
+
+        let (url, params) = request.build(&[
+            ("query", "madoka magica"),
+            ("anime", "42"),
+            ("episode", "1"),
+        ]);
+        assert_eq!(url, "https://example.com/anime/42/episodes/1");
+        // Parameters are encoded by the HTTP client
+        assert_eq!(
+            params,
+            [
+                (String::from("q"), String::from("madoka magica")),
+                (String::from("page"), String::from("1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn values_in_the_url_are_single_path_segments() {
+        let request = RequestSpec {
+            url: String::from("https://example.com/search/{query}"),
+            params: Vec::new(),
+        };
+
+        let (url, _) = request.build(&[("query", "Madoka/Magica ?&#% ★ a-b.c_d~")]);
+        assert_eq!(
+            url,
+            "https://example.com/search/Madoka%2FMagica%20%3F%26%23%25%20%E2%98%85%20a-b.c_d~"
+        );
+        // Unknown placeholders are kept
+        let (url, _) = request.build(&[("anime", "42")]);
+        assert_eq!(url, "https://example.com/search/{query}");
+    }
+}
@@ -1,6 +1,6 @@
 mod api;
 mod parser;
-mod schemas;
+pub mod schemas;
 mod source;
 
 pub use source::Anilibria;
@@ -1,19 +1,97 @@
 use super::source::Anilibria;
 
-use reqwest;
+use reqwest::{self, Method};
+use std::time::Duration;
+
+/// Search results include the episodes of each anime, which are watched for new episodes,
+/// so they stay fresh only for a few minutes
+const SEARCH_TTL: Duration = Duration::from_secs(5 * 60);
+/// Login form of the site, sessions of accounts are created by it and not by the API
+const LOGIN_URL: &str = "https://www.anilibria.tv/public/login.php";
 
 pub trait Api {
-    fn search_anime(&self, query: &str) -> Result<String, reqwest::Error>;
+    async fn search_anime(&self, query: &str) -> Result<String, reqwest::Error>;
+
+    /// Log in by the email or the login of the account
+    /// # Returns
+    /// The response with the session
+    async fn login(&self, login: &str, password: &str) -> Result<String, reqwest::Error>;
+
+    /// Get favorite anime of the account of the session
+    async fn favorites(&self, session: &str) -> Result<String, reqwest::Error>;
+
+    async fn add_favorite(&self, session: &str, anime_id: u64) -> Result<String, reqwest::Error>;
+
+    async fn remove_favorite(&self, session: &str, anime_id: u64)
+        -> Result<String, reqwest::Error>;
 }
 
 impl Api for Anilibria<'_> {
-    fn search_anime(&self, query: &str) -> Result<String, reqwest::Error> {
-        let url = format!("{}/searchTitles", self.api_url());
-
-        self.client()
-            .get(&url)
-            .query(&[("search", query), ("limit", "30")])
-            .send()?
-            .text()
+    async fn search_anime(&self, query: &str) -> Result<String, reqwest::Error> {
+        let params = [("search", query), ("limit", "30")];
+
+        self.cache()
+            .fetch("anilibria/searchTitles", &params, SEARCH_TTL, || {
+                self.api_urls().try_each(None, |api_url| async move {
+                    let url = format!("{api_url}/searchTitles");
+                    self.http().get_text(&url, &params).await
+                })
+            })
+            .await
     }
+
+    async fn login(&self, login: &str, password: &str) -> Result<String, reqwest::Error> {
+        let request = self
+            .http()
+            .request(Method::POST, LOGIN_URL)
+            .form(&[("mail", login), ("passw", password)]);
+
+        self.http().send(request).await?.text().await
+    }
+
+    async fn favorites(&self, session: &str) -> Result<String, reqwest::Error> {
+        // Favorites are changed by the user, so they aren't cached
+        let params = [("session", session), ("limit", "100")];
+
+        self.api_urls()
+            .try_each(None, |api_url| async move {
+                let url = format!("{api_url}/getFavorites");
+                self.http().get_text(&url, &params).await
+            })
+            .await
+    }
+
+    async fn add_favorite(&self, session: &str, anime_id: u64) -> Result<String, reqwest::Error> {
+        edit_favorites(self, Method::PUT, "addFavorite", session, anime_id).await
+    }
+
+    async fn remove_favorite(
+        &self,
+        session: &str,
+        anime_id: u64,
+    ) -> Result<String, reqwest::Error> {
+        edit_favorites(self, Method::DELETE, "delFavorite", session, anime_id).await
+    }
+}
+
+async fn edit_favorites(
+    source: &Anilibria<'_>,
+    method: Method,
+    endpoint: &str,
+    session: &str,
+    anime_id: u64,
+) -> Result<String, reqwest::Error> {
+    let anime_id = anime_id.to_string();
+    let params = [("session", session), ("title_id", anime_id.as_str())];
+
+    source
+        .api_urls()
+        .try_each(None, |api_url| {
+            let request = source
+                .http()
+                .request(method.clone(), &format!("{api_url}/{endpoint}"))
+                .query(&params);
+            async move { source.http().send(request).await?.text().await }
+        })
+        .await
 }
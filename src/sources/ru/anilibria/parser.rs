@@ -7,56 +7,146 @@ use serde_json;
 
 pub trait Parser {
     fn search_anime(&self, query: &str) -> Result<Vec<Anime>, serde_json::Error>;
+
+    /// Parse favorites, a list of anime like search results or a page of them in `list`
+    fn favorites(&self, response: &str) -> Result<Vec<Anime>, serde_json::Error>;
+
+    /// Parse the session of the login response, `None` if the login failed
+    fn session(&self, response: &str) -> Result<Option<String>, serde_json::Error>;
+
+    /// Get the message of the failed request, e.g. of `{ "error": { "message": "..." } }`,
+    /// `None` if the request succeeded
+    fn error(&self, response: &str) -> Option<String>;
 }
 
 impl Parser for Anilibria<'_> {
     fn search_anime(&self, query: &str) -> Result<Vec<Anime>, serde_json::Error> {
         Ok(serde_json::from_str::<Vec<serde_json::Value>>(query)?
             .iter()
-            .map(|value| Anime {
-                announce: value["announce"].as_str().map(ToString::to_string),
-                names: {
-                    let names = value["names"].as_object().unwrap();
-
-                    Names {
-                        ru: names["ru"].as_str().unwrap().to_string(),
-                        en: names["en"].as_str().unwrap().to_string(),
-                    }
-                },
-                player: Player {
-                    host: value["player"]["host"].as_str().unwrap().to_string(),
-                    series: {
-                        let series = value["player"]["series"].as_object().unwrap();
-
-                        Series {
-                            first: series["first"].as_u64().unwrap().try_into().unwrap(),
-                            last: series["last"].as_u64().unwrap().try_into().unwrap(),
-                            string: series["string"].as_str().unwrap().to_string(),
-                        }
-                    },
-                    playlist: {
-                        let player = value["player"].as_object().unwrap();
-                        let host = player["host"].as_str().unwrap();
-
-                        player["playlist"]
-                            .as_object()
-                            .unwrap()
-                            .iter()
-                            .map(|(k, v)| {
-                                (k.to_string(), {
-                                    let hls = v["hls"].as_object().unwrap();
-                                    SerieInfo {
-                                        serie: v["serie"].as_u64().unwrap().try_into().unwrap(),
-                                        fhd: hls["fhd"].as_str().map(|s| host.to_string() + s),
-                                        hd: hls["hd"].as_str().map(|s| host.to_string() + s),
-                                        sd: hls["sd"].as_str().map(|s| host.to_string() + s),
-                                    }
-                                })
-                            })
-                            .collect()
-                    },
-                },
-            })
+            .map(anime)
             .collect())
     }
+
+    fn favorites(&self, response: &str) -> Result<Vec<Anime>, serde_json::Error> {
+        let value = serde_json::from_str::<serde_json::Value>(response)?;
+        let list = match &value["list"] {
+            serde_json::Value::Array(list) => list,
+            _ => value.as_array().map(Vec::as_slice).unwrap_or_default(),
+        };
+
+        Ok(list.iter().map(anime).collect())
+    }
+
+    fn session(&self, response: &str) -> Result<Option<String>, serde_json::Error> {
+        let value = serde_json::from_str::<serde_json::Value>(response)?;
+
+        Ok(value["sessionId"]
+            .as_str()
+            .filter(|session| value["key"] == "success" && !session.is_empty())
+            .map(ToString::to_string))
+    }
+
+    fn error(&self, response: &str) -> Option<String> {
+        let value = serde_json::from_str::<serde_json::Value>(response).ok()?;
+
+        // The API reports errors in `error`, the login in `key` and `mes`
+        if let Some(error) = value.get("error").filter(|error| !error.is_null()) {
+            return Some(
+                error["message"]
+                    .as_str()
+                    .map_or_else(|| error.to_string(), ToString::to_string),
+            );
+        }
+        match value["key"].as_str() {
+            Some(key) if key != "success" => Some(value["mes"].as_str().unwrap_or(key).to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Parse an anime of the API
+fn anime(value: &serde_json::Value) -> Anime {
+    Anime {
+        id: value["id"].as_u64().unwrap_or_default(),
+        announce: value["announce"].as_str().map(ToString::to_string),
+        names: {
+            let names = value["names"].as_object().unwrap();
+
+            Names {
+                ru: names["ru"].as_str().unwrap().to_string(),
+                en: names["en"].as_str().unwrap().to_string(),
+            }
+        },
+        description: value["description"]
+            .as_str()
+            .filter(|description| !description.is_empty())
+            .map(ToString::to_string),
+        genres: strings(&value["genres"]),
+        year: value["season"]["year"]
+            .as_u64()
+            .and_then(|year| year.try_into().ok()),
+        poster: value["posters"]["original"]["url"]
+            .as_str()
+            .map(ToString::to_string),
+        voice: strings(&value["team"]["voice"]),
+        player: Player {
+            host: value["player"]["host"].as_str().unwrap().to_string(),
+            series: {
+                let series = value["player"]["series"].as_object().unwrap();
+
+                Series {
+                    first: series["first"].as_u64().unwrap().try_into().unwrap(),
+                    last: series["last"].as_u64().unwrap().try_into().unwrap(),
+                    string: series["string"].as_str().unwrap().to_string(),
+                }
+            },
+            playlist: {
+                let player = value["player"].as_object().unwrap();
+
+                player["playlist"]
+                    .as_object()
+                    .unwrap()
+                    .iter()
+                    .map(|(k, v)| {
+                        (k.to_string(), {
+                            let hls = v["hls"].as_object().unwrap();
+                            SerieInfo {
+                                serie: v["serie"].as_u64().unwrap().try_into().unwrap(),
+                                name: v["name"]
+                                    .as_str()
+                                    .filter(|name| !name.is_empty())
+                                    .map(ToString::to_string),
+                                fhd: hls["fhd"].as_str().map(ToString::to_string),
+                                hd: hls["hd"].as_str().map(ToString::to_string),
+                                sd: hls["sd"].as_str().map(ToString::to_string),
+                                opening: skip(&v["skips"]["opening"]),
+                                ending: skip(&v["skips"]["ending"]),
+                            }
+                        })
+                    })
+                    .collect()
+            },
+        },
+    }
+}
+
+/// Parse a skip marker, e.g. `[0, 90]`, an empty array if the marker is unknown
+fn skip(value: &serde_json::Value) -> Option<(u64, u64)> {
+    match value.as_array()?.as_slice() {
+        [start, end] => Some((start.as_u64()?, end.as_u64()?)).filter(|(start, end)| start < end),
+        _ => None,
+    }
+}
+
+/// Parse an array of strings, other values are skipped
+fn strings(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(ToString::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
 }
@@ -4,8 +4,14 @@ use std::fmt::{self, Display};
 
 #[derive(Clone)]
 pub struct Anime {
+    pub id: u64,                  // 1
     pub announce: Option<String>, // "Серии выходят каждое воскресенье"
     pub names: Names,
+    pub description: Option<String>,
+    pub genres: Vec<String>,    // ["Драма", "Махо-сёдзё"]
+    pub year: Option<u16>,      // 2011
+    pub poster: Option<String>, // "/storage/releases/posters/1/poster.jpg"
+    pub voice: Vec<String>,     // ["Amikiri", "Hekomi"]
     pub player: Player,
 }
 
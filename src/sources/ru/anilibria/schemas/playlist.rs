@@ -4,10 +4,15 @@ pub type Serie = String; // "1", "2"
 
 #[derive(Clone)]
 pub struct SerieInfo {
-    pub serie: u16, // 1, 2
+    pub serie: u16,           // 1, 2
+    pub name: Option<String>, // "Как будто во сне"
+    // Paths of HLS playlists on the media host, e.g. "/videos/media/ts/9000/1/1080/7f3c.m3u8"
     pub fhd: Option<String>,
     pub hd: Option<String>,
     pub sd: Option<String>,
+    // Start and end of the opening and ending in seconds, e.g. (0, 90)
+    pub opening: Option<(u64, u64)>,
+    pub ending: Option<(u64, u64)>,
 }
 
 impl Display for SerieInfo {
@@ -1,58 +1,168 @@
 use super::{api::Api, parser::Parser, schemas::Anime};
-use crate::{enums::language::Language, errors::SourceError, sources::base::Source};
+use crate::{
+    enums::language::Language,
+    errors::SourceError,
+    locale::Message,
+    sources::{
+        base::{find_in_anime_list, Source},
+        cache::Cache,
+        details::{AnimeDetails, EpisodeDetails},
+        http::Http,
+        mirrors::Mirrors,
+    },
+    storage::accounts::Accounts,
+};
 
-use reqwest;
+use reqwest::Method;
+use serde_json::json;
 use std::{
+    cell::RefCell,
     fmt::{self, Display},
+    io,
     rc::Rc,
+    time::Duration,
 };
 
+/// API mirrors in order of preference
+const API_URLS: [&str; 2] = ["https://api.anilibria.tv/v2", "https://api.wwnd.space/v2"];
+/// Site that serves posters by the paths returned by the API
+const SITE_URL: &str = "https://anilibria.tv";
+/// Media hosts that are tried if the host returned by the API is unavailable
+const MEDIA_HOSTS: [&str; 3] = ["static.libria.fun", "de6.libria.fun", "cache.libria.fun"];
+/// Name of the source in the accounts
+const ACCOUNT: &str = "anilibria";
+
 #[derive(Clone)]
 pub struct Anilibria<'a> {
     name: &'a str,
     language: Language,
-    api_url: &'a str,
-    client: reqwest::blocking::Client,
+    api_urls: Mirrors,
+    media_hosts: Mirrors,
+    http: Http,
+    cache: Cache,
+    /// The session of the logged in user is kept by [`ACCOUNT`]
+    accounts: Rc<RefCell<Accounts>>,
 
     current_anime_list: Vec<Rc<Anime>>,
     current_anime: Option<Rc<Anime>>,
     current_episode: Option<u16>,
+    current_quality: Option<String>,
     current_hls: Option<String>,
 }
 
 impl<'a> Anilibria<'a> {
     #[must_use]
-    pub fn new(client_builder: Option<reqwest::blocking::ClientBuilder>) -> Self {
+    pub fn new(http: Http) -> Self {
         Self {
             name: "Anilibria",
             language: Language::Russian,
-            api_url: "https://api.anilibria.tv/v2",
-            client: match client_builder {
-                Some(builder) => builder.build().unwrap(),
-                None => reqwest::blocking::Client::new(),
-            },
+            api_urls: Mirrors::load(
+                "anilibria/api",
+                API_URLS.into_iter().map(ToString::to_string).collect(),
+            ),
+            media_hosts: Mirrors::load(
+                "anilibria/media",
+                MEDIA_HOSTS.into_iter().map(ToString::to_string).collect(),
+            ),
+            http,
+            cache: Cache::default(),
+            accounts: Rc::new(RefCell::new(Accounts::new(None))),
             current_anime_list: Vec::new(),
             current_anime: None,
             current_episode: None,
+            current_quality: None,
             current_hls: None,
         }
     }
 
+    /// Set the API mirrors, the mirrors of [`API_URLS`] are used by default
     #[must_use]
-    pub fn api_url(&self) -> &str {
-        self.api_url
+    pub fn with_api_urls(mut self, api_urls: Mirrors) -> Self {
+        self.api_urls = api_urls;
+        self
     }
 
+    /// API mirrors, e.g. `https://api.anilibria.tv/v2`
     #[must_use]
-    pub fn client(&self) -> &reqwest::blocking::Client {
-        &self.client
+    pub fn api_urls(&self) -> &Mirrors {
+        &self.api_urls
+    }
+
+    #[must_use]
+    pub fn http(&self) -> &Http {
+        &self.http
+    }
+
+    /// Set the cache of API responses, the cache is disabled by default
+    #[must_use]
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    #[must_use]
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Set the accounts where the session of the user is kept between runs,
+    /// the session is kept in memory by default
+    #[must_use]
+    pub fn with_accounts(mut self, accounts: Rc<RefCell<Accounts>>) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    /// Check the user is logged in, the session may be expired
+    #[must_use]
+    pub fn is_logged_in(&self) -> bool {
+        self.session().is_some()
+    }
+
+    /// Log in to the account on the site and keep the session in the accounts
+    /// # Arguments
+    /// * `login` - Email or login of the account
+    pub async fn login(&self, login: &str, password: &str) -> Result<(), SourceError> {
+        let response = <Anilibria as Api>::login(self, login, password).await?;
+
+        let Some(session) = <Anilibria as Parser>::session(self, &response)? else {
+            let error = <Anilibria as Parser>::error(self, &response).unwrap_or(response);
+            return Err(SourceError::ApiError(error));
+        };
+
+        self.accounts
+            .borrow_mut()
+            .set(ACCOUNT, json!({ "session": session }))
+            .map_err(|err| SourceError::ApiError(err.to_string()))
+    }
+
+    /// Forget the session of the user
+    /// # Returns
+    /// `false` if the user isn't logged in
+    pub fn logout(&self) -> io::Result<bool> {
+        self.accounts.borrow_mut().remove(ACCOUNT)
+    }
+
+    fn session(&self) -> Option<String> {
+        self.accounts
+            .borrow()
+            .get(ACCOUNT)
+            .and_then(|account| account["session"].as_str())
+            .map(ToString::to_string)
+    }
+
+    /// Check the response of the API isn't an error, e.g. of an expired session
+    fn check(&self, response: &str) -> Result<(), SourceError> {
+        match <Anilibria as Parser>::error(self, response) {
+            Some(error) => Err(SourceError::ApiError(Message::ApiError(&error).to_string())),
+            None => Ok(()),
+        }
     }
 }
 
 impl Default for Anilibria<'_> {
-    #[must_use]
     fn default() -> Self {
-        Self::new(None)
+        Self::new(Http::default())
     }
 }
 
@@ -82,15 +192,17 @@ impl Source for Anilibria<'_> {
         &self.language
     }
 
-    fn search_anime_list(&mut self, query: &str) -> Result<Self::SearchAnimeListInfo, SourceError> {
-        let api_result = <Anilibria as Api>::search_anime(self, query)?;
+    async fn search_anime_list(
+        &mut self,
+        query: &str,
+    ) -> Result<Self::SearchAnimeListInfo, SourceError> {
+        let api_result = <Anilibria as Api>::search_anime(self, query).await?;
         let anime_list = <Anilibria as Parser>::search_anime(self, &api_result)?;
 
         if anime_list.is_empty() {
-            return Err(SourceError::ApiError(format!(
-                "Anime list by query `{}` is empty",
-                query
-            )));
+            return Err(SourceError::ApiError(
+                Message::EmptyAnimeList(query).to_string(),
+            ));
         }
 
         let anime_info = anime_list
@@ -104,46 +216,71 @@ impl Source for Anilibria<'_> {
         Ok(anime_info)
     }
 
-    fn select_anime_as_current(&mut self, title_or_seq_num: String) -> Result<(), SourceError> {
-        let anime_list = &self.current_anime_list;
+    async fn select_anime_as_current(
+        &mut self,
+        title_or_seq_num: String,
+    ) -> Result<(), SourceError> {
+        let anime = find_in_anime_list(
+            &self.current_anime_list,
+            &title_or_seq_num,
+            |anime, name| {
+                // User can different combinations ru and en names,
+                // so we use `.contains` for works different situations,
+                // e.g `A | B` | `B | A` | `a` (where `A` is `ru`, `B` is `en` and `a` is incomplete name) returns `true`
+                anime.names.ru.to_lowercase().contains(name)
+                    || anime.names.en.to_lowercase().contains(name)
+            },
+        )?;
 
-        let anime = if let Some(anime) = anime_list.iter().find(|anime| {
-            let name = title_or_seq_num.to_lowercase();
-            let ru_name = anime.names.ru.to_lowercase();
-            let en_name = anime.names.en.to_lowercase();
+        self.current_anime = Some(Rc::clone(anime));
 
-            // User can different combinations ru and en names,
-            // so we use `.contains` for works different situations,
-            // e.g `A | B` | `B | A` | `a` (where `A` is `ru`, `B` is `en` and `a` is incomplete name) returns `true`
-            ru_name.contains(&name) || en_name.contains(&name)
-        }) {
-            anime
-        } else {
-            match title_or_seq_num.parse::<usize>() {
-                Ok(seq_num) => {
-                    if let Some(anime) = seq_num
-                        .checked_sub(1)
-                        .and_then(|seq_num| anime_list.get(seq_num))
-                    {
-                        anime
-                    } else {
-                        return Err(SourceError::UnknownVariant(format!(
-                            "Unknown anime sequence number `{}`",
-                            seq_num
-                        )));
-                    }
-                }
-                Err(_) => {
-                    return Err(SourceError::UnknownVariant(format!(
-                        "Unknown anime name `{title_or_seq_num}`"
-                    )))
-                }
-            }
+        Ok(())
+    }
+
+    fn anime_list(&self) -> Vec<String> {
+        self.current_anime_list
+            .iter()
+            .map(|anime| anime.to_string())
+            .collect()
+    }
+
+    async fn favorite_anime_list(
+        &mut self,
+    ) -> Result<Option<Self::SearchAnimeListInfo>, SourceError> {
+        let Some(session) = self.session() else {
+            return Ok(None);
         };
 
-        self.current_anime = Some(Rc::clone(anime));
+        let api_result = <Anilibria as Api>::favorites(self, &session).await?;
+        self.check(&api_result)?;
+        let anime_list = <Anilibria as Parser>::favorites(self, &api_result)?;
 
-        Ok(())
+        let anime_info = anime_list
+            .iter()
+            .enumerate()
+            .map(|(seq_num, anime)| format!("\t{seq_num}. {anime}\n", seq_num = seq_num + 1))
+            .collect();
+
+        self.current_anime_list = anime_list.into_iter().map(Rc::new).collect();
+
+        Ok(Some(anime_info))
+    }
+
+    async fn set_favorite(&self, favorite: bool) -> Result<(), SourceError> {
+        let anime = self.current_anime.as_ref().expect("No anime selected");
+        let Some(session) = self.session() else {
+            return Err(SourceError::ApiError(
+                Message::NotLoggedIn(ACCOUNT).to_string(),
+            ));
+        };
+
+        let api_result = if favorite {
+            <Anilibria as Api>::add_favorite(self, &session, anime.id).await?
+        } else {
+            <Anilibria as Api>::remove_favorite(self, &session, anime.id).await?
+        };
+
+        self.check(&api_result)
     }
 
     fn anime_info(&self) -> Result<Self::AnimeInfo, SourceError> {
@@ -154,6 +291,25 @@ impl Source for Anilibria<'_> {
             .expect("No anime selected"))
     }
 
+    fn anime_details(&self) -> Result<AnimeDetails, SourceError> {
+        let anime = self.current_anime.as_ref().expect("No anime selected");
+
+        Ok(AnimeDetails {
+            title: anime.names.ru.clone(),
+            original_title: Some(anime.names.en.clone()),
+            description: anime.description.clone(),
+            genres: anime.genres.clone(),
+            year: anime.year,
+            poster: anime.poster.as_ref().map(|poster| {
+                if poster.starts_with("http") {
+                    poster.clone()
+                } else {
+                    format!("{SITE_URL}{poster}")
+                }
+            }),
+        })
+    }
+
     fn episodes_info(&mut self) -> Result<Self::EpisodesInfo, SourceError> {
         let anime = self.current_anime.as_ref().expect("No anime selected");
 
@@ -162,7 +318,10 @@ impl Source for Anilibria<'_> {
         Ok(episodes_info)
     }
 
-    fn select_episode_as_current(&mut self, seq_num_or_pattern: String) -> Result<(), SourceError> {
+    async fn select_episode_as_current(
+        &mut self,
+        seq_num_or_pattern: String,
+    ) -> Result<(), SourceError> {
         assert!(!seq_num_or_pattern.is_empty());
 
         let anime = self.current_anime.as_ref().expect("No anime selected");
@@ -175,21 +334,20 @@ impl Source for Anilibria<'_> {
             "last" | "l" => last,
             _ => {
                 if let Ok(seq_num) = seq_num_or_pattern.parse::<u16>() {
-                    if anime
+                    if !anime
                         .player
                         .playlist
-                        .get(&seq_num_or_pattern.to_lowercase())
-                        .is_none()
+                        .contains_key(&seq_num_or_pattern.to_lowercase())
                     {
-                        return Err(SourceError::UnknownVariant(format!(
-                            "Unknown episode number `{seq_num}`"
-                        )));
+                        return Err(SourceError::UnknownVariant(
+                            Message::UnknownEpisodeNumber(&seq_num).to_string(),
+                        ));
                     }
                     seq_num
                 } else {
-                    return Err(SourceError::UnknownVariant(format!(
-                            "Unknown episode pattern `{seq_num_or_pattern}`. Possible patterns: first|f, last|l"
-                        )));
+                    return Err(SourceError::UnknownVariant(
+                        Message::UnknownEpisodePattern(&seq_num_or_pattern).to_string(),
+                    ));
                 }
             }
         };
@@ -199,6 +357,20 @@ impl Source for Anilibria<'_> {
         Ok(())
     }
 
+    fn episodes(&self) -> Result<Vec<String>, SourceError> {
+        let anime = self.current_anime.as_ref().expect("No anime selected");
+
+        let mut episodes = anime
+            .player
+            .playlist
+            .values()
+            .map(|serie_info| serie_info.serie)
+            .collect::<Vec<u16>>();
+        episodes.sort_unstable();
+
+        Ok(episodes.iter().map(ToString::to_string).collect())
+    }
+
     fn episode_info(&self) -> Result<Self::EpisodeIndo, SourceError> {
         let episode = self.current_episode.as_ref().expect("No episode selected");
 
@@ -207,6 +379,21 @@ impl Source for Anilibria<'_> {
         Ok(episode_info)
     }
 
+    fn episode_details(&self) -> EpisodeDetails {
+        let anime = self.current_anime.as_ref().expect("No anime selected");
+        let episode = self.current_episode.as_ref().expect("No episode selected");
+
+        let serie_info = anime.player.playlist.get(&episode.to_string());
+        let range = |(start, end): (u64, u64)| Duration::from_secs(start)..Duration::from_secs(end);
+
+        EpisodeDetails {
+            title: serie_info.and_then(|info| info.name.clone()),
+            voice: anime.voice.clone(),
+            opening: serie_info.and_then(|info| info.opening).map(range),
+            ending: serie_info.and_then(|info| info.ending).map(range),
+        }
+    }
+
     fn qualities_info(&mut self) -> Result<Self::QualitiesInfo, SourceError> {
         let anime = self.current_anime.as_ref().expect("No anime selected");
         let episode = self.current_episode.as_ref().expect("No episode selected");
@@ -215,50 +402,99 @@ impl Source for Anilibria<'_> {
 
         let mut qualities_info = String::new();
 
+        // Resolutions of the streams are known only from their playlists,
+        // so they're shown by the dialog if the streams are checked
         if serie_with_hls_info.sd.is_some() {
-            qualities_info.push_str("\t1 | sd | 360p | 480p | min\n");
+            qualities_info.push_str("\t1 | sd | min\n");
         }
         if serie_with_hls_info.hd.is_some() {
-            qualities_info.push_str("\t2 | hd | 720p | avg\n");
+            qualities_info.push_str("\t2 | hd | avg\n");
         }
         if serie_with_hls_info.fhd.is_some() {
-            qualities_info.push_str("\t3 | fhd | 1080p | full | max\n");
+            qualities_info.push_str("\t3 | fhd | max\n");
         }
 
         Ok(qualities_info)
     }
 
+    fn qualities(&self) -> Result<Vec<String>, SourceError> {
+        let anime = self.current_anime.as_ref().expect("No anime selected");
+        let episode = self.current_episode.as_ref().expect("No episode selected");
+
+        let serie_with_hls_info = anime.player.playlist.get(&episode.to_string()).unwrap();
+
+        Ok([
+            ("sd", &serie_with_hls_info.sd),
+            ("hd", &serie_with_hls_info.hd),
+            ("fhd", &serie_with_hls_info.fhd),
+        ]
+        .into_iter()
+        .filter(|(_, hls)| hls.is_some())
+        .map(|(quality, _)| quality.to_string())
+        .collect())
+    }
+
     fn select_quality_as_current(&mut self, quality: String) -> Result<(), SourceError> {
         let anime = self.current_anime.as_ref().expect("No anime selected");
         let episode = self.current_episode.as_ref().expect("No episode selected");
 
         let serie_with_hls_info = anime.player.playlist.get(&episode.to_string()).unwrap();
 
-        let hls = match quality.to_lowercase().as_str() {
-            "1" | "sd" | "360p" | "360" | "480p" | "480" | "min" => serie_with_hls_info.sd.as_ref(),
-            "2" | "hd" | "720p" | "720" | "avg" => serie_with_hls_info.hd.as_ref(),
-            "3" | "fhd" | "1080p" | "1080" | "full" | "max" => serie_with_hls_info.fhd.as_ref(),
+        let (name, hls) = match quality.to_lowercase().as_str() {
+            "1" | "sd" | "360p" | "360" | "480p" | "480" | "min" => {
+                ("sd", serie_with_hls_info.sd.as_ref())
+            }
+            "2" | "hd" | "720p" | "720" | "avg" => ("hd", serie_with_hls_info.hd.as_ref()),
+            "3" | "fhd" | "1080p" | "1080" | "full" | "max" => {
+                ("fhd", serie_with_hls_info.fhd.as_ref())
+            }
             _ => {
-                return Err(SourceError::UnknownVariant(format!(
-                    "Unknown quality `{quality}`"
-                )));
+                return Err(SourceError::UnknownVariant(
+                    Message::UnknownQuality(&quality).to_string(),
+                ));
             }
-        }
-        .unwrap();
+        };
+        // The quality may be unavailable for the episode
+        let Some(hls) = hls else {
+            return Err(SourceError::UnknownVariant(
+                Message::UnknownQuality(&quality).to_string(),
+            ));
+        };
 
+        self.current_quality = Some(name.to_string());
         self.current_hls = Some(hls.clone());
 
         Ok(())
     }
 
-    fn url_for_stream(&self) -> Result<String, SourceError> {
+    fn quality_info(&self) -> Result<Self::QualityInfo, SourceError> {
+        let quality = self.current_quality.as_ref().expect("No quality selected");
+
+        Ok(quality.clone())
+    }
+
+    async fn url_for_stream(&self) -> Result<String, SourceError> {
+        let anime = self.current_anime.as_ref().expect("No anime selected");
         let hls = self.current_hls.as_ref().expect("No hls unit selected");
 
         if hls.starts_with("http") {
             return Ok(hls.clone());
         }
 
-        let url = format!("https://{hls}");
+        if let Some(host) = self.media_hosts.checked() {
+            return Ok(format!("https://{host}{hls}"));
+        }
+
+        // The playlist is requested once to check the host is reachable,
+        // so an unavailable host doesn't delay the next host by retries
+        let url = self
+            .media_hosts
+            .try_each(Some(&anime.player.host), |host| async move {
+                let url = format!("https://{host}{hls}");
+                let request = self.http().request(Method::GET, &url);
+                self.http().send(request).await.map(|_| url)
+            })
+            .await?;
 
         Ok(url)
     }
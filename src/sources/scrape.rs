@@ -0,0 +1,8 @@
+pub mod embed;
+pub mod page;
+pub mod pages;
+mod source;
+mod spec;
+
+pub use source::HtmlSource;
+pub use spec::{EpisodesSpec, HtmlSpec, ListingSpec, SearchSpec, StreamsSpec};
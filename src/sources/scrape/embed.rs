@@ -0,0 +1,147 @@
+use super::{page::Page, pages};
+
+use crate::{errors::SourceError, sources::http::Http};
+
+use reqwest::Url;
+use scraper::Selector;
+
+/// Elements that embed players of other pages
+const EMBED_SELECTOR: &str = "iframe[src], iframe[data-src], embed[src], object[data]";
+/// Elements that play media directly
+const MEDIA_SELECTOR: &str = "video[src], video source[src], audio source[src]";
+/// Extensions of media that can be passed to a player
+const MEDIA_EXTENSIONS: [&str; 3] = [".m3u8", ".mp4", ".mpd"];
+
+/// Check the URL points to media, e.g. an HLS playlist or an MP4 file
+#[must_use]
+pub fn is_media_url(url: &str) -> bool {
+    let path = Url::parse(url).map_or_else(|_| url.to_string(), |url| url.path().to_string());
+
+    MEDIA_EXTENSIONS
+        .iter()
+        .any(|extension| path.to_lowercase().ends_with(extension))
+}
+
+/// Find media URLs of the page: sources of `<video>` and URLs in quotes in scripts,
+/// e.g. `player.setup({ file: "https:\/\/cdn.example.com\/1.m3u8" })`
+#[must_use]
+pub fn media_urls(page: &Page) -> Vec<String> {
+    let media = Selector::parse(MEDIA_SELECTOR).expect("Valid selector");
+    let scripts = Selector::parse("script").expect("Valid selector");
+
+    let mut urls: Vec<String> = page
+        .select(&media)
+        .filter_map(|element| element.attr("src"))
+        .filter_map(|src| page.resolve(src))
+        .collect();
+
+    for script in page.select(&scripts) {
+        let script: String = script.text().collect();
+        for quoted in script.split(['"', '\'', '`']) {
+            let quoted = quoted.replace("\\/", "/");
+            if quoted.contains(char::is_whitespace) || !is_media_url(&quoted) {
+                continue;
+            }
+            if let Some(url) = page.resolve(&quoted) {
+                urls.push(url);
+            }
+        }
+    }
+
+    urls.dedup();
+    urls
+}
+
+/// Find URLs of pages embedded into the page, e.g. `<iframe src="//player.example.com/embed/1">`
+#[must_use]
+pub fn embedded_urls(page: &Page) -> Vec<String> {
+    let embeds = Selector::parse(EMBED_SELECTOR).expect("Valid selector");
+
+    page.select(&embeds)
+        .filter_map(|element| {
+            element
+                .attr("src")
+                .or_else(|| element.attr("data-src"))
+                .or_else(|| element.attr("data"))
+        })
+        .filter_map(|src| page.resolve(src))
+        .collect()
+}
+
+/// Find the media of a player page, following embedded pages in order
+/// # Arguments
+/// * `max_depth` - How many levels of embedded pages are followed
+/// # Returns
+/// `None` if no media is found
+pub async fn resolve(
+    http: &Http,
+    url: &str,
+    max_depth: usize,
+) -> Result<Option<String>, SourceError> {
+    if is_media_url(url) {
+        return Ok(Some(url.to_string()));
+    }
+
+    // Depth-first, so the first player of the page is preferred
+    let mut stack = vec![(url.to_string(), 0)];
+    let mut visited = Vec::new();
+    while let Some((url, depth)) = stack.pop() {
+        if visited.contains(&url) {
+            continue;
+        }
+        // Embedded players are often unavailable, so only errors of the page itself are reported
+        let page = match pages::fetch(http, &url, &[]).await {
+            Ok(page) => page,
+            Err(err) if depth == 0 => return Err(err),
+            Err(_) => continue,
+        };
+        visited.push(url);
+
+        if let Some(media) = media_urls(&page).into_iter().next() {
+            return Ok(Some(media));
+        }
+        if depth < max_depth {
+            let embedded = embedded_urls(&page);
+            if let Some(media) = embedded.iter().find(|url| is_media_url(url)) {
+                return Ok(Some(media.clone()));
+            }
+            stack.extend(embedded.into_iter().rev().map(|url| (url, depth + 1)));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(body: &str) -> Page {
+        Page::parse(body, Url::parse("https://example.com/player/1").unwrap())
+    }
+
+    #[test]
+    fn media_is_found_in_scripts() {
+        let page = page(include_str!("fixtures/embed.html"));
+
+        // The poster isn't media and the script without code is skipped
+        assert_eq!(
+            media_urls(&page),
+            ["https://cdn.example.com/hls/7f3a9c/720p.m3u8"]
+        );
+    }
+
+    #[test]
+    fn embedded_players_are_found_in_order() {
+        let page = page(include_str!("fixtures/player.html"));
+
+        assert_eq!(
+            embedded_urls(&page),
+            [
+                "https://example.com/ads/banner",
+                "https://example.com/embed/7f3a9c"
+            ]
+        );
+        assert!(media_urls(&page).is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+
+/// HTML page with the URL it was loaded from, so relative links can be resolved
+pub struct Page {
+    html: Html,
+    url: Url,
+}
+
+impl Page {
+    #[must_use]
+    pub fn parse(body: &str, url: Url) -> Self {
+        Self {
+            html: Html::parse_document(body),
+            url,
+        }
+    }
+
+    #[must_use]
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Get elements matching the selector in document order
+    pub fn select<'a>(&'a self, selector: &'a Selector) -> impl Iterator<Item = ElementRef<'a>> {
+        self.html.select(selector)
+    }
+
+    /// Resolve the link against the URL of the page
+    /// # Returns
+    /// `None` if the link is empty or invalid
+    #[must_use]
+    pub fn resolve(&self, link: &str) -> Option<String> {
+        let link = link.trim();
+        if link.is_empty() {
+            return None;
+        }
+
+        self.url.join(link).ok().map(Into::into)
+    }
+}
+
+/// Parse a CSS selector
+/// # Returns
+/// A description of the error if the selector is invalid
+pub fn selector(selector: &str) -> Result<Selector, String> {
+    Selector::parse(selector).map_err(|err| format!("Invalid selector `{selector}`: {err}"))
+}
+
+/// Value of an element written as `selector@attribute`, e.g. `a.title@href`. \
+/// The text of the element is taken if there is no attribute,
+/// the element itself is used if there is no selector, e.g. `@href`
+#[derive(Clone, Debug)]
+pub struct Extract {
+    selector: Option<Selector>,
+    attribute: Option<String>,
+}
+
+impl Extract {
+    /// # Returns
+    /// A description of the error if the selector is invalid
+    pub fn parse(extract: &str) -> Result<Self, String> {
+        let (selector_text, attribute) = match extract.rsplit_once('@') {
+            Some((selector, attribute))
+                if !attribute.is_empty()
+                    && attribute.chars().all(|char| {
+                        char.is_ascii_alphanumeric() || matches!(char, '-' | '_' | ':')
+                    }) =>
+            {
+                (selector.trim(), Some(attribute.to_string()))
+            }
+            _ => (extract.trim(), None),
+        };
+
+        Ok(Self {
+            selector: if selector_text.is_empty() {
+                None
+            } else {
+                Some(selector(selector_text)?)
+            },
+            attribute,
+        })
+    }
+
+    /// Get the value from the first matching element inside the element
+    /// # Returns
+    /// `None` if there is no matching element or the value is empty
+    #[must_use]
+    pub fn value(&self, element: ElementRef) -> Option<String> {
+        let element = match &self.selector {
+            Some(selector) => element.select(selector).next()?,
+            None => element,
+        };
+
+        let value = match &self.attribute {
+            Some(attribute) => element.attr(attribute)?.trim().to_string(),
+            None => text(element),
+        };
+        (!value.is_empty()).then_some(value)
+    }
+}
+
+/// Get the text of the element with collapsed whitespace
+#[must_use]
+pub fn text(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
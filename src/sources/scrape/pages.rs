@@ -0,0 +1,59 @@
+use super::page::Page;
+
+use crate::{errors::SourceError, sources::http::Http};
+
+use reqwest::Url;
+use scraper::Selector;
+
+/// Load the page
+/// # Arguments
+/// * `query` - Query parameters of the request
+pub async fn fetch(http: &Http, url: &str, query: &[(&str, &str)]) -> Result<Page, SourceError> {
+    let body = http.get_text(url, query).await?;
+    // The URL is valid because the request was sent
+    let mut url = Url::parse(url).map_err(|err| SourceError::ParseError(err.to_string()))?;
+    if !query.is_empty() {
+        url.query_pairs_mut().extend_pairs(query);
+    }
+
+    Ok(Page::parse(&body, url))
+}
+
+/// Get the link to the next page of a paginated list
+/// # Arguments
+/// * `next` - Selector of the link to the next page, e.g. `a[rel=next]`
+#[must_use]
+pub fn next_page(page: &Page, next: &Selector) -> Option<String> {
+    page.select(next)
+        .find_map(|link| link.attr("href"))
+        .and_then(|href| page.resolve(href))
+}
+
+/// Load the page and the pages following it
+/// # Arguments
+/// * `next` - Selector of the link to the next page, `None` to load only the first page
+/// * `max_pages` - Maximum count of loaded pages, pages linking to already loaded pages end the list
+pub async fn fetch_all(
+    http: &Http,
+    url: &str,
+    query: &[(&str, &str)],
+    next: Option<&Selector>,
+    max_pages: usize,
+) -> Result<Vec<Page>, SourceError> {
+    let mut pages = vec![fetch(http, url, query).await?];
+
+    if let Some(next) = next {
+        while pages.len() < max_pages {
+            let Some(url) = pages.last().and_then(|page| next_page(page, next)) else {
+                break;
+            };
+            if pages.iter().any(|page| page.url().as_str() == url) {
+                break;
+            }
+
+            pages.push(fetch(http, &url, &[]).await?);
+        }
+    }
+
+    Ok(pages)
+}
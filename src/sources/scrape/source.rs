@@ -0,0 +1,492 @@
+use super::{
+    embed,
+    page::Page,
+    pages,
+    spec::{HtmlSpec, ListingSpec},
+};
+
+use crate::{
+    enums::language::Language,
+    errors::SourceError,
+    locale::Message,
+    sources::{
+        base::{find_in_anime_list, Source},
+        http::Http,
+        rest::RequestSpec,
+    },
+};
+
+use std::{
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+/// Quality of the only stream of an episode if the source doesn't list streams
+const DEFAULT_QUALITY: &str = "default";
+
+/// Anime, episode or stream found on a page
+#[derive(Clone)]
+struct Item {
+    /// Title of an anime, number of an episode or quality of a stream
+    name: String,
+    /// Absolute URL of the page of an anime or an episode, or of a stream
+    link: String,
+}
+
+/// Source scraping an HTML site defined in the config file, see [`HtmlSpec`]. \
+/// Clones of the source share the definition
+#[derive(Clone)]
+pub struct HtmlSource {
+    spec: Rc<HtmlSpec>,
+    http: Http,
+
+    current_anime_list: Vec<Item>,
+    current_anime: Option<Item>,
+    current_episodes: Vec<Item>,
+    current_episode: Option<Item>,
+    current_streams: Vec<Item>,
+    current_stream: Option<Item>,
+}
+
+impl HtmlSource {
+    #[must_use]
+    pub fn new(spec: HtmlSpec, http: Http) -> Self {
+        Self {
+            spec: Rc::new(spec),
+            http,
+            current_anime_list: Vec::new(),
+            current_anime: None,
+            current_episodes: Vec::new(),
+            current_episode: None,
+            current_streams: Vec::new(),
+            current_stream: None,
+        }
+    }
+
+    /// Load pages of the list
+    /// # Arguments
+    /// * `request` - Request of the first page, `None` to load the link
+    /// * `values` - Values of placeholders of the request
+    async fn load(
+        &self,
+        request: Option<&RequestSpec>,
+        link: &str,
+        values: &[(&str, &str)],
+        listing: &ListingSpec,
+    ) -> Result<Vec<Page>, SourceError> {
+        let (url, params) = match request {
+            Some(request) => request.build(values),
+            None => (link.to_string(), Vec::new()),
+        };
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        pages::fetch_all(
+            &self.http,
+            &url,
+            &params,
+            listing.next_page.as_ref(),
+            listing.max_pages,
+        )
+        .await
+    }
+
+    fn anime(&self) -> &Item {
+        self.current_anime.as_ref().expect("No anime selected")
+    }
+
+    fn episode(&self) -> &Item {
+        self.current_episode.as_ref().expect("No episode selected")
+    }
+
+    fn stream(&self) -> &Item {
+        self.current_stream.as_ref().expect("No quality selected")
+    }
+}
+
+impl Display for HtmlSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec.name)
+    }
+}
+
+impl PartialEq<String> for HtmlSource {
+    fn eq(&self, other: &String) -> bool {
+        self.spec.name.to_lowercase() == other.to_lowercase()
+    }
+}
+
+impl Source for HtmlSource {
+    type SearchAnimeListInfo = String;
+    type AnimeInfo = String;
+    type EpisodesInfo = String;
+    type EpisodeIndo = String;
+    type QualitiesInfo = String;
+    type QualityInfo = String;
+
+    fn language(&self) -> &Language {
+        &self.spec.language
+    }
+
+    async fn search_anime_list(
+        &mut self,
+        query: &str,
+    ) -> Result<Self::SearchAnimeListInfo, SourceError> {
+        let search = &self.spec.search;
+        let pages = self
+            .load(
+                Some(&search.request),
+                "",
+                &[("query", query)],
+                &search.listing,
+            )
+            .await?;
+
+        // Items without a title or a link are usually ads
+        let found = pages.iter().flat_map(|page| {
+            page.select(&search.listing.items).filter_map(|item| {
+                Some(Item {
+                    name: search.title.value(item)?,
+                    link: page.resolve(&search.link.value(item)?)?,
+                })
+            })
+        });
+        // Pages may repeat popular anime
+        self.current_anime_list.clear();
+        for anime in found {
+            if !self
+                .current_anime_list
+                .iter()
+                .any(|known| known.link == anime.link)
+            {
+                self.current_anime_list.push(anime);
+            }
+        }
+
+        Ok(self
+            .current_anime_list
+            .iter()
+            .enumerate()
+            .map(|(index, anime)| format!("\t{seq_num}. {}\n", anime.name, seq_num = index + 1))
+            .collect())
+    }
+
+    async fn select_anime_as_current(
+        &mut self,
+        title_or_seq_num: String,
+    ) -> Result<(), SourceError> {
+        let anime = find_in_anime_list(
+            &self.current_anime_list,
+            &title_or_seq_num,
+            |anime, name| anime.name.to_lowercase().contains(name),
+        )?
+        .clone();
+
+        let episodes = &self.spec.episodes;
+        let pages = self
+            .load(
+                episodes.request.as_ref(),
+                &anime.link,
+                &[("anime", &anime.link)],
+                &episodes.listing,
+            )
+            .await?;
+
+        self.current_episodes = pages
+            .iter()
+            .flat_map(|page| {
+                page.select(&episodes.listing.items).filter_map(|item| {
+                    Some(Item {
+                        name: episodes.number.value(item)?,
+                        link: page.resolve(&episodes.link.value(item)?)?,
+                    })
+                })
+            })
+            .collect();
+        self.current_anime = Some(anime);
+        self.current_episode = None;
+        self.current_stream = None;
+
+        Ok(())
+    }
+
+    fn anime_list(&self) -> Vec<String> {
+        self.current_anime_list
+            .iter()
+            .map(|anime| anime.name.clone())
+            .collect()
+    }
+
+    fn anime_info(&self) -> Result<Self::AnimeInfo, SourceError> {
+        Ok(self.anime().name.clone())
+    }
+
+    fn episodes_info(&mut self) -> Result<Self::EpisodesInfo, SourceError> {
+        Ok(format!("\n\t{}\n", self.episodes()?.join(", ")))
+    }
+
+    async fn select_episode_as_current(&mut self, episode: String) -> Result<(), SourceError> {
+        let episode = match episode.to_lowercase().as_str() {
+            "first" | "f" => self.current_episodes.first(),
+            "last" | "l" => self.current_episodes.last(),
+            number => self
+                .current_episodes
+                .iter()
+                .find(|known| known.name.to_lowercase() == number),
+        }
+        .cloned()
+        .ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownEpisodePattern(&episode).to_string())
+        })?;
+
+        self.current_streams = match &self.spec.streams {
+            Some(streams) => {
+                let pages = self
+                    .load(
+                        streams.request.as_ref(),
+                        &episode.link,
+                        &[("anime", &self.anime().link), ("episode", &episode.name)],
+                        &streams.listing,
+                    )
+                    .await?;
+
+                pages
+                    .iter()
+                    .flat_map(|page| {
+                        page.select(&streams.listing.items).filter_map(|item| {
+                            Some(Item {
+                                name: streams.quality.value(item)?,
+                                link: page.resolve(&streams.stream_url.value(item)?)?,
+                            })
+                        })
+                    })
+                    .collect()
+            }
+            None => vec![Item {
+                name: DEFAULT_QUALITY.to_string(),
+                link: episode.link.clone(),
+            }],
+        };
+        self.current_episode = Some(episode);
+        self.current_stream = None;
+
+        Ok(())
+    }
+
+    fn episodes(&self) -> Result<Vec<String>, SourceError> {
+        Ok(self
+            .current_episodes
+            .iter()
+            .map(|episode| episode.name.clone())
+            .collect())
+    }
+
+    fn episode_info(&self) -> Result<Self::EpisodeIndo, SourceError> {
+        Ok(self.episode().name.clone())
+    }
+
+    fn qualities_info(&mut self) -> Result<Self::QualitiesInfo, SourceError> {
+        Ok(self
+            .current_streams
+            .iter()
+            .enumerate()
+            .map(|(index, stream)| format!("\t{seq_num} | {}\n", stream.name, seq_num = index + 1))
+            .collect())
+    }
+
+    fn qualities(&self) -> Result<Vec<String>, SourceError> {
+        Ok(self
+            .current_streams
+            .iter()
+            .map(|stream| stream.name.clone())
+            .collect())
+    }
+
+    fn select_quality_as_current(&mut self, quality: String) -> Result<(), SourceError> {
+        let name = quality.to_lowercase();
+
+        let known = match name.as_str() {
+            "min" => self.current_streams.first(),
+            "max" => self.current_streams.last(),
+            _ => self
+                .current_streams
+                .iter()
+                .find(|known| known.name.to_lowercase() == name)
+                .or_else(|| {
+                    name.parse::<usize>()
+                        .ok()
+                        .and_then(|seq_num| seq_num.checked_sub(1))
+                        .and_then(|index| self.current_streams.get(index))
+                }),
+        };
+
+        self.current_stream = Some(known.cloned().ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownQuality(&quality).to_string())
+        })?);
+
+        Ok(())
+    }
+
+    fn quality_info(&self) -> Result<Self::QualityInfo, SourceError> {
+        Ok(self.stream().name.clone())
+    }
+
+    /// Players are resolved only now, so listing qualities doesn't load every player
+    async fn url_for_stream(&self) -> Result<String, SourceError> {
+        let link = &self.stream().link;
+
+        embed::resolve(&self.http, link, self.spec.max_embed_depth)
+            .await?
+            .ok_or_else(|| SourceError::ApiError(Message::NoMediaFound(link).to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::html,
+        testing::{http, MockServer},
+    };
+
+    /// Pages of the example site saved in `fixtures`
+    fn site() -> MockServer {
+        MockServer::start(|request| {
+            let page = match request.path.as_str() {
+                "/search?q=madoka" => include_str!("fixtures/search.html"),
+                "/search?q=madoka&page=2" => include_str!("fixtures/search_2.html"),
+                "/anime/madoka-magica" => include_str!("fixtures/anime.html"),
+                "/watch/madoka-magica/1" => include_str!("fixtures/episode.html"),
+                "/player/madoka-magica/1?quality=720p" => include_str!("fixtures/player.html"),
+                "/embed/7f3a9c" => include_str!("fixtures/embed.html"),
+                _ => return (404, String::new()),
+            };
+            (200, page.to_string())
+        })
+    }
+
+    /// The example definition of the README with the site replaced by the server
+    fn example(server: &MockServer) -> HtmlSpec {
+        let config =
+            include_str!("fixtures/example.json").replace("https://example.com", server.url());
+        let config: serde_json::Value = serde_json::from_str(&config).unwrap();
+
+        html::parse(&config["html_sources"]).unwrap().remove(0)
+    }
+
+    async fn select_episode(source: &mut HtmlSource) {
+        source.search_anime_list("madoka").await.unwrap();
+        source
+            .select_anime_as_current(String::from("1"))
+            .await
+            .unwrap();
+        source
+            .select_episode_as_current(String::from("1"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn anime_are_found_on_all_pages_of_search() {
+        let server = site();
+        let mut source = HtmlSource::new(example(&server), http());
+
+        let info = source.search_anime_list("madoka").await.unwrap();
+
+        assert_eq!(
+            source.anime_list(),
+            [
+                "Mahou Shoujo Madoka★Magica",
+                "Madoka Magica the Movie: Rebellion",
+                "Magia Record",
+            ]
+        );
+        assert_eq!(
+            info,
+            "\t1. Mahou Shoujo Madoka★Magica\n\t2. Madoka Magica the Movie: Rebellion\n\t3. Magia Record\n"
+        );
+        // The second page links back to the first one
+        assert_eq!(
+            server.log(),
+            ["GET /search?q=madoka", "GET /search?q=madoka&page=2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn episodes_and_qualities_are_found_on_linked_pages() {
+        let server = site();
+        let mut source = HtmlSource::new(example(&server), http());
+
+        select_episode(&mut source).await;
+
+        assert_eq!(source.anime_info().unwrap(), "Mahou Shoujo Madoka★Magica");
+        // Episodes without a link aren't released yet
+        assert_eq!(source.episodes().unwrap(), ["1", "2"]);
+        assert_eq!(source.qualities().unwrap(), ["720p", "1080p"]);
+        assert_eq!(
+            source.qualities_info().unwrap(),
+            "\t1 | 720p\n\t2 | 1080p\n"
+        );
+        assert_eq!(
+            server.log()[2..],
+            ["GET /anime/madoka-magica", "GET /watch/madoka-magica/1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn media_is_taken_directly_or_found_in_embedded_players() {
+        let server = site();
+        let mut source = HtmlSource::new(example(&server), http());
+        select_episode(&mut source).await;
+
+        source
+            .select_quality_as_current(String::from("1080p"))
+            .unwrap();
+        assert_eq!(
+            source.url_for_stream().await.unwrap(),
+            format!("{}/media/madoka-magica/1/1080p/index.m3u8", server.url())
+        );
+        assert_eq!(server.requests().len(), 4);
+
+        source
+            .select_quality_as_current(String::from("720p"))
+            .unwrap();
+        assert_eq!(
+            source.url_for_stream().await.unwrap(),
+            "https://cdn.example.com/hls/7f3a9c/720p.m3u8"
+        );
+        // The unavailable player of ads is skipped
+        assert_eq!(
+            server.log()[4..],
+            [
+                "GET /player/madoka-magica/1?quality=720p",
+                "GET /ads/banner",
+                "GET /embed/7f3a9c",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn episode_page_is_the_player_without_streams() {
+        let server = site();
+        let mut spec = example(&server);
+        spec.streams = None;
+        spec.max_embed_depth = 1;
+        let mut source = HtmlSource::new(spec, http());
+        select_episode(&mut source).await;
+
+        assert_eq!(source.qualities().unwrap(), [DEFAULT_QUALITY]);
+        source
+            .select_quality_as_current(String::from(DEFAULT_QUALITY))
+            .unwrap();
+
+        // The media is embedded two levels deeper than the page of the episode
+        let err = source.url_for_stream().await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            Message::NoMediaFound(&format!("{}/watch/madoka-magica/1", server.url())).to_string()
+        );
+    }
+}
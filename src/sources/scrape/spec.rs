@@ -0,0 +1,62 @@
+use super::page::Extract;
+
+use crate::{enums::language::Language, sources::rest::RequestSpec};
+
+use scraper::Selector;
+
+/// Definition of a source scraping an HTML site, read from the `html_sources` key of the config file. \
+/// Pages of anime and episodes are loaded from their links unless requests of them are defined
+#[derive(Clone)]
+pub struct HtmlSpec {
+    pub name: String,
+    pub language: Language,
+    pub search: SearchSpec,
+    pub episodes: EpisodesSpec,
+    /// `None` if the link of an episode is its player page
+    pub streams: Option<StreamsSpec>,
+    /// How many levels of embedded players are followed to find media
+    pub max_embed_depth: usize,
+}
+
+/// Items of a list that may span several pages
+#[derive(Clone)]
+pub struct ListingSpec {
+    /// Selector of the items
+    pub items: Selector,
+    /// Selector of the link to the next page, `None` if the list is on a single page
+    pub next_page: Option<Selector>,
+    /// Maximum count of loaded pages
+    pub max_pages: usize,
+}
+
+/// How anime are found
+#[derive(Clone)]
+pub struct SearchSpec {
+    pub request: RequestSpec,
+    pub listing: ListingSpec,
+    pub title: Extract,
+    /// Link to the page of an anime
+    pub link: Extract,
+}
+
+/// How episodes of the selected anime are listed
+#[derive(Clone)]
+pub struct EpisodesSpec {
+    /// `None` if episodes are on the page of the anime
+    pub request: Option<RequestSpec>,
+    pub listing: ListingSpec,
+    pub number: Extract,
+    /// Link to the page of an episode
+    pub link: Extract,
+}
+
+/// How streams of the selected episode are listed
+#[derive(Clone)]
+pub struct StreamsSpec {
+    /// `None` if streams are on the page of the episode
+    pub request: Option<RequestSpec>,
+    pub listing: ListingSpec,
+    pub quality: Extract,
+    /// URL of media or of a player page the media is found on
+    pub stream_url: Extract,
+}
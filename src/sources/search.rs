@@ -0,0 +1,219 @@
+use super::base::Source;
+
+use crate::{errors::SourceError, locale::Message};
+
+use futures::{stream::FuturesUnordered, StreamExt as _};
+use std::{future::Future, iter};
+
+/// An anime found by one of the searched sources
+pub struct Hit {
+    /// Index of the source in the searched sources
+    pub source_index: usize,
+    /// Index of the anime in [`Source::anime_list`] of the source
+    pub anime_index: usize,
+    pub title: String,
+}
+
+/// Result of [`search_all`]
+pub enum Search {
+    Finished(Vec<Hit>),
+    /// The search was cancelled, hits of the sources finished before cancellation are kept
+    Cancelled(Vec<Hit>),
+}
+
+impl Search {
+    #[must_use]
+    pub fn into_hits(self) -> Vec<Hit> {
+        match self {
+            Self::Finished(hits) | Self::Cancelled(hits) => hits,
+        }
+    }
+}
+
+/// What is searched in the sources
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Query<'a> {
+    /// Search anime by the name
+    Name(&'a str),
+    /// List favorite anime of accounts of the user instead of searching,
+    /// sources without a logged in account are skipped
+    Favorites,
+}
+
+/// Search anime by the name in the source or load its favorites. \
+/// Found anime are available in [`Source::anime_list`]
+/// # Returns
+/// `Ok(false)` if favorites are requested, but the source has no logged in account
+pub async fn search_source<S: Source>(
+    source: &mut S,
+    query: Query<'_>,
+) -> Result<bool, SourceError> {
+    match query {
+        Query::Name(name) => source.search_anime_list(name).await.map(|_| true),
+        Query::Favorites => source
+            .favorite_anime_list()
+            .await
+            .map(|favorites| favorites.is_some()),
+    }
+}
+
+/// Search anime by the query in all sources concurrently, see [`search_source`]
+/// # Arguments
+/// * `cancel` - Future that cancels the search when it's completed,
+///   e.g. [`std::future::pending`] to never cancel it
+/// * `on_result` - Called for each source as soon as its search is finished,
+///   with the new hits or the source error, sources without favorites aren't reported
+pub async fn search_all<S, C, F>(
+    sources: &mut [S],
+    query: Query<'_>,
+    cancel: C,
+    mut on_result: F,
+) -> Search
+where
+    S: Source,
+    C: Future<Output = ()>,
+    F: FnMut(&S, Result<&[Hit], &SourceError>),
+{
+    let mut searches = sources
+        .iter_mut()
+        .enumerate()
+        .map(|(source_index, source)| async move {
+            let result = search_source(source, query).await;
+            (source_index, source, result)
+        })
+        .collect::<FuturesUnordered<_>>();
+
+    let mut hits = Vec::new();
+
+    tokio::pin!(cancel);
+
+    loop {
+        let (source_index, source, result) = tokio::select! {
+            next = searches.next() => match next {
+                Some(next) => next,
+                None => return Search::Finished(hits),
+            },
+            () = &mut cancel => return Search::Cancelled(hits),
+        };
+
+        match result {
+            // The source has no favorites
+            Ok(false) => {}
+            Ok(true) => {
+                let first_new_hit = hits.len();
+
+                hits.extend(source.anime_list().into_iter().enumerate().map(
+                    |(anime_index, title)| Hit {
+                        source_index,
+                        anime_index,
+                        title,
+                    },
+                ));
+
+                on_result(source, Ok(&hits[first_new_hit..]));
+            }
+            Err(err) => on_result(source, Err(&err)),
+        }
+    }
+}
+
+/// Find the anime by the title it was saved with, e.g. in the watchlist, and select it as current anime
+pub async fn find_anime<S: Source>(source: &mut S, title: &str) -> Result<(), SourceError> {
+    // Titles may consist of names in several languages, e.g. `ru | en` of Anilibria,
+    // so the names are searched if the whole title isn't found
+    let names = title.split(" | ").filter(|name| *name != title);
+
+    let mut last_err = None;
+    for query in iter::once(title).chain(names) {
+        // Sources may find nothing by the whole title, e.g. Anilibria by `ru | en`
+        if let Err(err) = source.search_anime_list(query.trim()).await {
+            last_err = Some(err);
+            continue;
+        }
+        last_err = None;
+
+        // Titles may have a changing suffix like the schedule of the next episode
+        let position = source.anime_list().into_iter().position(|known| {
+            known == title || known.starts_with(title) || title.starts_with(known.as_str())
+        });
+        // Sources select anime by their names and not by whole titles, e.g. `ru | en`,
+        // so the anime is selected by its sequence number
+        if let Some(position) = position {
+            return source
+                .select_anime_as_current((position + 1).to_string())
+                .await;
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| {
+        SourceError::UnknownVariant(Message::UnknownAnimeName(title).to_string())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{anilibria, anilibria_anime, MockServer, MockSource};
+
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn anime_is_found_by_title_of_several_names() {
+        // The API finds nothing by both names like the API of Anilibria
+        let server = MockServer::start(|request| {
+            if request.path.contains("%7C") {
+                return (200, String::from("[]"));
+            }
+
+            let mut series = anilibria_anime(
+                9000,
+                "Девочка-волшебница Мадока★Магика",
+                "Mahou Shoujo Madoka★Magica",
+                12,
+            );
+            series["announce"] = json!("Сериал завершён");
+            let movie = anilibria_anime(
+                9001,
+                "Девочка-волшебница Мадока★Магика: Бунт",
+                "Mahou Shoujo Madoka★Magica Movie: Hangyaku no Monogatari",
+                1,
+            );
+            (200, json!([movie, series]).to_string())
+        });
+        let mut source = anilibria(&server);
+
+        // The title is saved without the announce
+        find_anime(
+            &mut source,
+            "Девочка-волшебница Мадока★Магика | Mahou Shoujo Madoka★Magica",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(source.episodes().unwrap().len(), 12);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn error_of_last_search_is_returned_if_anime_is_not_found() {
+        let mut source = MockSource::new("Mock", "http://stream.test")
+            .with_anime("Mahou Shoujo Madoka Magica", &["1", "2"]);
+
+        let err = find_anime(&mut source, "Бакемоногатари | Bakemonogatari")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            Message::EmptyAnimeList("bakemonogatari").to_string()
+        );
+
+        // The English name finds an anime, but it's another anime
+        let err = find_anime(&mut source, "Мадока | Magica")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            Message::UnknownAnimeName("Мадока | Magica").to_string()
+        );
+    }
+}
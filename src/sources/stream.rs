@@ -0,0 +1,281 @@
+use super::{
+    hls::{self, MediaPlaylist, Playlist, StreamInfo},
+    http::Http,
+};
+
+use crate::{errors::SourceError, locale::Message};
+
+use reqwest::Url;
+use std::time::Duration;
+
+/// Inspector of HLS streams before they are passed to a player. \
+/// The playlist is requested and the first segments are probed,
+/// so unavailable renditions are found before the player fails silently
+#[derive(Clone)]
+pub struct StreamCheck {
+    /// `None` if the check is disabled
+    http: Option<Http>,
+    /// Count of the first segments to probe
+    segments: usize,
+}
+
+impl StreamCheck {
+    #[must_use]
+    pub fn new(http: Http) -> Self {
+        Self {
+            http: Some(http),
+            segments: 2,
+        }
+    }
+
+    /// Create a check that accepts all streams and doesn't describe them
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            http: None,
+            segments: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.http.is_some()
+    }
+
+    /// Check the stream is playable
+    /// # Arguments
+    /// * `url` - URL of a media playlist or a master playlist,
+    ///   the first variant of a master playlist is checked
+    pub async fn check(&self, url: &str) -> Result<(), SourceError> {
+        let Some(http) = &self.http else {
+            return Ok(());
+        };
+
+        let (url, media, _) = self.media_playlist(http, url).await?;
+
+        let segments = media
+            .segments
+            .iter()
+            .take(self.segments)
+            .map(|segment| join_url(&url, &segment.uri))
+            .collect::<Result<Vec<_>, _>>()?;
+        if segments.is_empty() {
+            return Err(empty_playlist(&url));
+        }
+
+        for segment in segments {
+            http.probe(segment.as_str()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Describe the stream, e.g. its resolution, bitrate, duration and size
+    /// # Returns
+    /// `None` if the check is disabled
+    pub async fn info(&self, url: &str) -> Result<Option<StreamInfo>, SourceError> {
+        let Some(http) = &self.http else {
+            return Ok(None);
+        };
+
+        let (url, media, mut info) = self.media_playlist(http, url).await?;
+
+        let duration = media.duration();
+        info.duration = Some(duration).filter(|duration| !duration.is_zero());
+
+        // Media playlists have no bitrate, so it's estimated by the first segment
+        if info.bandwidth.is_none() {
+            if let Some(segment) = media.segments.first() {
+                let size = http.probe(join_url(&url, &segment.uri)?.as_str()).await?;
+                info.bandwidth = size.and_then(|size| bitrate(size, segment.duration));
+            }
+        }
+        info.size = info
+            .bandwidth
+            .zip(info.duration)
+            .map(|(bandwidth, duration)| bandwidth / 8 * duration.as_secs());
+
+        Ok(Some(info))
+    }
+
+    /// Request the media playlist, the first variant of a master playlist is requested
+    /// # Returns
+    /// URL of the media playlist, the playlist and attributes of the variant
+    async fn media_playlist(
+        &self,
+        http: &Http,
+        url: &str,
+    ) -> Result<(Url, MediaPlaylist, StreamInfo), SourceError> {
+        let url = Url::parse(url)
+            .map_err(|err| SourceError::ParseError(Message::ParseError(&err).to_string()))?;
+
+        match hls::parse(&http.get_text(url.as_str(), &[]).await?)? {
+            Playlist::Media(media) => Ok((url, media, StreamInfo::default())),
+            Playlist::Master(variants) => {
+                let variant = variants.first().ok_or_else(|| empty_playlist(&url))?;
+                let info = StreamInfo {
+                    resolution: variant.resolution,
+                    bandwidth: variant.bandwidth,
+                    codecs: variant.codecs.clone(),
+                    ..StreamInfo::default()
+                };
+
+                let url = join_url(&url, &variant.uri)?;
+                match hls::parse(&http.get_text(url.as_str(), &[]).await?)? {
+                    Playlist::Media(media) => Ok((url, media, info)),
+                    Playlist::Master(_) => Err(empty_playlist(&url)),
+                }
+            }
+        }
+    }
+}
+
+/// Bitrate in bits per second of a segment
+fn bitrate(size: u64, duration: Duration) -> Option<u64> {
+    let millis = u64::try_from(duration.as_millis())
+        .ok()
+        .filter(|millis| *millis > 0)?;
+
+    Some(size * 8 * 1000 / millis)
+}
+
+/// Resolve the URI relative to the playlist
+fn join_url(playlist: &Url, uri: &str) -> Result<Url, SourceError> {
+    playlist
+        .join(uri)
+        .map_err(|err| SourceError::ParseError(Message::ParseError(&err).to_string()))
+}
+
+fn empty_playlist(url: &Url) -> SourceError {
+    SourceError::ParseError(Message::EmptyPlaylist(url.as_str()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{http, MockServer};
+
+    const MEDIA: &str = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n\
+        #EXTINF:10.0,\nseg1.ts\n#EXTINF:10.0,\nseg2.ts\n#EXTINF:10.0,\nseg3.ts\n#EXT-X-ENDLIST\n";
+    const MASTER: &str = "#EXTM3U\n\
+        #EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=1280x720,CODECS=\"avc1.64001f\"\nhd/index.m3u8\n";
+
+    /// Serve playlists of the path and segments of 125 000 bytes, i.e. of 100 kbit/s for 10 seconds
+    fn serve() -> MockServer {
+        MockServer::start(|request| {
+            let path = request.path.as_str();
+            match path {
+                "/good.m3u8" | "/hd/index.m3u8" => (200, MEDIA.to_string()),
+                "/master.m3u8" => (200, MASTER.to_string()),
+                "/empty.m3u8" => (200, String::from("#EXTM3U\n#EXT-X-ENDLIST\n")),
+                "/malformed.m3u8" => (200, String::from("<html>Not found</html>")),
+                "/missing-segments.m3u8" => (200, MEDIA.replace("seg", "missing")),
+                _ if path.ends_with(".ts") && !path.contains("missing") => {
+                    (200, "x".repeat(125_000))
+                }
+                _ => (404, String::new()),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn good_playlist_passes_and_first_segments_are_probed() {
+        let server = serve();
+        let check = StreamCheck::new(http());
+
+        check
+            .check(&format!("{}/good.m3u8", server.url()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            server.log(),
+            ["GET /good.m3u8", "GET /seg1.ts", "GET /seg2.ts"]
+        );
+        let range = &server.requests()[1].headers["range"];
+        assert_eq!(range, "bytes=0-0");
+    }
+
+    #[tokio::test]
+    async fn missing_playlist_fails() {
+        let server = serve();
+        let err = StreamCheck::new(http())
+            .check(&format!("{}/gone.m3u8", server.url()))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("404"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn missing_segment_fails() {
+        let server = serve();
+        let err = StreamCheck::new(http())
+            .check(&format!("{}/missing-segments.m3u8", server.url()))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("404"), "{err}");
+        assert_eq!(server.log().last().unwrap(), "GET /missing1.ts");
+    }
+
+    #[tokio::test]
+    async fn empty_playlist_fails() {
+        let server = serve();
+        let url = format!("{}/empty.m3u8", server.url());
+        let err = StreamCheck::new(http()).check(&url).await.unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            SourceError::ParseError(Message::EmptyPlaylist(&url).to_string()).to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_playlist_fails() {
+        let server = serve();
+        let err = StreamCheck::new(http())
+            .check(&format!("{}/malformed.m3u8", server.url()))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SourceError::ParseError(_)));
+    }
+
+    #[tokio::test]
+    async fn master_playlist_is_described_by_its_first_variant() {
+        let server = serve();
+        let info = StreamCheck::new(http())
+            .info(&format!("{}/master.m3u8", server.url()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(info.resolution, Some((1280, 720)));
+        assert_eq!(info.bandwidth, Some(800_000));
+        assert_eq!(info.duration, Some(Duration::from_secs(30)));
+        assert_eq!(info.size, Some(3_000_000));
+        assert_eq!(server.log(), ["GET /master.m3u8", "GET /hd/index.m3u8"]);
+    }
+
+    #[tokio::test]
+    async fn bitrate_of_media_playlist_is_estimated_by_first_segment() {
+        let server = serve();
+        let info = StreamCheck::new(http())
+            .info(&format!("{}/good.m3u8", server.url()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(info.bandwidth, Some(100_000));
+        assert_eq!(info.size, Some(375_000));
+    }
+
+    #[tokio::test]
+    async fn disabled_check_accepts_everything() {
+        let check = StreamCheck::disabled();
+
+        check.check("http://127.0.0.1:9/gone.m3u8").await.unwrap();
+        assert!(check.info("not a url").await.unwrap().is_none());
+    }
+}
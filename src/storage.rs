@@ -0,0 +1,49 @@
+pub mod accounts;
+pub mod downloads;
+pub mod history;
+pub mod seen;
+pub mod watchlist;
+
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Get path of a file in the user data directory,
+/// e.g. `~/.local/share/ani_cli_rs/history.json` on Linux
+#[must_use]
+pub fn data_path(file_name: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(file_name))
+}
+
+/// Get path of a file or a directory in the user cache directory,
+/// e.g. `~/.cache/ani_cli_rs/http` on Linux
+#[must_use]
+pub fn cache_path(name: &str) -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(name))
+}
+
+/// Read a JSON file
+/// # Returns
+/// * `Ok(None)` - If the file doesn't exist
+pub fn read_json(path: &Path) -> io::Result<Option<Value>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Write a JSON file, creating parent directories if needed. \
+/// The content is written to a temporary file first,
+/// so the file isn't corrupted if the program is interrupted
+pub fn write_json(path: &Path, value: &Value) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_string_pretty(value)?)?;
+    fs::rename(tmp_path, path)
+}
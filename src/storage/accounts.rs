@@ -0,0 +1,82 @@
+use super::{data_path, read_json, write_json};
+
+use serde_json::{Map, Value};
+use std::{io, path::PathBuf};
+
+/// Credentials of linked accounts of services, e.g. tokens of a tracker, by names of the services. \
+/// The file is readable only by the user on Unix
+pub struct Accounts {
+    path: Option<PathBuf>,
+    accounts: Map<String, Value>,
+}
+
+impl Accounts {
+    /// Create empty accounts that are saved to the file by `path`, `None` to keep them in memory
+    #[must_use]
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            accounts: Map::new(),
+        }
+    }
+
+    /// Load accounts from the user data directory
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(data_path("accounts.json"))
+    }
+
+    /// Load accounts from the file by `path`, `None` to keep them in memory
+    pub fn load_from(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut accounts = Self::new(path);
+
+        let Some(path) = &accounts.path else {
+            return Ok(accounts);
+        };
+
+        if let Some(Value::Object(known)) = read_json(path)? {
+            accounts.accounts = known;
+        }
+
+        Ok(accounts)
+    }
+
+    /// Get credentials of the service, `None` if the account isn't linked
+    #[must_use]
+    pub fn get(&self, service: &str) -> Option<&Value> {
+        self.accounts.get(service)
+    }
+
+    /// Set credentials of the service and save accounts
+    pub fn set(&mut self, service: &str, credentials: Value) -> io::Result<()> {
+        self.accounts.insert(service.to_string(), credentials);
+        self.save()
+    }
+
+    /// Remove credentials of the service and save accounts
+    /// # Returns
+    /// `false` if the account isn't linked
+    pub fn remove(&mut self, service: &str) -> io::Result<bool> {
+        if self.accounts.remove(service).is_none() {
+            return Ok(false);
+        }
+
+        self.save().map(|()| true)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        write_json(path, &Value::Object(self.accounts.clone()))?;
+
+        #[cfg(unix)]
+        {
+            use std::{fs, os::unix::fs::PermissionsExt as _};
+
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use super::{data_path, read_json, write_json};
+
+use serde_json::{json, Value};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub struct Entry {
+    pub source: String,     // "Anilibria"
+    pub anime: String,      // "Девочка-волшебница Мадока★Магика | Mahou Shoujo Madoka★Magica"
+    pub episode: String,    // "1"
+    pub quality: String,    // "hd"
+    pub path: PathBuf,      // "/home/user/Videos/Anime/Madoka/01.ts"
+    pub size: u64,          // Size in bytes
+    pub downloaded_at: u64, // Unix time in seconds
+}
+
+/// State of the file of a downloaded episode
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FileState {
+    Ok,
+    Missing,
+    /// The file has another size than the downloaded one, e.g. it's truncated
+    SizeMismatch(u64),
+}
+
+impl Entry {
+    /// Check that the file of the episode exists and has the downloaded size
+    pub fn check(&self) -> io::Result<FileState> {
+        match fs::metadata(&self.path) {
+            Ok(metadata) if metadata.len() == self.size => Ok(FileState::Ok),
+            Ok(metadata) => Ok(FileState::SizeMismatch(metadata.len())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(FileState::Missing),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Downloaded episodes
+pub struct Downloads {
+    path: Option<PathBuf>,
+    entries: Vec<Entry>,
+}
+
+impl Downloads {
+    /// Create an empty index that is saved to the file by `path`, `None` to keep it in memory
+    #[must_use]
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Load the index from the user data directory
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(data_path("downloads.json"))
+    }
+
+    /// Load the index from the file by `path`, `None` to keep it in memory
+    pub fn load_from(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut downloads = Self::new(path);
+
+        let Some(path) = &downloads.path else {
+            return Ok(downloads);
+        };
+
+        if let Some(Value::Array(entries)) = read_json(path)? {
+            downloads.entries = entries
+                .iter()
+                .filter_map(|entry| {
+                    Some(Entry {
+                        source: entry["source"].as_str()?.to_string(),
+                        anime: entry["anime"].as_str()?.to_string(),
+                        episode: entry["episode"].as_str()?.to_string(),
+                        quality: entry["quality"].as_str().unwrap_or_default().to_string(),
+                        path: PathBuf::from(entry["path"].as_str()?),
+                        size: entry["size"].as_u64().unwrap_or_default(),
+                        downloaded_at: entry["downloaded_at"].as_u64().unwrap_or_default(),
+                    })
+                })
+                .collect();
+        }
+
+        Ok(downloads)
+    }
+
+    /// Get entries in the order they were downloaded
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub fn find(&self, source: &str, anime: &str, episode: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| {
+            entry.source == source && entry.anime == anime && entry.episode == episode
+        })
+    }
+
+    /// Find the file of the downloaded episode
+    /// # Returns
+    /// `None` if the episode isn't downloaded or its file is missing or damaged
+    #[must_use]
+    pub fn find_file(&self, source: &str, anime: &str, episode: &str) -> Option<&Path> {
+        self.find(source, anime, episode)
+            .filter(|entry| matches!(entry.check(), Ok(FileState::Ok)))
+            .map(|entry| entry.path.as_path())
+    }
+
+    /// Add the downloaded episode, replacing an earlier download of it, and save the index
+    pub fn add(&mut self, entry: Entry) -> io::Result<()> {
+        self.entries.retain(|known| {
+            !(known.source == entry.source
+                && known.anime == entry.anime
+                && known.episode == entry.episode)
+        });
+        self.entries.push(entry);
+
+        self.save()
+    }
+
+    /// Remove the episode from the index and save it, the file isn't removed
+    /// # Returns
+    /// The removed entry, `None` if the episode isn't downloaded
+    pub fn remove(
+        &mut self,
+        source: &str,
+        anime: &str,
+        episode: &str,
+    ) -> io::Result<Option<Entry>> {
+        let Some(index) = self.entries.iter().position(|entry| {
+            entry.source == source && entry.anime == anime && entry.episode == episode
+        }) else {
+            return Ok(None);
+        };
+
+        let entry = self.entries.remove(index);
+        self.save().map(|()| Some(entry))
+    }
+
+    /// Remove the file of the episode and the episode from the index. \
+    /// A file that is already missing isn't an error
+    /// # Returns
+    /// The removed entry, `None` if the episode isn't downloaded
+    pub fn delete(
+        &mut self,
+        source: &str,
+        anime: &str,
+        episode: &str,
+    ) -> io::Result<Option<Entry>> {
+        let Some(entry) = self.find(source, anime, episode) else {
+            return Ok(None);
+        };
+
+        match fs::remove_file(&entry.path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        self.remove(source, anime, episode)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "source": entry.source,
+                    "anime": entry.anime,
+                    "episode": entry.episode,
+                    "quality": entry.quality,
+                    "path": entry.path,
+                    "size": entry.size,
+                    "downloaded_at": entry.downloaded_at,
+                })
+            })
+            .collect();
+
+        write_json(path, &Value::Array(entries))
+    }
+}
@@ -0,0 +1,115 @@
+use super::{data_path, read_json, write_json};
+
+use serde_json::{json, Value};
+use std::{
+    io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub struct Entry {
+    pub source: String,  // "Anilibria"
+    pub anime: String,   // "Девочка-волшебница Мадока★Магика | Mahou Shoujo Madoka★Magica"
+    pub episode: String, // "1"
+    pub watched_at: u64, // Unix time in seconds
+}
+
+/// Watched episodes
+pub struct History {
+    path: Option<PathBuf>,
+    entries: Vec<Entry>,
+}
+
+impl History {
+    /// Create an empty history that is saved to the file by `path`, `None` to keep it in memory
+    #[must_use]
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Load the history from the user data directory
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(data_path("history.json"))
+    }
+
+    /// Load the history from the file by `path`, `None` to keep it in memory
+    pub fn load_from(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut history = Self::new(path);
+
+        let Some(path) = &history.path else {
+            return Ok(history);
+        };
+
+        if let Some(Value::Array(entries)) = read_json(path)? {
+            history.entries = entries
+                .iter()
+                .filter_map(|entry| {
+                    Some(Entry {
+                        source: entry["source"].as_str()?.to_string(),
+                        anime: entry["anime"].as_str()?.to_string(),
+                        episode: entry["episode"].as_str()?.to_string(),
+                        watched_at: entry["watched_at"].as_u64().unwrap_or_default(),
+                    })
+                })
+                .collect();
+        }
+
+        Ok(history)
+    }
+
+    /// Get entries from the earliest watched to the last watched
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub fn is_watched(&self, source: &str, anime: &str, episode: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.source == source && entry.anime == anime && entry.episode == episode)
+    }
+
+    /// Mark the episode as watched and save the history. \
+    /// If the episode is already watched, it's moved to the end of the history
+    pub fn add(&mut self, source: &str, anime: &str, episode: &str) -> io::Result<()> {
+        self.entries.retain(|entry| {
+            !(entry.source == source && entry.anime == anime && entry.episode == episode)
+        });
+        self.entries.push(Entry {
+            source: source.to_string(),
+            anime: anime.to_string(),
+            episode: episode.to_string(),
+            watched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        });
+
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "source": entry.source,
+                    "anime": entry.anime,
+                    "episode": entry.episode,
+                    "watched_at": entry.watched_at,
+                })
+            })
+            .collect();
+
+        write_json(path, &Value::Array(entries))
+    }
+}
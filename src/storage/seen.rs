@@ -0,0 +1,125 @@
+use super::{data_path, read_json, write_json};
+
+use serde_json::{json, Value};
+use std::{io, path::PathBuf};
+
+pub struct Entry {
+    pub source: String,        // "Anilibria"
+    pub anime: String,         // "Девочка-волшебница Мадока★Магика | Mahou Shoujo Madoka★Magica"
+    pub episodes: Vec<String>, // ["1", "2"]
+    pub checked_at: u64,       // Unix time in seconds
+}
+
+/// Episodes of anime known by the new episode watcher,
+/// so episodes are notified only once between restarts
+pub struct SeenEpisodes {
+    path: Option<PathBuf>,
+    entries: Vec<Entry>,
+}
+
+impl SeenEpisodes {
+    /// Create an empty state that is saved to the file by `path`, `None` to keep it in memory
+    #[must_use]
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Load the state from the user data directory
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(data_path("seen_episodes.json"))
+    }
+
+    /// Load the state from the file by `path`, `None` to keep it in memory
+    pub fn load_from(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut seen = Self::new(path);
+
+        let Some(path) = &seen.path else {
+            return Ok(seen);
+        };
+
+        if let Some(Value::Array(entries)) = read_json(path)? {
+            seen.entries = entries
+                .iter()
+                .filter_map(|entry| {
+                    Some(Entry {
+                        source: entry["source"].as_str()?.to_string(),
+                        anime: entry["anime"].as_str()?.to_string(),
+                        episodes: entry["episodes"]
+                            .as_array()?
+                            .iter()
+                            .filter_map(|episode| episode.as_str().map(ToString::to_string))
+                            .collect(),
+                        checked_at: entry["checked_at"].as_u64().unwrap_or_default(),
+                    })
+                })
+                .collect();
+        }
+
+        Ok(seen)
+    }
+
+    /// Get known episodes of the anime
+    /// # Returns
+    /// `None` if the anime wasn't checked yet
+    #[must_use]
+    pub fn episodes(&self, source: &str, anime: &str) -> Option<&[String]> {
+        self.entries
+            .iter()
+            .find(|entry| entry.source == source && entry.anime == anime)
+            .map(|entry| entry.episodes.as_slice())
+    }
+
+    /// Remember episodes of the anime and save the state
+    /// # Arguments
+    /// * `checked_at` - Unix time in seconds
+    pub fn update(
+        &mut self,
+        source: &str,
+        anime: &str,
+        episodes: Vec<String>,
+        checked_at: u64,
+    ) -> io::Result<()> {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.source == source && entry.anime == anime)
+        {
+            Some(entry) => {
+                entry.episodes = episodes;
+                entry.checked_at = checked_at;
+            }
+            None => self.entries.push(Entry {
+                source: source.to_string(),
+                anime: anime.to_string(),
+                episodes,
+                checked_at,
+            }),
+        }
+
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "source": entry.source,
+                    "anime": entry.anime,
+                    "episodes": entry.episodes,
+                    "checked_at": entry.checked_at,
+                })
+            })
+            .collect();
+
+        write_json(path, &Value::Array(entries))
+    }
+}
@@ -0,0 +1,128 @@
+use super::{data_path, read_json, write_json};
+
+use serde_json::{json, Value};
+use std::{
+    io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub struct Entry {
+    pub source: String, // "Anilibria"
+    pub anime: String,  // "Девочка-волшебница Мадока★Магика | Mahou Shoujo Madoka★Magica"
+    pub added_at: u64,  // Unix time in seconds
+}
+
+/// Anime the user is going to watch
+pub struct Watchlist {
+    path: Option<PathBuf>,
+    entries: Vec<Entry>,
+}
+
+impl Watchlist {
+    /// Create an empty watchlist that is saved to the file by `path`, `None` to keep it in memory
+    #[must_use]
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Load the watchlist from the user data directory
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(data_path("watchlist.json"))
+    }
+
+    /// Load the watchlist from the file by `path`, `None` to keep it in memory
+    pub fn load_from(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut watchlist = Self::new(path);
+
+        let Some(path) = &watchlist.path else {
+            return Ok(watchlist);
+        };
+
+        if let Some(Value::Array(entries)) = read_json(path)? {
+            watchlist.entries = entries
+                .iter()
+                .filter_map(|entry| {
+                    Some(Entry {
+                        source: entry["source"].as_str()?.to_string(),
+                        anime: entry["anime"].as_str()?.to_string(),
+                        added_at: entry["added_at"].as_u64().unwrap_or_default(),
+                    })
+                })
+                .collect();
+        }
+
+        Ok(watchlist)
+    }
+
+    /// Get entries in the order they were added
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub fn contains(&self, source: &str, anime: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.source == source && entry.anime == anime)
+    }
+
+    /// Add the anime and save the watchlist
+    /// # Returns
+    /// `false` if the anime is already in the watchlist
+    pub fn add(&mut self, source: &str, anime: &str) -> io::Result<bool> {
+        if self.contains(source, anime) {
+            return Ok(false);
+        }
+
+        self.entries.push(Entry {
+            source: source.to_string(),
+            anime: anime.to_string(),
+            added_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        });
+
+        self.save().map(|()| true)
+    }
+
+    /// Remove the anime and save the watchlist
+    /// # Returns
+    /// `false` if the anime isn't in the watchlist
+    pub fn remove(&mut self, source: &str, anime: &str) -> io::Result<bool> {
+        let len = self.entries.len();
+        self.entries
+            .retain(|entry| !(entry.source == source && entry.anime == anime));
+
+        if self.entries.len() == len {
+            return Ok(false);
+        }
+
+        self.save().map(|()| true)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "source": entry.source,
+                    "anime": entry.anime,
+                    "added_at": entry.added_at,
+                })
+            })
+            .collect();
+
+        write_json(path, &Value::Array(entries))
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{
+    download::{
+        ffmpeg::{self, Container, Metadata},
+        file_name, Download, Downloader,
+    },
+    errors::SourceError,
+    locale::Message,
+    sources::{base::Source, details::EpisodeDetails},
+    storage::downloads::{self, Downloads},
+};
+
+use futures::{stream, StreamExt as _};
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Extensions of downloaded files, see [`Downloader::download`]
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "webm"];
+
+/// Rule of `sync`, e.g. download new episodes of Madoka in hd to `~/Anime/Madoka` and keep the last 3
+#[derive(Clone)]
+pub struct SyncRule {
+    pub source: String,
+    /// Search query and the title of the anime
+    pub anime: String,
+    /// `None` for the highest quality
+    pub quality: Option<String>,
+    pub dir: PathBuf,
+    /// Count of the last episodes kept on disk, `None` to keep all episodes
+    pub keep: Option<usize>,
+}
+
+/// Settings of the `sync` command
+#[derive(Clone)]
+pub struct SyncConfig {
+    pub rules: Vec<SyncRule>,
+    /// Count of episodes downloaded at the same time
+    pub concurrency: usize,
+    /// Limit of the total download speed in bytes per second
+    pub bandwidth: Option<u64>,
+    /// Container to remux downloaded episodes into with ffmpeg, `None` to keep them as downloaded
+    pub remux: Option<Container>,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            concurrency: 2,
+            bandwidth: None,
+            remux: None,
+        }
+    }
+}
+
+/// Counts of episodes processed by [`run`]
+#[derive(Default)]
+pub struct Report {
+    pub downloaded: usize,
+    pub failed: usize,
+    pub removed: usize,
+}
+
+/// Episode to download
+struct Job {
+    source: String,
+    anime: String,
+    episode: String,
+    quality: String,
+    url: String,
+    /// Path of the file without the extension
+    path: PathBuf,
+    details: EpisodeDetails,
+}
+
+/// Episodes of a rule to keep on disk
+struct Retention<'a> {
+    source: String,
+    /// Title of the anime, other anime of the directory aren't removed
+    anime: String,
+    dir: &'a Path,
+    episodes: Vec<String>,
+}
+
+/// Download missing episodes of the rules, then remove episodes that exceed `keep` of the rules. \
+/// Progress is printed, episodes are added to the downloads index
+pub async fn run<S: Source>(
+    sources: &[S],
+    config: &SyncConfig,
+    downloader: &Downloader,
+    downloads: &mut Downloads,
+) -> Report {
+    let mut report = Report::default();
+    let mut jobs = Vec::new();
+    let mut retentions = Vec::new();
+
+    for rule in &config.rules {
+        match plan(sources, rule, &mut jobs, &mut report).await {
+            Ok(Some(retention)) => retentions.push(retention),
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!("{}", Message::CantSyncRule(&rule.anime, &err));
+                report.failed += 1;
+            }
+        }
+    }
+
+    // Episodes are kept as downloaded without ffmpeg
+    let remux = config.remux.filter(|_| {
+        let installed = ffmpeg::is_installed();
+        if !installed {
+            eprintln!("{}", Message::FfmpegNotInstalled);
+        }
+        installed
+    });
+
+    let mut queue = stream::iter(jobs)
+        .map(|job| async move {
+            println!(
+                "{}",
+                Message::Downloading(&job.anime, &job.episode, &job.quality)
+            );
+            let result = match (downloader.download(&job.url, &job.path).await, remux) {
+                (Ok(download), Some(container)) => {
+                    Ok(remux_download(&job, download, container).await)
+                }
+                (result, _) => result,
+            };
+            (job, result)
+        })
+        .buffer_unordered(config.concurrency.max(1));
+
+    while let Some((job, result)) = queue.next().await {
+        match result {
+            Ok(Download { path, size, .. }) => {
+                println!(
+                    "{}",
+                    Message::Downloaded(&job.anime, &job.episode, &(size / 1_000_000))
+                );
+                report.downloaded += 1;
+
+                let entry = downloads::Entry {
+                    source: job.source,
+                    anime: job.anime,
+                    episode: job.episode,
+                    quality: job.quality,
+                    path,
+                    size,
+                    downloaded_at: now(),
+                };
+                if let Err(err) = downloads.add(entry) {
+                    eprintln!("{}", Message::CantSaveDownloads(&err));
+                }
+            }
+            Err(err) => {
+                eprintln!("{}", Message::CantDownload(&job.anime, &job.episode, &err));
+                report.failed += 1;
+            }
+        }
+    }
+    drop(queue);
+
+    for retention in retentions {
+        report.removed += remove_old(downloads, &retention);
+    }
+
+    report
+}
+
+/// Queue missing episodes of the rule
+/// # Returns
+/// Episodes to keep if the rule limits them
+async fn plan<'a, S: Source>(
+    sources: &[S],
+    rule: &'a SyncRule,
+    jobs: &mut Vec<Job>,
+    report: &mut Report,
+) -> Result<Option<Retention<'a>>, SourceError> {
+    let mut source = sources
+        .iter()
+        .find(|source| **source == rule.source)
+        .cloned()
+        .ok_or_else(|| {
+            SourceError::UnknownVariant(Message::UnknownSource(&rule.source).to_string())
+        })?;
+
+    source.search_anime_list(&rule.anime).await?;
+    source.select_anime_as_current(rule.anime.clone()).await?;
+    let anime = source.anime_info()?.to_string();
+
+    let mut episodes = source.episodes()?;
+    if let Some(keep) = rule.keep {
+        episodes.drain(..episodes.len().saturating_sub(keep));
+    }
+
+    for episode in &episodes {
+        // Files are checked instead of the index, so titles with a changing suffix aren't downloaded again
+        let path = rule.dir.join(file_stem(episode));
+        if find_file(&path).is_some() {
+            continue;
+        }
+
+        let result = async {
+            source.select_episode_as_current(episode.clone()).await?;
+            source.select_quality_as_current(
+                rule.quality.clone().unwrap_or_else(|| String::from("max")),
+            )?;
+            let quality = source.quality_info()?.to_string();
+            let url = source.url_for_stream().await?;
+            Ok::<_, SourceError>((quality, url))
+        }
+        .await;
+
+        match result {
+            Ok((quality, url)) => jobs.push(Job {
+                source: source.to_string(),
+                anime: anime.clone(),
+                episode: episode.clone(),
+                quality,
+                url,
+                path,
+                details: source.episode_details(),
+            }),
+            // Other episodes may be available
+            Err(err) => {
+                eprintln!("{}", Message::CantDownload(&anime, episode, &err));
+                report.failed += 1;
+            }
+        }
+    }
+
+    Ok(rule.keep.map(|_| Retention {
+        source: source.to_string(),
+        anime,
+        dir: &rule.dir,
+        episodes,
+    }))
+}
+
+/// Remux the downloaded episode with its metadata
+/// # Returns
+/// The remuxed file, the downloaded file if ffmpeg fails
+async fn remux_download(job: &Job, download: Download, container: Container) -> Download {
+    let metadata = Metadata {
+        anime: &job.anime,
+        episode: &job.episode,
+        source: &job.source,
+        details: &job.details,
+        duration: download.duration,
+    };
+
+    match ffmpeg::remux(&download.path, container, &metadata).await {
+        Ok((path, size)) => Download {
+            path,
+            size,
+            ..download
+        },
+        Err(err) => {
+            eprintln!("{}", Message::CantRemux(&job.anime, &job.episode, &err));
+            download
+        }
+    }
+}
+
+/// Remove downloaded episodes of the anime of the rule in its directory that aren't kept
+/// # Returns
+/// Count of removed episodes
+fn remove_old(downloads: &mut Downloads, retention: &Retention) -> usize {
+    let old: Vec<String> = downloads
+        .entries()
+        .iter()
+        .filter(|entry| {
+            entry.source == retention.source
+                && entry.anime == retention.anime
+                && entry.path.starts_with(retention.dir)
+                && !retention.episodes.contains(&entry.episode)
+        })
+        .map(|entry| entry.episode.clone())
+        .collect();
+
+    let anime = &retention.anime;
+    let mut removed = 0;
+    for episode in old {
+        match downloads.delete(&retention.source, anime, &episode) {
+            Ok(Some(entry)) => {
+                println!("{}", Message::RemovedDownload(&entry.path.display()));
+                removed += 1;
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("{}", Message::CantDeleteDownload(anime, &episode, &err)),
+        }
+    }
+
+    removed
+}
+
+/// Name of the file of the episode without the extension,
+/// numbers are padded so files are sorted by name, e.g. `01`
+#[must_use]
+pub fn file_stem(episode: &str) -> String {
+    if episode.chars().all(|char| char.is_ascii_digit()) {
+        return format!("{episode:0>2}");
+    }
+
+    // Dots would be taken for the extension
+    file_name(episode).replace('.', "_")
+}
+
+/// Find the downloaded file by the path without the extension
+fn find_file(path: &Path) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|extension| path.with_extension(extension))
+        .find(|path| path.is_file())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_dir, MockSource};
+
+    use std::fs;
+
+    fn rule(anime: &str, dir: &Path, keep: Option<usize>) -> SyncRule {
+        SyncRule {
+            source: String::from("Mock"),
+            anime: anime.to_string(),
+            quality: Some(String::from("720p")),
+            dir: dir.to_path_buf(),
+            keep,
+        }
+    }
+
+    fn entry(anime: &str, episode: &str, path: PathBuf) -> downloads::Entry {
+        fs::write(&path, "video").unwrap();
+        downloads::Entry {
+            source: String::from("Mock"),
+            anime: anime.to_string(),
+            episode: episode.to_string(),
+            quality: String::from("720p"),
+            path,
+            size: 5,
+            downloaded_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn last_missing_episodes_are_planned() {
+        let dir = temp_dir("sync_plan");
+        fs::write(dir.join("03.ts"), "video").unwrap();
+        let sources = [MockSource::new("Mock", "http://stream.test")
+            .with_anime("Madoka", &["1", "2", "3", "4"])];
+
+        let last_two = rule("Madoka", &dir, Some(2));
+        let mut jobs = Vec::new();
+        let mut report = Report::default();
+        let retention = plan(&sources, &last_two, &mut jobs, &mut report)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The third episode is already downloaded
+        let planned = jobs
+            .iter()
+            .map(|job| (job.episode.as_str(), job.url.as_str(), job.path.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            planned,
+            [("4", "http://stream.test/1/4/720p.m3u8", dir.join("04"))]
+        );
+        assert_eq!(retention.anime, "Madoka");
+        assert_eq!(retention.episodes, ["3", "4"]);
+        assert_eq!(report.failed, 0);
+
+        // Without `keep` all episodes are downloaded and kept
+        let all = rule("Madoka", &dir, None);
+        let mut jobs = Vec::new();
+        let retention = plan(&sources, &all, &mut jobs, &mut report).await.unwrap();
+        assert!(retention.is_none());
+        assert_eq!(jobs.len(), 3);
+    }
+
+    #[test]
+    fn old_episodes_of_other_anime_of_the_directory_are_kept() {
+        let dir = temp_dir("sync_remove_old");
+        let mut downloads = Downloads::new(None);
+        for (anime, episode, file) in [
+            ("Madoka", "1", "madoka_01.ts"),
+            ("Madoka", "2", "madoka_02.ts"),
+            ("Madoka", "3", "madoka_03.ts"),
+            ("Evangelion", "1", "evangelion_01.ts"),
+        ] {
+            downloads
+                .add(entry(anime, episode, dir.join(file)))
+                .unwrap();
+        }
+
+        let retention = Retention {
+            source: String::from("Mock"),
+            anime: String::from("Madoka"),
+            dir: &dir,
+            episodes: vec![String::from("2"), String::from("3")],
+        };
+        assert_eq!(remove_old(&mut downloads, &retention), 1);
+
+        assert!(!dir.join("madoka_01.ts").exists());
+        assert!(dir.join("madoka_02.ts").exists());
+        assert!(dir.join("evangelion_01.ts").exists());
+        let kept = downloads
+            .entries()
+            .iter()
+            .map(|entry| (entry.anime.as_str(), entry.episode.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kept,
+            [("Madoka", "2"), ("Madoka", "3"), ("Evangelion", "1")]
+        );
+    }
+
+    #[test]
+    fn file_stems_are_sorted_by_name() {
+        assert_eq!(file_stem("1"), "01");
+        assert_eq!(file_stem("12"), "12");
+        assert_eq!(file_stem("123"), "123");
+        assert_eq!(file_stem("6.5"), "6_5");
+        assert_eq!(file_stem(" OVA: 1/2 "), "OVA_ 1_2");
+    }
+}
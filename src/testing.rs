@@ -0,0 +1,411 @@
+//! Helpers of the tests: an in-memory source and a local HTTP server
+
+use crate::{
+    enums::language::Language,
+    errors::SourceError,
+    locale::Message,
+    sources::{
+        base::{find_in_anime_list, Source},
+        http::{Http, HttpConfig},
+        mirrors::Mirrors,
+        ru::anilibria::Anilibria,
+    },
+};
+
+use hyper::{
+    header::HeaderMap,
+    service::{make_service_fn, service_fn},
+    Body, Method, Response, Server,
+};
+use serde_json::{json, Map, Value};
+use std::{
+    cell::RefCell,
+    convert::Infallible,
+    env,
+    fmt::{self, Display},
+    fs,
+    path::PathBuf,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// HTTP client without rate limiting, so tests don't wait between requests
+pub fn http() -> Http {
+    Http::new(&HttpConfig {
+        rate_limit: None,
+        ..HttpConfig::default()
+    })
+    .unwrap()
+}
+
+/// Create an empty directory for files of a test
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!(
+        "{}-{name}-{pid}",
+        env!("CARGO_PKG_NAME"),
+        pid = std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// Anilibria requesting the API of the server, e.g. `GET /v2/searchTitles`
+pub fn anilibria(server: &MockServer) -> Anilibria<'static> {
+    Anilibria::new(http()).with_api_urls(Mirrors::new(
+        "anilibria/api",
+        vec![format!("{}/v2", server.url())],
+        None,
+    ))
+}
+
+/// Anime of the Anilibria API, its title is `ru | en`
+/// # Arguments
+/// * `episodes` - Count of episodes, numbered from `1`
+pub fn anilibria_anime(id: u64, ru: &str, en: &str, episodes: u16) -> Value {
+    let playlist = (1..=episodes)
+        .map(|serie| {
+            let hls = json!({
+                "fhd": null,
+                "hd": format!("/videos/media/ts/{id}/{serie}/720/index.m3u8"),
+                "sd": format!("/videos/media/ts/{id}/{serie}/480/index.m3u8"),
+            });
+            (serie.to_string(), json!({ "serie": serie, "hls": hls }))
+        })
+        .collect::<Map<_, _>>();
+
+    json!({
+        "id": id,
+        "names": { "ru": ru, "en": en },
+        "player": {
+            "host": "cache.libria.fun",
+            "series": { "first": 1, "last": episodes, "string": format!("1-{episodes}") },
+            "playlist": playlist,
+        },
+    })
+}
+
+/// Request received by the [`MockServer`]
+#[derive(Clone)]
+pub struct Request {
+    pub method: Method,
+    /// Path with the query, e.g. `/v2/searchTitles?search=x`
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)
+    }
+}
+
+/// HTTP server on a free local port that answers with a handler and records requests
+pub struct MockServer {
+    url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    /// Start the server in the background of the current runtime
+    /// # Arguments
+    /// * `handler` - Returns the status and the body of the response to a request
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> (u16, String) + Send + Sync + 'static,
+    {
+        let handler = Arc::new(handler);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let make_service = {
+            let requests = Arc::clone(&requests);
+            make_service_fn(move |_| {
+                let handler = Arc::clone(&handler);
+                let requests = Arc::clone(&requests);
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request: hyper::Request<Body>| {
+                        let handler = Arc::clone(&handler);
+                        let requests = Arc::clone(&requests);
+                        async move {
+                            let (parts, body) = request.into_parts();
+                            let body = hyper::body::to_bytes(body).await.unwrap_or_default();
+                            let request = Request {
+                                method: parts.method,
+                                path: parts
+                                    .uri
+                                    .path_and_query()
+                                    .map_or_else(String::new, ToString::to_string),
+                                headers: parts.headers,
+                                body: String::from_utf8_lossy(&body).into_owned(),
+                            };
+
+                            let (status, body) = handler(&request);
+                            requests.lock().unwrap().push(request);
+
+                            Ok::<_, Infallible>(
+                                Response::builder()
+                                    .status(status)
+                                    .body(Body::from(body))
+                                    .unwrap(),
+                            )
+                        }
+                    }))
+                }
+            })
+        };
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let url = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+
+        Self { url, requests }
+    }
+
+    /// URL of the server without the trailing slash, e.g. `http://127.0.0.1:43210`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Received requests in order
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Received requests as `METHOD path`, e.g. `GET /v2/searchTitles?search=x`
+    pub fn log(&self) -> Vec<String> {
+        self.requests().iter().map(ToString::to_string).collect()
+    }
+}
+
+struct Anime {
+    title: String,
+    episodes: Vec<String>,
+}
+
+/// Source with anime kept in memory
+#[derive(Clone)]
+pub struct MockSource {
+    name: String,
+    language: Language,
+    /// Base of the stream URLs, e.g. the URL of a [`MockServer`]
+    stream_url: String,
+    anime: Rc<RefCell<Vec<Anime>>>,
+    /// Titles of the favorite anime, `None` if the user isn't logged in
+    favorites: Option<Vec<String>>,
+
+    found: Vec<usize>,
+    current_anime: Option<usize>,
+    current_episode: Option<String>,
+    current_quality: Option<String>,
+}
+
+impl MockSource {
+    /// Qualities of every episode
+    pub const QUALITIES: [&'static str; 2] = ["480p", "720p"];
+
+    pub fn new(name: &str, stream_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            language: Language::Russian,
+            stream_url: stream_url.to_string(),
+            anime: Rc::default(),
+            favorites: None,
+            found: Vec::new(),
+            current_anime: None,
+            current_episode: None,
+            current_quality: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_anime(self, title: &str, episodes: &[&str]) -> Self {
+        self.anime.borrow_mut().push(Anime {
+            title: title.to_string(),
+            episodes: episodes.iter().map(ToString::to_string).collect(),
+        });
+        self
+    }
+
+    /// Log in to an account with the favorite anime
+    #[must_use]
+    pub fn with_favorites(mut self, titles: &[&str]) -> Self {
+        self.favorites = Some(titles.iter().map(ToString::to_string).collect());
+        self
+    }
+
+    /// Release a new episode of the anime. \
+    /// Clones share the anime, so episodes added to one clone are found by the others
+    pub fn add_episode(&self, title: &str, episode: &str) {
+        let mut anime = self.anime.borrow_mut();
+        let anime = anime.iter_mut().find(|anime| anime.title == title).unwrap();
+        anime.episodes.push(episode.to_string());
+    }
+
+    fn current_index(&self) -> usize {
+        self.current_anime.expect("No anime selected")
+    }
+
+    fn title(&self, index: usize) -> String {
+        self.anime.borrow()[index].title.clone()
+    }
+}
+
+impl Display for MockSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl PartialEq<String> for MockSource {
+    fn eq(&self, other: &String) -> bool {
+        self.name.eq_ignore_ascii_case(other)
+    }
+}
+
+impl Source for MockSource {
+    type SearchAnimeListInfo = String;
+    type AnimeInfo = String;
+    type EpisodesInfo = String;
+    type EpisodeIndo = String;
+    type QualitiesInfo = String;
+    type QualityInfo = String;
+
+    fn language(&self) -> &Language {
+        &self.language
+    }
+
+    async fn search_anime_list(
+        &mut self,
+        query: &str,
+    ) -> Result<Self::SearchAnimeListInfo, SourceError> {
+        let query = query.to_lowercase();
+        self.found = self
+            .anime
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, anime)| anime.title.to_lowercase().contains(&query))
+            .map(|(index, _)| index)
+            .collect();
+
+        if self.found.is_empty() {
+            return Err(SourceError::ApiError(
+                Message::EmptyAnimeList(&query).to_string(),
+            ));
+        }
+
+        Ok(self
+            .anime_list()
+            .iter()
+            .enumerate()
+            .map(|(seq_num, title)| format!("\t{seq_num}. {title}\n", seq_num = seq_num + 1))
+            .collect())
+    }
+
+    async fn favorite_anime_list(
+        &mut self,
+    ) -> Result<Option<Self::SearchAnimeListInfo>, SourceError> {
+        let Some(favorites) = &self.favorites else {
+            return Ok(None);
+        };
+        self.found = self
+            .anime
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, anime)| favorites.contains(&anime.title))
+            .map(|(index, _)| index)
+            .collect();
+
+        Ok(Some(self.anime_list().join("\n")))
+    }
+
+    /// Select an anime by its sequence number or by a part of the title like the real sources
+    async fn select_anime_as_current(
+        &mut self,
+        title_or_seq_num: String,
+    ) -> Result<(), SourceError> {
+        let index = find_in_anime_list(&self.found, &title_or_seq_num, |index, name| {
+            self.title(*index).to_lowercase().contains(name)
+        })?;
+
+        self.current_anime = Some(*index);
+        self.current_episode = None;
+        self.current_quality = None;
+
+        Ok(())
+    }
+
+    fn anime_list(&self) -> Vec<String> {
+        self.found.iter().map(|index| self.title(*index)).collect()
+    }
+
+    fn anime_info(&self) -> Result<Self::AnimeInfo, SourceError> {
+        Ok(self.title(self.current_index()))
+    }
+
+    fn episodes_info(&mut self) -> Result<Self::EpisodesInfo, SourceError> {
+        Ok(format!("\n\t{}\n", self.episodes()?.join(", ")))
+    }
+
+    async fn select_episode_as_current(&mut self, episode: String) -> Result<(), SourceError> {
+        if !self.episodes()?.contains(&episode) {
+            return Err(SourceError::UnknownVariant(
+                Message::UnknownEpisodePattern(&episode).to_string(),
+            ));
+        }
+
+        self.current_episode = Some(episode);
+
+        Ok(())
+    }
+
+    fn episodes(&self) -> Result<Vec<String>, SourceError> {
+        let index = self.current_index();
+
+        Ok(self.anime.borrow()[index].episodes.clone())
+    }
+
+    fn episode_info(&self) -> Result<Self::EpisodeIndo, SourceError> {
+        Ok(self.current_episode.clone().expect("No episode selected"))
+    }
+
+    fn qualities_info(&mut self) -> Result<Self::QualitiesInfo, SourceError> {
+        Ok(Self::QUALITIES
+            .iter()
+            .map(|quality| format!("\t{quality}\n"))
+            .collect())
+    }
+
+    fn qualities(&self) -> Result<Vec<String>, SourceError> {
+        Ok(Self::QUALITIES.iter().map(ToString::to_string).collect())
+    }
+
+    fn select_quality_as_current(&mut self, quality: String) -> Result<(), SourceError> {
+        if !Self::QUALITIES.contains(&quality.as_str()) {
+            return Err(SourceError::UnknownVariant(
+                Message::UnknownQuality(&quality).to_string(),
+            ));
+        }
+
+        self.current_quality = Some(quality);
+
+        Ok(())
+    }
+
+    fn quality_info(&self) -> Result<Self::QualityInfo, SourceError> {
+        Ok(self.current_quality.clone().expect("No quality selected"))
+    }
+
+    async fn url_for_stream(&self) -> Result<String, SourceError> {
+        Ok(format!(
+            "{}/{}/{}/{}.m3u8",
+            self.stream_url,
+            self.current_index() + 1,
+            self.current_episode.as_ref().expect("No episode selected"),
+            self.current_quality.as_ref().expect("No quality selected"),
+        ))
+    }
+}
@@ -0,0 +1,52 @@
+pub mod import;
+pub mod matching;
+pub mod shikimori;
+
+use crate::{
+    errors::TrackerError,
+    sources::{details::AnimeDetails, ru::anilibria::schemas::Names},
+};
+
+/// Anime of a tracker
+#[derive(Clone)]
+pub struct TrackedAnime {
+    /// Identifier of the anime on the tracker
+    pub id: u64,
+    pub names: Names,
+}
+
+/// Service keeping the anime list of the user and the progress of watching, e.g. Shikimori. \
+/// Anime are matched by their Russian and English names like in [`Names`] of Anilibria
+// Futures of trackers aren't `Send` like futures of sources
+#[allow(async_fn_in_trait)]
+pub trait Tracker {
+    /// Get name of the service, e.g. `Shikimori`
+    fn name(&self) -> &str;
+
+    /// Find the anime with the same names
+    /// # Returns
+    /// `Ok(None)` if no anime of the tracker has the names
+    async fn find_anime(&self, names: &Names) -> Result<Option<TrackedAnime>, TrackerError>;
+
+    /// Set count of watched episodes of the anime if it's greater than the current one. \
+    /// The anime is added to the watching list if it isn't in the list of the user
+    /// # Returns
+    /// `true` if the progress is changed
+    async fn update_progress(&self, anime_id: u64, episodes: u32) -> Result<bool, TrackerError>;
+
+    /// Get anime of the watching list of the user
+    async fn watching(&self) -> Result<Vec<TrackedAnime>, TrackerError>;
+}
+
+/// Get names of the anime to find it on a tracker. \
+/// The title is used for both names if the original title is unknown
+#[must_use]
+pub fn names_of(details: &AnimeDetails) -> Names {
+    Names {
+        ru: details.title.clone(),
+        en: details
+            .original_title
+            .clone()
+            .unwrap_or_else(|| details.title.clone()),
+    }
+}
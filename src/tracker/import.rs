@@ -0,0 +1,175 @@
+use super::{matching::matches_title, Tracker};
+
+use crate::{
+    errors::TrackerError,
+    locale::Message,
+    sources::{base::Source, ru::anilibria::schemas::Names},
+    storage::watchlist::Watchlist,
+};
+
+/// Counts of anime processed by [`run`]
+#[derive(Default)]
+pub struct Report {
+    pub added: usize,
+    /// Anime that are already in the watchlist
+    pub known: usize,
+    pub not_found: usize,
+}
+
+/// Add anime of the watching list of the tracker to the watchlist. \
+/// Each anime is searched by its names in the sources in their order, the first source having it is used. \
+/// Progress is printed
+pub async fn run<T, S>(
+    tracker: &T,
+    sources: &[S],
+    watchlist: &mut Watchlist,
+) -> Result<Report, TrackerError>
+where
+    T: Tracker,
+    S: Source,
+{
+    let mut report = Report::default();
+
+    for anime in tracker.watching().await? {
+        let Some((source, title)) = find_in_sources(sources, &anime.names).await else {
+            eprintln!("{}", Message::NotFoundInSources(&anime.names));
+            report.not_found += 1;
+            continue;
+        };
+
+        if watchlist.add(&source, &title)? {
+            println!("{}", Message::Imported(&title, &source));
+            report.added += 1;
+        } else {
+            report.known += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Find the anime with the names in the sources
+/// # Returns
+/// Name of the source and the title of the anime like it's saved by the dialog
+async fn find_in_sources<S: Source>(sources: &[S], names: &Names) -> Option<(String, String)> {
+    let mut queries = vec![names.ru.trim(), names.en.trim()];
+    queries.dedup();
+
+    for source in sources {
+        let mut source = source.clone();
+
+        for query in queries.iter().filter(|query| !query.is_empty()) {
+            if let Err(err) = source.search_anime_list(query).await {
+                eprintln!("{source}: {err}");
+                break;
+            }
+
+            let Some(position) = source
+                .anime_list()
+                .iter()
+                .position(|title| matches_title(names, title))
+            else {
+                continue;
+            };
+
+            // Sources select anime by their names and not by whole titles, e.g. `ru | en`
+            if source
+                .select_anime_as_current((position + 1).to_string())
+                .await
+                .is_ok()
+            {
+                if let Ok(anime) = source.anime_info() {
+                    return Some((source.to_string(), anime.to_string()));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::accounts::Accounts,
+        testing::{anilibria, anilibria_anime, http, MockServer},
+        tracker::shikimori::{Shikimori, ShikimoriConfig, SERVICE},
+    };
+
+    use serde_json::json;
+    use std::{cell::RefCell, rc::Rc};
+
+    const TITLE: &str = "Девочка-волшебница Мадока★Магика | Mahou Shoujo Madoka★Magica";
+
+    /// Shikimori with the linked account and Anilibria on the same server
+    fn server() -> MockServer {
+        MockServer::start(|request| {
+            let body = if request.path.starts_with("/api/users/42/anime_rates?") {
+                json!([
+                    { "episodes": 3, "anime": { "id": 9756, "name": "Mahou Shoujo Madoka★Magica", "russian": "Девочка-волшебница Мадока★Магика" } },
+                    { "episodes": 1, "anime": { "id": 5081, "name": "Bakemonogatari", "russian": "Истории монстров" } },
+                ])
+            } else if request.path.starts_with("/v2/searchTitles?search=%D0%94") {
+                // The movie is found first by the Russian name of the series
+                json!([
+                    anilibria_anime(
+                        9001,
+                        "Девочка-волшебница Мадока★Магика: Бунт",
+                        "Mahou Shoujo Madoka★Magica Movie: Hangyaku no Monogatari",
+                        1,
+                    ),
+                    anilibria_anime(
+                        9000,
+                        "Девочка-волшебница Мадока★Магика",
+                        "Mahou Shoujo Madoka★Magica",
+                        12,
+                    ),
+                ])
+            } else {
+                json!([])
+            };
+            (200, body.to_string())
+        })
+    }
+
+    fn shikimori(server: &MockServer) -> Shikimori {
+        let mut accounts = Accounts::new(None);
+        let account = json!({
+            "user_id": 42,
+            "nickname": "homura",
+            "token": { "access_token": "access", "refresh_token": "refresh", "expires_at": u64::MAX },
+        });
+        accounts.set(SERVICE, account).unwrap();
+        let config = ShikimoriConfig {
+            url: server.url().to_string(),
+            client_id: Some(String::from("client")),
+            client_secret: Some(String::from("secret")),
+        };
+
+        Shikimori::new(config, http(), Rc::new(RefCell::new(accounts)))
+    }
+
+    #[tokio::test]
+    async fn watching_anime_are_added_to_watchlist() {
+        let server = server();
+        let shikimori = shikimori(&server);
+        let sources = [anilibria(&server)];
+        let mut watchlist = Watchlist::new(None);
+
+        let report = run(&shikimori, &sources, &mut watchlist).await.unwrap();
+
+        assert_eq!((report.added, report.known, report.not_found), (1, 0, 1));
+        let titles = watchlist
+            .entries()
+            .iter()
+            .map(|entry| (entry.source.as_str(), entry.anime.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(titles, [("Anilibria", TITLE)]);
+
+        // Anime of the watchlist aren't added again
+        let report = run(&shikimori, &sources, &mut watchlist).await.unwrap();
+        assert_eq!((report.added, report.known, report.not_found), (0, 1, 1));
+        assert_eq!(watchlist.entries().len(), 1);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::sources::ru::anilibria::schemas::Names;
+
+/// Check the names are of the same anime. \
+/// Names are compared without case, punctuation and spaces,
+/// a Russian name may also match an English one, e.g. if a source knows only one title
+#[must_use]
+pub fn is_same(names: &Names, other: &Names) -> bool {
+    let other = [normalize(&other.ru), normalize(&other.en)];
+
+    [normalize(&names.ru), normalize(&names.en)]
+        .iter()
+        .any(|name| !name.is_empty() && other.contains(name))
+}
+
+/// Check the title of a source is of the anime with the names. \
+/// Titles may consist of names in several languages, e.g. `ru | en` of Anilibria
+#[must_use]
+pub fn matches_title(names: &Names, title: &str) -> bool {
+    title.split(" | ").any(|name| {
+        is_same(
+            names,
+            &Names {
+                ru: name.to_string(),
+                en: String::new(),
+            },
+        )
+    })
+}
+
+/// Keep only letters and digits in lower case
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        // `ё` is often written as `е`
+        .map(|char| if char == 'ё' { 'е' } else { char })
+        .collect()
+}
@@ -0,0 +1,517 @@
+pub mod oauth;
+
+use super::{matching::is_same, TrackedAnime, Tracker};
+
+use crate::{
+    errors::TrackerError,
+    locale::Message,
+    sources::{http::Http, ru::anilibria::schemas::Names},
+    storage::accounts::Accounts,
+};
+
+use oauth::Token;
+use reqwest::{header::CONTENT_TYPE, Method};
+use serde_json::{json, Value};
+use std::{cell::RefCell, rc::Rc};
+
+/// Name of the service in the accounts and in the config file
+pub const SERVICE: &str = "shikimori";
+
+/// Settings of Shikimori, e.g. `"shikimori": { "client_id": "...", "client_secret": "..." }`. \
+/// The client is an application registered by the user at `https://shikimori.one/oauth/applications`
+#[derive(Clone)]
+pub struct ShikimoriConfig {
+    /// URL of the site without the trailing slash, e.g. of a mirror or a mock of the API
+    pub url: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+impl Default for ShikimoriConfig {
+    fn default() -> Self {
+        Self {
+            url: String::from("https://shikimori.one"),
+            client_id: None,
+            client_secret: None,
+        }
+    }
+}
+
+/// The linked account with its token
+struct Account {
+    user_id: u64,
+    nickname: String,
+    token: Token,
+}
+
+impl Account {
+    fn to_json(&self) -> Value {
+        json!({
+            "user_id": self.user_id,
+            "nickname": self.nickname,
+            "token": self.token.to_json(),
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            user_id: value["user_id"].as_u64()?,
+            nickname: value["nickname"].as_str()?.to_string(),
+            token: Token::from_json(&value["token"])?,
+        })
+    }
+}
+
+/// Tracker of <https://shikimori.one>. \
+/// The account is linked by OAuth, its token is kept in the accounts and refreshed when it expires
+pub struct Shikimori {
+    config: ShikimoriConfig,
+    http: Http,
+    accounts: Rc<RefCell<Accounts>>,
+}
+
+impl Shikimori {
+    #[must_use]
+    pub fn new(config: ShikimoriConfig, http: Http, accounts: Rc<RefCell<Accounts>>) -> Self {
+        Self {
+            config,
+            http,
+            accounts,
+        }
+    }
+
+    /// Get nickname of the linked user, `None` if the account isn't linked
+    #[must_use]
+    pub fn nickname(&self) -> Option<String> {
+        self.account().ok().map(|account| account.nickname)
+    }
+
+    /// Get URL of the page where the user allows access and gets the code for [`Shikimori::link`]
+    pub fn authorize_url(&self) -> Result<String, TrackerError> {
+        oauth::authorize_url(&self.config)
+    }
+
+    /// Link the account by the authorization code from the page of [`Shikimori::authorize_url`]
+    /// # Returns
+    /// Nickname of the user
+    pub async fn link(&self, code: &str) -> Result<String, TrackerError> {
+        let token = oauth::exchange_code(&self.http, &self.config, code.trim()).await?;
+
+        let request = self
+            .http
+            .request(
+                Method::GET,
+                &format!("{}/api/users/whoami", self.config.url),
+            )
+            .bearer_auth(&token.access_token);
+        let user: Value = serde_json::from_str(&self.http.send(request).await?.text().await?)?;
+
+        let account = Account {
+            user_id: user["id"].as_u64().ok_or_else(|| parse_error("id"))?,
+            nickname: user["nickname"].as_str().unwrap_or_default().to_string(),
+            token,
+        };
+        self.save(&account)?;
+
+        Ok(account.nickname)
+    }
+
+    /// Forget the token of the account
+    /// # Returns
+    /// `false` if the account isn't linked
+    pub fn unlink(&self) -> Result<bool, TrackerError> {
+        Ok(self.accounts.borrow_mut().remove(SERVICE)?)
+    }
+
+    fn account(&self) -> Result<Account, TrackerError> {
+        self.accounts
+            .borrow()
+            .get(SERVICE)
+            .and_then(Account::from_json)
+            .ok_or_else(|| TrackerError::NotLinked(Message::TrackerNotLinked(SERVICE).to_string()))
+    }
+
+    fn save(&self, account: &Account) -> Result<(), TrackerError> {
+        Ok(self.accounts.borrow_mut().set(SERVICE, account.to_json())?)
+    }
+
+    /// Get the linked account with a valid token, the expired token is refreshed
+    async fn authorized(&self) -> Result<Account, TrackerError> {
+        let mut account = self.account()?;
+
+        if account.token.is_expired() {
+            account.token = oauth::refresh(&self.http, &self.config, &account.token).await?;
+            self.save(&account)?;
+        }
+
+        Ok(account)
+    }
+
+    /// Send an authorized request to the API
+    /// # Arguments
+    /// * `path` - Path of the endpoint, e.g. `/api/v2/user_rates`
+    async fn api(
+        &self,
+        account: &Account,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&Value>,
+    ) -> Result<Value, TrackerError> {
+        let mut request = self
+            .http
+            .request(method, &format!("{}{path}", self.config.url))
+            .bearer_auth(&account.token.access_token)
+            .query(query);
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        }
+
+        let text = self.http.send(request).await?.text().await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+impl Tracker for Shikimori {
+    fn name(&self) -> &str {
+        "Shikimori"
+    }
+
+    async fn find_anime(&self, names: &Names) -> Result<Option<TrackedAnime>, TrackerError> {
+        let mut queries = vec![names.en.trim(), names.ru.trim()];
+        queries.dedup();
+
+        for query in queries.into_iter().filter(|query| !query.is_empty()) {
+            let text = self
+                .http
+                .get_text(
+                    &format!("{}/api/animes", self.config.url),
+                    &[("search", query), ("limit", "10")],
+                )
+                .await?;
+            let found: Value = serde_json::from_str(&text)?;
+
+            for anime in found.as_array().into_iter().flatten() {
+                let anime = parse_anime(anime)?;
+                if is_same(names, &anime.names) {
+                    return Ok(Some(anime));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn update_progress(&self, anime_id: u64, episodes: u32) -> Result<bool, TrackerError> {
+        let account = self.authorized().await?;
+
+        let rates = self
+            .api(
+                &account,
+                Method::GET,
+                "/api/v2/user_rates",
+                &[
+                    ("user_id", account.user_id.to_string()),
+                    ("target_id", anime_id.to_string()),
+                    ("target_type", String::from("Anime")),
+                ],
+                None,
+            )
+            .await?;
+
+        match rates.as_array().and_then(|rates| rates.first()) {
+            Some(rate) => {
+                if rate["episodes"].as_u64().unwrap_or_default() >= u64::from(episodes) {
+                    return Ok(false);
+                }
+
+                let rate_id = rate["id"].as_u64().ok_or_else(|| parse_error("id"))?;
+                let mut user_rate = json!({ "episodes": episodes });
+                // Planned anime are being watched now
+                if rate["status"] == "planned" {
+                    user_rate["status"] = json!("watching");
+                }
+
+                self.api(
+                    &account,
+                    Method::PATCH,
+                    &format!("/api/v2/user_rates/{rate_id}"),
+                    &[],
+                    Some(&json!({ "user_rate": user_rate })),
+                )
+                .await?;
+            }
+            None => {
+                let body = json!({
+                    "user_rate": {
+                        "user_id": account.user_id,
+                        "target_id": anime_id,
+                        "target_type": "Anime",
+                        "status": "watching",
+                        "episodes": episodes,
+                    }
+                });
+
+                self.api(
+                    &account,
+                    Method::POST,
+                    "/api/v2/user_rates",
+                    &[],
+                    Some(&body),
+                )
+                .await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn watching(&self) -> Result<Vec<TrackedAnime>, TrackerError> {
+        let account = self.authorized().await?;
+
+        let rates = self
+            .api(
+                &account,
+                Method::GET,
+                &format!("/api/users/{}/anime_rates", account.user_id),
+                &[
+                    ("status", String::from("watching")),
+                    ("limit", String::from("5000")),
+                ],
+                None,
+            )
+            .await?;
+
+        rates
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|rate| parse_anime(&rate["anime"]))
+            .collect()
+    }
+}
+
+/// Parse an anime of the API, e.g. `{ "id": 9756, "name": "Mahou Shoujo Madoka★Magica", "russian": "..." }`
+fn parse_anime(value: &Value) -> Result<TrackedAnime, TrackerError> {
+    Ok(TrackedAnime {
+        id: value["id"].as_u64().ok_or_else(|| parse_error("id"))?,
+        names: Names {
+            ru: value["russian"].as_str().unwrap_or_default().to_string(),
+            en: value["name"].as_str().unwrap_or_default().to_string(),
+        },
+    })
+}
+
+fn parse_error(key: &str) -> TrackerError {
+    TrackerError::ParseError(Message::ParseError(&format!("missing `{key}`")).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{http, MockServer, Request};
+
+    fn shikimori(server: &MockServer) -> Shikimori {
+        let config = ShikimoriConfig {
+            url: server.url().to_string(),
+            client_id: Some(String::from("client")),
+            client_secret: Some(String::from("secret")),
+        };
+
+        Shikimori::new(config, http(), Rc::new(RefCell::new(Accounts::new(None))))
+    }
+
+    /// Link the account with the token expiring at the time
+    fn link(shikimori: &Shikimori, expires_at: u64) {
+        let account = Account {
+            user_id: 42,
+            nickname: String::from("homura"),
+            token: Token {
+                access_token: String::from("access"),
+                refresh_token: String::from("refresh"),
+                expires_at,
+            },
+        };
+        shikimori.save(&account).unwrap();
+    }
+
+    fn authorization(request: &Request) -> &str {
+        request
+            .headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    }
+
+    fn token(access_token: &str) -> (u16, String) {
+        let token = json!({
+            "access_token": access_token,
+            "refresh_token": format!("{access_token}-refresh"),
+            "expires_in": 86400,
+        });
+        (200, token.to_string())
+    }
+
+    #[tokio::test]
+    async fn account_is_linked_by_authorization_code() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/oauth/token" => token("linked"),
+            "/api/users/whoami" if authorization(request) == "Bearer linked" => {
+                (200, json!({ "id": 42, "nickname": "homura" }).to_string())
+            }
+            _ => (401, String::new()),
+        });
+        let shikimori = shikimori(&server);
+
+        let authorize_url = shikimori.authorize_url().unwrap();
+        assert!(
+            authorize_url.starts_with(&format!(
+                "{}/oauth/authorize?client_id=client&",
+                server.url()
+            )),
+            "{authorize_url}"
+        );
+        assert_eq!(shikimori.link("code\n").await.unwrap(), "homura");
+
+        assert_eq!(shikimori.nickname().as_deref(), Some("homura"));
+        let account = shikimori.account().unwrap();
+        assert_eq!(account.user_id, 42);
+        assert_eq!(account.token.access_token, "linked");
+        assert!(!account.token.is_expired());
+
+        let requests = server.requests();
+        assert_eq!(requests[0].to_string(), "POST /oauth/token");
+        assert_eq!(
+            requests[0].body,
+            "client_id=client&client_secret=secret&grant_type=authorization_code&code=code\
+             &redirect_uri=urn%3Aietf%3Awg%3Aoauth%3A2.0%3Aoob"
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_token_is_refreshed_before_request() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/oauth/token" => token("refreshed"),
+            _ if authorization(request) != "Bearer refreshed" => (401, String::new()),
+            path if path.starts_with("/api/users/42/anime_rates?") => (200, String::from("[]")),
+            _ => (404, String::new()),
+        });
+        let shikimori = shikimori(&server);
+        link(&shikimori, 0);
+
+        assert!(shikimori.watching().await.unwrap().is_empty());
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].body,
+            "client_id=client&client_secret=secret&grant_type=refresh_token&refresh_token=refresh"
+        );
+        assert_eq!(
+            requests[1].to_string(),
+            "GET /api/users/42/anime_rates?status=watching&limit=5000"
+        );
+        // The refreshed token is kept for next runs
+        let token = shikimori.account().unwrap().token;
+        assert_eq!(token.access_token, "refreshed");
+        assert_eq!(token.refresh_token, "refreshed-refresh");
+    }
+
+    #[tokio::test]
+    async fn unlinked_account_is_reported() {
+        let server = MockServer::start(|_| (500, String::new()));
+        let shikimori = shikimori(&server);
+
+        let err = shikimori.update_progress(9756, 1).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            Message::TrackerNotLinked(SERVICE).to_string()
+        );
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn progress_is_only_increased() {
+        // The anime is planned with 3 watched episodes
+        let server = MockServer::start(|request| match request.method {
+            Method::GET => (
+                200,
+                json!([{ "id": 7, "episodes": 3, "status": "planned" }]).to_string(),
+            ),
+            _ => (200, String::from("{}")),
+        });
+        let shikimori = shikimori(&server);
+        link(&shikimori, u64::MAX);
+
+        assert!(!shikimori.update_progress(9756, 3).await.unwrap());
+        assert!(shikimori.update_progress(9756, 5).await.unwrap());
+
+        let requests = server.requests();
+        assert_eq!(
+            server.log(),
+            [
+                "GET /api/v2/user_rates?user_id=42&target_id=9756&target_type=Anime",
+                "GET /api/v2/user_rates?user_id=42&target_id=9756&target_type=Anime",
+                "PATCH /api/v2/user_rates/7",
+            ]
+        );
+        assert_eq!(authorization(&requests[2]), "Bearer access");
+        assert_eq!(
+            serde_json::from_str::<Value>(&requests[2].body).unwrap(),
+            json!({ "user_rate": { "episodes": 5, "status": "watching" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn anime_is_added_to_watching_list_with_progress() {
+        let server = MockServer::start(|request| match request.method {
+            Method::GET => (200, String::from("[]")),
+            _ => (201, String::from("{}")),
+        });
+        let shikimori = shikimori(&server);
+        link(&shikimori, u64::MAX);
+
+        assert!(shikimori.update_progress(9756, 1).await.unwrap());
+
+        let requests = server.requests();
+        assert_eq!(requests[1].to_string(), "POST /api/v2/user_rates");
+        assert_eq!(
+            serde_json::from_str::<Value>(&requests[1].body).unwrap(),
+            json!({
+                "user_rate": {
+                    "user_id": 42,
+                    "target_id": 9756,
+                    "target_type": "Anime",
+                    "status": "watching",
+                    "episodes": 1,
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn anime_is_found_by_names() {
+        let server = MockServer::start(|request| {
+            let found = if request.path.contains("search=Mahou") {
+                json!([
+                    { "id": 11981, "name": "Mahou Shoujo Madoka★Magica Movie 3: Hangyaku no Monogatari", "russian": "Девочка-волшебница Мадока★Магика. Фильм 3: Восстание" },
+                    { "id": 9756, "name": "Mahou Shoujo Madoka★Magica", "russian": "Девочка-волшебница Мадока★Магика" },
+                ])
+            } else {
+                json!([])
+            };
+            (200, found.to_string())
+        });
+        let shikimori = shikimori(&server);
+        let names = Names {
+            ru: String::from("Девочка-волшебница Мадока Магика"),
+            en: String::from("Mahou Shoujo Madoka Magica"),
+        };
+
+        let anime = shikimori.find_anime(&names).await.unwrap().unwrap();
+
+        assert_eq!(anime.id, 9756);
+        assert_eq!(anime.names.ru, "Девочка-волшебница Мадока★Магика");
+    }
+}
@@ -0,0 +1,148 @@
+use super::ShikimoriConfig;
+
+use crate::{errors::TrackerError, locale::Message, sources::http::Http};
+
+use reqwest::{Method, Url};
+use serde_json::{json, Value};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Redirect URI of applications without a server, the authorization code is shown to the user to copy it
+pub const REDIRECT_URI: &str = "urn:ietf:wg:oauth:2.0:oob";
+
+/// Access to the API of the linked account
+#[derive(Clone)]
+pub struct Token {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Unix time when the access token expires
+    pub expires_at: u64,
+}
+
+impl Token {
+    /// Check the access token is expired or expires within a minute,
+    /// so it should be refreshed before a request
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        now().saturating_add(60) >= self.expires_at
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "access_token": self.access_token,
+            "refresh_token": self.refresh_token,
+            "expires_at": self.expires_at,
+        })
+    }
+
+    /// Parse the token saved by [`Token::to_json`], `None` if it's invalid
+    #[must_use]
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            access_token: value["access_token"].as_str()?.to_string(),
+            refresh_token: value["refresh_token"].as_str()?.to_string(),
+            expires_at: value["expires_at"].as_u64()?,
+        })
+    }
+}
+
+/// Get URL of the page where the user allows access to the account and gets the authorization code
+pub fn authorize_url(config: &ShikimoriConfig) -> Result<String, TrackerError> {
+    let (client_id, _) = credentials(config)?;
+
+    let url = Url::parse_with_params(
+        &format!("{}/oauth/authorize", config.url),
+        [
+            ("client_id", client_id),
+            ("redirect_uri", REDIRECT_URI),
+            ("response_type", "code"),
+            ("scope", "user_rates"),
+        ],
+    )
+    .map_err(|err| TrackerError::NotConfigured(format!("`shikimori.url`: {err}")))?;
+
+    Ok(url.to_string())
+}
+
+/// Exchange the authorization code from the page of [`authorize_url`] for a token
+pub async fn exchange_code(
+    http: &Http,
+    config: &ShikimoriConfig,
+    code: &str,
+) -> Result<Token, TrackerError> {
+    request_token(
+        http,
+        config,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", REDIRECT_URI),
+        ],
+    )
+    .await
+}
+
+/// Get a new token by the refresh token of the expired one
+pub async fn refresh(
+    http: &Http,
+    config: &ShikimoriConfig,
+    token: &Token,
+) -> Result<Token, TrackerError> {
+    request_token(
+        http,
+        config,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &token.refresh_token),
+        ],
+    )
+    .await
+}
+
+async fn request_token(
+    http: &Http,
+    config: &ShikimoriConfig,
+    params: &[(&str, &str)],
+) -> Result<Token, TrackerError> {
+    let (client_id, client_secret) = credentials(config)?;
+
+    let mut form = vec![("client_id", client_id), ("client_secret", client_secret)];
+    form.extend_from_slice(params);
+
+    let request = http
+        .request(Method::POST, &format!("{}/oauth/token", config.url))
+        .form(&form);
+    let response: Value = serde_json::from_str(&http.send(request).await?.text().await?)?;
+
+    let expires_in = response["expires_in"].as_u64().unwrap_or_default();
+    match (
+        response["access_token"].as_str(),
+        response["refresh_token"].as_str(),
+    ) {
+        (Some(access_token), Some(refresh_token)) => Ok(Token {
+            access_token: access_token.to_string(),
+            refresh_token: refresh_token.to_string(),
+            expires_at: now().saturating_add(expires_in),
+        }),
+        _ => Err(TrackerError::ParseError(
+            Message::ParseError(&"missing `access_token`").to_string(),
+        )),
+    }
+}
+
+fn credentials(config: &ShikimoriConfig) -> Result<(&str, &str), TrackerError> {
+    match (&config.client_id, &config.client_secret) {
+        (Some(client_id), Some(client_secret)) => Ok((client_id, client_secret)),
+        _ => Err(TrackerError::NotConfigured(
+            Message::TrackerNotConfigured(super::SERVICE).to_string(),
+        )),
+    }
+}
+
+/// Get current Unix time in seconds
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
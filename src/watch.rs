@@ -0,0 +1,43 @@
+pub mod clock;
+pub mod hook;
+mod watcher;
+
+pub use watcher::{Report, Watcher};
+
+use crate::locale::Message;
+
+use hook::Hook;
+use std::time::Duration;
+
+/// New episodes of an anime of the watchlist
+pub struct NewEpisodes {
+    pub source: String,
+    pub anime: String,
+    pub episodes: Vec<String>,
+}
+
+impl NewEpisodes {
+    /// Describe the episodes in the current locale
+    #[must_use]
+    pub fn message(&self) -> String {
+        Message::NewEpisodes(&self.anime, &self.episodes.join(", ")).to_string()
+    }
+}
+
+/// Settings of the `watch` command
+#[derive(Clone)]
+pub struct WatchConfig {
+    /// Time between checks of the watchlist
+    pub interval: Duration,
+    /// Hooks called for new episodes, new episodes are only printed without hooks
+    pub hooks: Vec<Hook>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60 * 60),
+            hooks: Vec::new(),
+        }
+    }
+}
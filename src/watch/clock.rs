@@ -0,0 +1,30 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time;
+
+/// Time of the watcher, so checks can be driven by a fake clock
+// The futures don't need to be `Send`, the watcher runs on a single thread
+#[allow(async_fn_in_trait)]
+pub trait Clock {
+    /// Get Unix time in seconds
+    fn now(&self) -> u64;
+
+    /// Wait until the duration passes
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Time of the system
+#[derive(Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        time::sleep(duration).await;
+    }
+}
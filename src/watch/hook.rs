@@ -0,0 +1,85 @@
+use super::NewEpisodes;
+
+use crate::{errors::HookError, locale::Message, sources::http::Http};
+
+use serde_json::json;
+use std::{
+    fmt::{self, Display},
+    process::Stdio,
+};
+use tokio::process::Command;
+
+/// Way to notify about new episodes
+#[derive(Clone, Debug)]
+pub enum Hook {
+    /// Program with arguments, e.g. `["notify-send", "{anime}", "{message}"]`. \
+    /// Placeholders `{source}`, `{anime}`, `{episodes}` and `{message}` are replaced in arguments,
+    /// the values are also passed to scripts in `ANI_SOURCE`, `ANI_ANIME`, `ANI_EPISODES` and `ANI_MESSAGE`
+    Command(Vec<String>),
+    /// URL the notification is posted to as a JSON object with the same keys
+    Webhook(String),
+}
+
+impl Hook {
+    /// Notify about the new episodes
+    pub async fn notify(&self, http: &Http, new: &NewEpisodes) -> Result<(), HookError> {
+        let episodes = new.episodes.join(", ");
+        let message = new.message();
+
+        match self {
+            Self::Command(command) => {
+                let Some((program, args)) = command.split_first() else {
+                    return Ok(());
+                };
+                let args = args.iter().map(|arg| {
+                    arg.replace("{source}", &new.source)
+                        .replace("{anime}", &new.anime)
+                        .replace("{episodes}", &episodes)
+                        .replace("{message}", &message)
+                });
+
+                let status = Command::new(program)
+                    .args(args)
+                    .env("ANI_SOURCE", &new.source)
+                    .env("ANI_ANIME", &new.anime)
+                    .env("ANI_EPISODES", &episodes)
+                    .env("ANI_MESSAGE", &message)
+                    .stdin(Stdio::null())
+                    .status()
+                    .await
+                    .map_err(|err| HookError::CommandError(err.to_string()))?;
+
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(HookError::CommandError(
+                        Message::HookExited(&status).to_string(),
+                    ))
+                }
+            }
+            Self::Webhook(url) => {
+                let body = json!({
+                    "source": new.source,
+                    "anime": new.anime,
+                    "episodes": new.episodes,
+                    "message": message,
+                });
+
+                http.post_json(url, &body)
+                    .await
+                    .map(|_| ())
+                    .map_err(|err| HookError::WebhookError(err.to_string()))
+            }
+        }
+    }
+}
+
+/// Display the program or the URL of the hook
+impl Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Command(command) => write!(f, "{}", command.first().map_or("", String::as_str)),
+            Self::Webhook(url) => write!(f, "{url}"),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use super::{clock::Clock, hook::Hook, NewEpisodes};
+
+use crate::{
+    errors::SourceError,
+    locale::Message,
+    sources::{base::Source, http::Http, search::find_anime},
+    storage::{
+        seen::SeenEpisodes,
+        watchlist::{Entry, Watchlist},
+    },
+};
+
+use std::{future::Future, io, time::Duration};
+
+/// Result of a check of the watchlist
+#[derive(Default)]
+pub struct Report {
+    pub new_episodes: Vec<NewEpisodes>,
+    /// Descriptions of anime that can't be checked and hooks that failed
+    pub errors: Vec<String>,
+}
+
+/// Checker of new episodes of anime of the watchlist. \
+/// Episodes found at the first check of an anime are only remembered,
+/// later checks report episodes that weren't seen before
+pub struct Watcher<'a, S, C> {
+    sources: &'a [S],
+    seen: SeenEpisodes,
+    clock: C,
+    hooks: Vec<Hook>,
+    http: Http,
+}
+
+impl<'a, S, C> Watcher<'a, S, C>
+where
+    S: Source,
+    C: Clock,
+{
+    #[must_use]
+    pub fn new(sources: &'a [S], seen: SeenEpisodes, clock: C) -> Self {
+        Self {
+            sources,
+            seen,
+            clock,
+            hooks: Vec::new(),
+            http: Http::default(),
+        }
+    }
+
+    /// Set hooks called for new episodes
+    /// # Arguments
+    /// * `http` - Client of webhooks
+    #[must_use]
+    pub fn with_hooks(mut self, hooks: Vec<Hook>, http: Http) -> Self {
+        self.hooks = hooks;
+        self.http = http;
+        self
+    }
+
+    /// Check every anime of the watchlist once and call hooks for new episodes
+    pub async fn check(&mut self, watchlist: &[Entry]) -> Report {
+        let mut report = Report::default();
+
+        for entry in watchlist {
+            let new = match self.check_anime(entry).await {
+                Ok(Some(new)) => new,
+                Ok(None) => continue,
+                Err(err) => {
+                    report
+                        .errors
+                        .push(Message::CantCheckAnime(&entry.anime, &err).to_string());
+                    continue;
+                }
+            };
+
+            for hook in &self.hooks {
+                if let Err(err) = hook.notify(&self.http, &new).await {
+                    report
+                        .errors
+                        .push(Message::HookFailed(hook, &err).to_string());
+                }
+            }
+            report.new_episodes.push(new);
+        }
+
+        report
+    }
+
+    /// Check the watchlist until `stop` completes
+    /// # Arguments
+    /// * `watchlist` - Load the watchlist, it's loaded before each check to see changes of other front-ends
+    /// * `on_report` - Called after each check
+    pub async fn run<L, R>(
+        &mut self,
+        interval: Duration,
+        mut watchlist: L,
+        mut on_report: R,
+        stop: impl Future<Output = ()>,
+    ) where
+        L: FnMut() -> io::Result<Watchlist>,
+        R: FnMut(Report),
+    {
+        tokio::pin!(stop);
+
+        loop {
+            let report = match watchlist() {
+                Ok(watchlist) => self.check(watchlist.entries()).await,
+                Err(err) => Report {
+                    new_episodes: Vec::new(),
+                    errors: vec![Message::CantLoadWatchlist(&err).to_string()],
+                },
+            };
+            on_report(report);
+
+            tokio::select! {
+                () = self.clock.sleep(interval) => {}
+                () = &mut stop => return,
+            }
+        }
+    }
+
+    /// # Returns
+    /// `None` if the anime is checked the first time or there are no new episodes
+    async fn check_anime(&mut self, entry: &Entry) -> Result<Option<NewEpisodes>, SourceError> {
+        let mut source = self
+            .sources
+            .iter()
+            .find(|source| **source == entry.source)
+            .cloned()
+            .ok_or_else(|| {
+                SourceError::UnknownVariant(Message::UnknownSource(&entry.source).to_string())
+            })?;
+
+        find_anime(&mut source, &entry.anime).await?;
+        let episodes = source.episodes()?;
+
+        let new = self
+            .seen
+            .episodes(&entry.source, &entry.anime)
+            .map(|known| {
+                episodes
+                    .iter()
+                    .filter(|episode| !known.contains(episode))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        // The state is saved before hooks are called, so a restart doesn't repeat notifications
+        self.seen
+            .update(&entry.source, &entry.anime, episodes, self.clock.now())
+            .map_err(|err| {
+                SourceError::ParseError(Message::CantSaveSeenEpisodes(&err).to_string())
+            })?;
+
+        Ok((!new.is_empty()).then(|| NewEpisodes {
+            source: entry.source.clone(),
+            anime: entry.anime.clone(),
+            episodes: new,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_dir, MockSource};
+
+    use serde_json::Value;
+    use std::{
+        cell::{Cell, RefCell},
+        fs,
+        rc::Rc,
+    };
+    use tokio::{sync::oneshot, task};
+
+    /// Clock whose time passes at once when the watcher sleeps
+    #[derive(Clone, Default)]
+    struct FakeClock {
+        now: Rc<Cell<u64>>,
+        sleeps: Rc<RefCell<Vec<Duration>>>,
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> u64 {
+            self.now.get()
+        }
+
+        /// Only finished sleeps pass the time
+        async fn sleep(&self, duration: Duration) {
+            // The watcher is stopped if it's requested during the sleep
+            task::yield_now().await;
+            self.sleeps.borrow_mut().push(duration);
+            self.now.set(self.now.get() + duration.as_secs());
+        }
+    }
+
+    #[tokio::test]
+    async fn new_episodes_are_reported_after_first_check() {
+        const INTERVAL: Duration = Duration::from_secs(60 * 60);
+        const STARTED_AT: u64 = 1_700_000_000;
+
+        let sources =
+            [MockSource::new("Mock", "http://stream.test").with_anime("Madoka", &["1", "2"])];
+        let seen_path = temp_dir("watcher").join("seen.json");
+        let clock = FakeClock::default();
+        clock.now.set(STARTED_AT);
+        let mut watcher = Watcher::new(
+            &sources,
+            SeenEpisodes::new(Some(seen_path.clone())),
+            clock.clone(),
+        );
+
+        let watchlist = || {
+            let mut watchlist = Watchlist::new(None);
+            watchlist.add("Mock", "Madoka")?;
+            watchlist.add("Mock", "Bakemonogatari")?;
+            watchlist.add("Other", "Madoka")?;
+            Ok(watchlist)
+        };
+        let mut reports = Vec::new();
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let mut stop_tx = Some(stop_tx);
+        let on_report = |report: Report| {
+            reports.push(report);
+            match reports.len() {
+                1 => sources[0].add_episode("Madoka", "3"),
+                // Nothing is released before the third check
+                2 => {}
+                3 => {
+                    sources[0].add_episode("Madoka", "4");
+                    sources[0].add_episode("Madoka", "5");
+                }
+                _ => {
+                    let _ = stop_tx.take().map(|stop_tx| stop_tx.send(()));
+                }
+            }
+        };
+        let stop = async {
+            let _ = stop_rx.await;
+        };
+
+        watcher.run(INTERVAL, watchlist, on_report, stop).await;
+
+        let new_episodes = reports
+            .iter()
+            .map(|report| {
+                report
+                    .new_episodes
+                    .iter()
+                    .map(|new| (new.anime.as_str(), new.episodes.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            new_episodes,
+            [
+                vec![],
+                vec![("Madoka", vec![String::from("3")])],
+                vec![],
+                vec![("Madoka", vec![String::from("4"), String::from("5")])],
+            ]
+        );
+        for report in &reports {
+            assert_eq!(report.errors.len(), 2);
+        }
+        assert_eq!(
+            reports[0].errors[1],
+            Message::CantCheckAnime(
+                "Madoka",
+                &SourceError::UnknownVariant(Message::UnknownSource("Other").to_string())
+            )
+            .to_string()
+        );
+        assert_eq!(*clock.sleeps.borrow(), [INTERVAL; 3]);
+
+        // Seen episodes are saved with the time of the last check
+        let seen: Value = serde_json::from_str(&fs::read_to_string(seen_path).unwrap()).unwrap();
+        assert_eq!(
+            seen[0]["episodes"],
+            serde_json::json!(["1", "2", "3", "4", "5"])
+        );
+        assert_eq!(seen[0]["checked_at"], STARTED_AT + 3 * INTERVAL.as_secs());
+    }
+}